@@ -0,0 +1,97 @@
+//! Validating form submissions on the server with Axum
+//!
+//! The `ValidatedForm` extractor decodes JSON, URL-encoded or multipart
+//! bodies into a `Form` and runs the same validation the browser runs.
+//! Invalid submissions are answered with `422 Unprocessable Entity` and a
+//! problem body that `HttpSubmitter` shows on the form field by field.
+//!
+//! ```sh
+//! cargo run -p axum-validation-example
+//! curl -d 'name=&email=ada' http://127.0.0.1:3000/signup
+//! ```
+
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use leptos_forms_rs::core::FormValue;
+use leptos_forms_rs::submission::validate_submission;
+use leptos_forms_rs::{Form, ValidationErrors};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Signup {
+    #[form(required)]
+    name: String,
+    #[form(required, email)]
+    email: String,
+    newsletter: bool,
+}
+
+/// A submitted form that decoded and passed validation
+struct ValidatedForm<T>(T);
+
+/// Errors of a submission that did not pass validation
+struct InvalidForm(ValidationErrors);
+
+impl<S, T> FromRequest<S> for ValidatedForm<T>
+where
+    S: Send + Sync,
+    T: Form + FormValue + Send,
+{
+    type Rejection = InvalidForm;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let content_type = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = Bytes::from_request(request, state).await.map_err(|e| {
+            let mut errors = ValidationErrors::new();
+            errors.add_form_error(e.body_text());
+            InvalidForm(errors)
+        })?;
+
+        validate_submission(content_type.as_deref(), &body)
+            .map(ValidatedForm)
+            .map_err(InvalidForm)
+    }
+}
+
+impl IntoResponse for InvalidForm {
+    fn into_response(self) -> Response {
+        let InvalidForm(errors) = self;
+        let mut problem = json!({
+            "title": "The submission is not valid",
+            "status": 422,
+            "errors": errors.field_errors,
+        });
+        if !errors.form_errors.is_empty() {
+            problem["detail"] = errors.form_errors.join(" ").into();
+        }
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            problem.to_string(),
+        )
+            .into_response()
+    }
+}
+
+async fn signup(ValidatedForm(signup): ValidatedForm<Signup>) -> Json<serde_json::Value> {
+    Json(json!({ "welcome": signup.name, "newsletter": signup.newsletter }))
+}
+
+#[tokio::main]
+async fn main() {
+    let app = Router::new().route("/signup", post(signup));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+        .await
+        .expect("port 3000 is free");
+    println!("Listening on http://127.0.0.1:3000");
+    axum::serve(listener, app).await.expect("server runs");
+}
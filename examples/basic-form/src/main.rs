@@ -29,6 +29,7 @@ impl Form for LoginForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "password".to_string(),
@@ -38,6 +39,7 @@ impl Form for LoginForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "remember_me".to_string(),
@@ -47,6 +49,7 @@ impl Form for LoginForm {
                 default_value: Some(FieldValue::Boolean(true)),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -48,6 +48,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "last_name".to_string(),
@@ -57,6 +58,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -66,6 +68,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "password".to_string(),
@@ -75,6 +78,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "confirm_password".to_string(),
@@ -84,6 +88,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "phone".to_string(),
@@ -93,6 +98,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "website".to_string(),
@@ -102,6 +108,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "street_address".to_string(),
@@ -111,6 +118,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "city".to_string(),
@@ -120,6 +128,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "state".to_string(),
@@ -129,6 +138,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "postal_code".to_string(),
@@ -138,6 +148,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "country".to_string(),
@@ -147,6 +158,7 @@ impl Form for UserRegistrationForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "newsletter".to_string(),
@@ -156,6 +168,7 @@ impl Form for UserRegistrationForm {
                 default_value: Some(FieldValue::Boolean(false)),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "marketing_emails".to_string(),
@@ -165,6 +178,7 @@ impl Form for UserRegistrationForm {
                 default_value: Some(FieldValue::Boolean(false)),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "language".to_string(),
@@ -174,6 +188,7 @@ impl Form for UserRegistrationForm {
                 default_value: Some(FieldValue::String("en".to_string())),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
         ]
     }
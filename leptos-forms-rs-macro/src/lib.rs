@@ -1,344 +1,902 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, Meta, Type};
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Expr, Field, Fields, Generics,
+    Ident, LitStr, Path, Token, Type, WherePredicate,
+};
 
 /// Derive macro for implementing the Form trait
+///
+/// Every field type must implement `FormValue`. Fields whose type also derives
+/// `Form` are described as `FieldType::Nested`, and `Vec`s of them as
+/// `FieldType::Array(Nested)`, with their metadata and validation recursing
+/// into the nested form.
+///
+/// Custom validation functions are attached with `#[form(validate_with = path)]`
+/// on a field, taking a reference to the field's own type, and with
+/// `#[form(validate = Self::check)]` on the struct for cross-field checks.
+///
+/// Presentation hints for `AutoForm` are set with `label`, `placeholder`,
+/// `help_text`, `group`, `order = n`, `hidden` and `readonly` and end up in the
+/// field's `attributes`. `visible_when = path` and `enabled_when = path` name
+/// functions returning a `FieldCondition` on the struct's fields; hidden fields
+/// are not validated.
+///
+/// `#[form(computed = path)]` makes a field read-only and derived from the
+/// whole form by a `fn(&Self) -> FieldValue`, recomputed by `FormHandle` when a
+/// `depends_on = "field"` input changes. `include_in_payload = false` leaves it
+/// out of the submitted data.
+///
+/// `#[form(sensitive)]` marks a field, like an SSN, whose value must stay out
+/// of persisted drafts, analytics and debug output. Password fields are
+/// sensitive without it.
+///
+/// Field names follow serde: `rename`, `rename_all`, `skip` and `default`, on
+/// fields and on the struct, are honoured so that metadata, error keys and the
+/// JSON payload agree. Fields serde does not read back, with `skip` or
+/// `skip_deserializing`, are not form fields and take the defaults serde gives
+/// them. Fields with only `skip_serializing` are still read back, so they
+/// stay form fields, but are left out of JSON payloads.
+/// `#[form(rename = "...")]` must name the field as serde does; rename
+/// fields with `#[serde(rename = "...")]` instead.
+///
+/// Generic structs are supported: field types that mention a type parameter
+/// get a `FormValue` bound inferred on the generated impls.
 #[proc_macro_derive(Form, attributes(form))]
 pub fn derive_form(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    match expand_form(input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(error) => TokenStream::from(error.to_compile_error()),
+    }
+}
+
+fn expand_form(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = input.ident;
-    let generics = input.generics;
+    let struct_attrs = StructAttributes::from_attributes(&name, &input.attrs)?;
 
     // Parse the struct fields and their attributes
     let fields = match input.data {
-        Data::Struct(data) => data.fields,
-        _ => panic!("Form derive macro only supports structs"),
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "Form derive macro only supports structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "Form derive macro only supports structs",
+            ))
+        }
     };
 
-    let field_metadata = generate_field_metadata(&fields);
+    let (skipped, fields): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .map(|field| FormField::from_field(field, &struct_attrs))
+        .collect::<syn::Result<Vec<_>>>()?
+        .into_iter()
+        .partition(|field| field.skip);
+
+    let field_metadata = generate_field_metadata(&fields, &struct_attrs);
     let field_accessors = generate_field_accessors(&fields);
     let field_setters = generate_field_setters(&fields);
-    let default_values = generate_default_values(&fields);
-    let validation_impl = generate_validation_impl(&fields);
+    let default_values = generate_default_values(&fields, &skipped, &struct_attrs);
+    let validation_impl = generate_validation_impl(&fields, &struct_attrs);
+    let object_setters = generate_object_setters(&fields);
+    let computed_fields = generate_computed_fields(&fields);
+
+    let generics = generate_bounds(&input.generics, &fields, &skipped, &struct_attrs);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let expanded = quote! {
-        impl #generics leptos_forms_rs::core::traits::Form for #name #generics {
-            fn field_metadata() -> Vec<leptos_forms_rs::core::traits::FieldMetadata> {
+        impl #impl_generics ::leptos_forms_rs::core::Form for #name #ty_generics #where_clause {
+            fn field_metadata() -> Vec<::leptos_forms_rs::core::FieldMetadata> {
                 vec![#field_metadata]
             }
 
-            fn get_field(&self, name: &str) -> Option<leptos_forms_rs::core::types::FieldValue> {
-                match name {
-                    #field_accessors
-                    _ => None,
+            fn validate(&self) -> Result<(), ::leptos_forms_rs::validation::ValidationErrors> {
+                let mut errors = ::leptos_forms_rs::validation::ValidationErrors::new();
+                ::leptos_forms_rs::core::FormValue::validate_at(self, "", &mut errors);
+
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(errors)
                 }
             }
 
-            fn set_field(&mut self, name: &str, value: leptos_forms_rs::core::types::FieldValue) -> Result<(), leptos_forms_rs::core::types::FieldError> {
-                match name {
-                    #field_setters
-                    _ => Err(leptos_forms_rs::core::types::FieldError::new(
-                        name.to_string(),
-                        "Unknown field".to_string()
-                    )),
+            fn default_values() -> Self {
+                #default_values
+            }
+
+            #computed_fields
+
+            fn get_field_value(&self, field_name: &str) -> ::leptos_forms_rs::core::FieldValue {
+                match field_name {
+                    #field_accessors
+                    _ => ::leptos_forms_rs::core::FieldValue::Null,
                 }
             }
 
-            fn default_values() -> Self {
-                Self {
-                    #default_values
+            fn set_field_value(&mut self, field_name: &str, value: ::leptos_forms_rs::core::FieldValue) {
+                match field_name {
+                    #field_setters
+                    _ => {}
                 }
             }
+        }
 
-            fn validate(&self) -> Result<(), leptos_forms_rs::validation::ValidationErrors> {
-    let mut errors = leptos_forms_rs::validation::ValidationErrors::new();
+        impl #impl_generics ::leptos_forms_rs::core::FormValue for #name #ty_generics #where_clause {
+            fn field_type() -> ::leptos_forms_rs::core::FieldType {
+                ::leptos_forms_rs::core::FieldType::Nested(stringify!(#name).to_string())
+            }
 
-                #validation_impl
+            fn nested_fields() -> Vec<::leptos_forms_rs::core::FieldMetadata> {
+                <Self as ::leptos_forms_rs::core::Form>::field_metadata()
+            }
 
-                if errors.is_empty() {
-                    Ok(())
-                } else {
-                    Err(errors)
-                }
+            fn default_value() -> Self {
+                <Self as ::leptos_forms_rs::core::Form>::default_values()
             }
 
-            fn schema() -> leptos_forms_rs::core::traits::FormSchema {
-    let mut schema = leptos_forms_rs::core::traits::FormSchema::new();
+            fn to_field_value(&self) -> ::leptos_forms_rs::core::FieldValue {
+                ::leptos_forms_rs::core::FieldValue::Object(
+                    ::leptos_forms_rs::core::Form::get_form_data(self),
+                )
+            }
 
-                for field in Self::field_metadata() {
-                    schema.add_field(field);
+            fn from_field_value(value: ::leptos_forms_rs::core::FieldValue) -> Result<Self, String> {
+                match value {
+                    ::leptos_forms_rs::core::FieldValue::Object(mut object) => {
+                        let mut form = <Self as ::leptos_forms_rs::core::Form>::default_values();
+                        #object_setters
+                        Ok(form)
+                    }
+                    other => Err(format!(
+                        "Expected an object for {}, got {:?}",
+                        stringify!(#name),
+                        other
+                    )),
                 }
+            }
 
-                schema
+            fn validate_at(&self, path: &str, errors: &mut ::leptos_forms_rs::validation::ValidationErrors) {
+                #validation_impl
             }
         }
     };
 
-    TokenStream::from(expanded)
+    Ok(expanded)
 }
 
-/// Generate field metadata from struct fields
-fn generate_field_metadata(fields: &Fields) -> proc_macro2::TokenStream {
-    let field_metadata: Vec<proc_macro2::TokenStream> = fields
-        .iter()
-        .map(|field| {
-            let field_name = &field.ident;
-            let _field_type = determine_field_type(&field.ty);
-            let validators = extract_validators(&field.attrs);
-            let is_required = has_validator(&field.attrs, "required");
-            let default_value = extract_default_value(&field.attrs, &field.ty);
-
-            quote! {
-                leptos_forms_rs::core::traits::FieldMetadata {
-                    name: stringify!(#field_name).to_string(),
-                    field_type: #_field_type,
-                    validators: vec![#validators],
-                    is_required: #is_required,
-                    default_value: #default_value,
-                    dependencies: vec![],
-                    attributes: std::collections::HashMap::new(),
-                }
-            }
-        })
+/// Add the bounds the generated impls need on the struct's type parameters
+///
+/// Every field type mentioning a type parameter must implement `FormValue`, or
+/// `Default` when the field is skipped or uses `#[serde(default)]`, and the
+/// struct itself must satisfy the supertraits of `Form`.
+fn generate_bounds(
+    generics: &Generics,
+    fields: &[FormField],
+    skipped: &[FormField],
+    struct_attrs: &StructAttributes,
+) -> Generics {
+    let mut generics = generics.clone();
+    let type_params: Vec<Ident> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
         .collect();
+    if type_params.is_empty() {
+        return generics;
+    }
 
-    quote! {
-        #(#field_metadata),*
+    let mut predicates: Vec<WherePredicate> = Vec::new();
+    for field in fields.iter().chain(skipped) {
+        let ty = &field.ty;
+        if !mentions_type_param(quote!(#ty), &type_params) {
+            continue;
+        }
+        if !field.skip {
+            predicates.push(parse_quote! { #ty: ::leptos_forms_rs::core::FormValue });
+        }
+        let uses_default = field.default.is_none()
+            && struct_attrs.serde_default.is_none()
+            && (field.skip || matches!(field.serde_default, Some(SerdeDefault::Default)));
+        if uses_default {
+            predicates.push(parse_quote! { #ty: ::core::default::Default });
+        }
     }
-}
 
-/// Generate field accessors for get_field method
-fn generate_field_accessors(fields: &Fields) -> proc_macro2::TokenStream {
-    let accessors: Vec<proc_macro2::TokenStream> = fields
-        .iter()
-        .map(|field| {
-            let field_name = &field.ident;
+    let ident = &struct_attrs.ident;
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let self_ty: Type = parse_quote! { #ident #ty_generics };
+    predicates.push(parse_quote! {
+        #self_ty: ::leptos_forms_rs::__private::serde::Serialize
+            + for<'de> ::leptos_forms_rs::__private::serde::Deserialize<'de>
+            + ::core::clone::Clone
+            + 'static
+    });
+    if matches!(struct_attrs.serde_default, Some(SerdeDefault::Default)) {
+        predicates.push(parse_quote! { #self_ty: ::core::default::Default });
+    }
 
-            quote! {
-                stringify!(#field_name) => {
-                    let value = &self.#field_name;
-                    Some(leptos_forms_rs::utils::field_utils::convert_to_field_value(value))
-                }
-            }
-        })
-        .collect();
+    generics.make_where_clause().predicates.extend(predicates);
+    generics
+}
 
-    quote! {
-        #(#accessors),*
-    }
+/// Whether a type refers to any of the given type parameters
+fn mentions_type_param(tokens: proc_macro2::TokenStream, type_params: &[Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ident) => type_params.contains(&ident),
+        proc_macro2::TokenTree::Group(group) => mentions_type_param(group.stream(), type_params),
+        _ => false,
+    })
 }
 
-/// Generate field setters for set_field method
-fn generate_field_setters(fields: &Fields) -> proc_macro2::TokenStream {
-    let setters: Vec<proc_macro2::TokenStream> = fields
-        .iter()
-        .map(|field| {
-            let field_name = &field.ident;
+/// Attributes declared through `#[form(...)]` and `#[serde(...)]` on the struct itself
+struct StructAttributes {
+    ident: Ident,
+    validators: Vec<Path>,
+    rename_all: Option<LitStr>,
+    serde_default: Option<SerdeDefault>,
+}
 
-            quote! {
-                stringify!(#field_name) => {
-                    match value {
-                        leptos_forms_rs::core::types::FieldValue::String(s) => {
-                            self.#field_name = s;
-                            Ok(())
-                        }
-                        leptos_forms_rs::core::types::FieldValue::Boolean(b) => {
-                            self.#field_name = b;
-                            Ok(())
+impl StructAttributes {
+    fn from_attributes(ident: &Ident, attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut struct_attrs = Self {
+            ident: ident.clone(),
+            validators: Vec::new(),
+            rename_all: None,
+            serde_default: None,
+        };
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    struct_attrs.rename_all = serialize_name(&meta)?;
+                    if let Some(rule) = &struct_attrs.rename_all {
+                        if !RENAME_RULES.contains(&rule.value().as_str()) {
+                            return Err(syn::Error::new_spanned(rule, "unknown rename_all rule"));
                         }
-                        _ => Err(leptos_forms_rs::core::types::FieldError::new(
-                            stringify!(#field_name).to_string(),
-                            "Invalid value type".to_string()
-                        )),
                     }
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    struct_attrs.serde_default = Some(SerdeDefault::parse(&meta)?);
+                    Ok(())
+                } else {
+                    skip_meta(&meta)
                 }
-            }
-        })
-        .collect();
+            })?;
+        }
 
-    quote! {
-        #(#setters),*
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("form")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("validate") {
+                    struct_attrs.validators.push(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported form attribute on struct"))
+                }
+            })?;
+        }
+
+        Ok(struct_attrs)
     }
 }
 
-/// Generate default values for the struct
-fn generate_default_values(fields: &Fields) -> proc_macro2::TokenStream {
-    let defaults: Vec<proc_macro2::TokenStream> = fields
-        .iter()
-        .map(|field| {
-            let field_name = &field.ident;
-            let _field_type = &field.ty;
-            let default_value = extract_default_value(&field.attrs, &field.ty);
+/// Validator declared through a `#[form(...)]` field attribute
+enum FieldValidator {
+    Required,
+    Email,
+    Url,
+    MinLength(Expr),
+    MaxLength(Expr),
+    Min(Expr),
+    Max(Expr),
+    Pattern(LitStr),
+}
 
-            quote! {
-                #field_name: #default_value
-            }
-        })
-        .collect();
+/// Default declared through `#[serde(default)]` or `#[serde(default = "path")]`
+enum SerdeDefault {
+    Default,
+    Path(Path),
+}
 
-    quote! {
-        #(#defaults),*
+impl SerdeDefault {
+    fn parse(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Self> {
+        Ok(if meta.input.peek(Token![=]) {
+            let path: LitStr = meta.value()?.parse()?;
+            SerdeDefault::Path(path.parse()?)
+        } else {
+            SerdeDefault::Default
+        })
     }
 }
 
-/// Generate validation implementation
-fn generate_validation_impl(fields: &Fields) -> proc_macro2::TokenStream {
-    let validations: Vec<proc_macro2::TokenStream> = fields
-        .iter()
-        .map(|field| {
-            let field_name = &field.ident;
-            let validators = extract_validators(&field.attrs);
+/// A struct field together with its parsed `#[form(...)]` and `#[serde(...)]` attributes
+struct FormField {
+    ident: Ident,
+    /// Name used in metadata, error keys and the serialized payload
+    key: LitStr,
+    /// Name given with `#[form(rename = "...")]`, which must match `key`
+    form_rename: Option<LitStr>,
+    ty: Type,
+    validators: Vec<FieldValidator>,
+    custom_validators: Vec<Path>,
+    default: Option<Expr>,
+    serde_default: Option<SerdeDefault>,
+    skip: bool,
+    /// Presentation attributes such as `label`, `group` and `order`
+    attributes: Vec<(LitStr, LitStr)>,
+    /// Functions returning the field's `visible_when` and `enabled_when` conditions
+    visible_when: Option<Path>,
+    enabled_when: Option<Path>,
+    /// Function computing the field from the whole form, with its inputs
+    computed: Option<Path>,
+    depends_on: Vec<LitStr>,
+    include_in_payload: Option<syn::LitBool>,
+    /// Whether the value is kept out of persistence, analytics and debug output
+    sensitive: bool,
+}
 
-            quote! {
-                // Validate field: #field_name
-                let field_value = leptos_forms_rs::utils::field_utils::convert_to_field_value(&self.#field_name);
-                for validator in &[#validators] {
-                    if let Err(error) = leptos_forms_rs::validation::validate_field_value(&field_value, validator) {
-                        errors.add_field_error(stringify!(#field_name).to_string(), error);
+impl FormField {
+    fn from_field(field: &Field, struct_attrs: &StructAttributes) -> syn::Result<Self> {
+        let ident = field.ident.clone().expect("named field");
+        let name = ident.unraw().to_string();
+        let name = match &struct_attrs.rename_all {
+            Some(rule) => apply_rename_rule(&rule.value(), &name),
+            None => name,
+        };
+
+        let mut form_field = Self {
+            key: LitStr::new(&name, ident.span()),
+            form_rename: None,
+            ident,
+            ty: field.ty.clone(),
+            validators: Vec::new(),
+            custom_validators: Vec::new(),
+            default: None,
+            serde_default: None,
+            skip: false,
+            attributes: Vec::new(),
+            visible_when: None,
+            enabled_when: None,
+            computed: None,
+            depends_on: Vec::new(),
+            include_in_payload: None,
+            sensitive: false,
+        };
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("serde"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if let Some(rename) = serialize_name(&meta)? {
+                        form_field.key = rename;
                     }
+                    Ok(())
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                    form_field.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    form_field.serde_default = Some(SerdeDefault::parse(&meta)?);
+                    Ok(())
+                } else {
+                    skip_meta(&meta)
                 }
+            })?;
+        }
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("form"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let validator = if meta.path.is_ident("required") {
+                    FieldValidator::Required
+                } else if meta.path.is_ident("email") {
+                    FieldValidator::Email
+                } else if meta.path.is_ident("url") {
+                    FieldValidator::Url
+                } else if meta.path.is_ident("min_length") {
+                    FieldValidator::MinLength(meta.value()?.parse()?)
+                } else if meta.path.is_ident("max_length") {
+                    FieldValidator::MaxLength(meta.value()?.parse()?)
+                } else if meta.path.is_ident("min") {
+                    FieldValidator::Min(meta.value()?.parse()?)
+                } else if meta.path.is_ident("max") {
+                    FieldValidator::Max(meta.value()?.parse()?)
+                } else if meta.path.is_ident("pattern") {
+                    FieldValidator::Pattern(meta.value()?.parse()?)
+                } else if meta.path.is_ident("validate_with") {
+                    form_field.custom_validators.push(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("default") {
+                    form_field.default = Some(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("rename") {
+                    form_field.form_rename = Some(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("visible_when") {
+                    form_field.visible_when = Some(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("enabled_when") {
+                    form_field.enabled_when = Some(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("computed") {
+                    form_field.computed = Some(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("depends_on") {
+                    form_field.depends_on.push(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("include_in_payload") {
+                    form_field.include_in_payload = Some(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("sensitive") {
+                    form_field.sensitive = true;
+                    return Ok(());
+                } else if let Some(attribute) = PRESENTATION_ATTRIBUTES
+                    .iter()
+                    .find(|attribute| meta.path.is_ident(attribute))
+                {
+                    let value = if *attribute == "hidden" || *attribute == "readonly" {
+                        LitStr::new("true", meta.path.span())
+                    } else if *attribute == "order" {
+                        let order: syn::LitInt = meta.value()?.parse()?;
+                        LitStr::new(&order.base10_parse::<i32>()?.to_string(), order.span())
+                    } else {
+                        meta.value()?.parse()?
+                    };
+                    let key = LitStr::new(attribute, meta.path.span());
+                    form_field.attributes.push((key, value));
+                    return Ok(());
+                } else {
+                    return Err(meta.error("unsupported form attribute"));
+                };
+                form_field.validators.push(validator);
+                Ok(())
+            })?;
+        }
+
+        // The form reads and writes fields through serde, so it cannot use
+        // another name
+        if let Some(rename) = &form_field.form_rename {
+            if rename.value() != form_field.key.value() {
+                return Err(syn::Error::new(
+                    rename.span(),
+                    format!(
+                        "`rename` must match the name serde uses, `{}`; rename the field with `#[serde(rename = \"...\")]` instead",
+                        form_field.key.value()
+                    ),
+                ));
             }
-        })
-        .collect();
+        }
+        // Serde decides what fields it does not read back hold
+        if form_field.skip {
+            if let Some(default) = &form_field.default {
+                return Err(syn::Error::new_spanned(
+                    default,
+                    "`default` has no effect on a field serde skips; use `#[serde(default = \"...\")]`",
+                ));
+            }
+        }
 
-    quote! {
-        #(#validations)*
+        if form_field.computed.is_none() {
+            if let Some(input) = form_field.depends_on.first() {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "`depends_on` requires `computed`",
+                ));
+            }
+            if let Some(include) = &form_field.include_in_payload {
+                return Err(syn::Error::new(
+                    include.span(),
+                    "`include_in_payload` requires `computed`",
+                ));
+            }
+        } else {
+            // Computed fields are read-only in rendered forms
+            let readonly = LitStr::new("readonly", form_field.ident.span());
+            let value = LitStr::new("true", form_field.ident.span());
+            form_field.attributes.push((readonly, value));
+        }
+
+        Ok(form_field)
     }
-}
 
-/// Determine the field type from the Rust type
-fn determine_field_type(ty: &Type) -> proc_macro2::TokenStream {
-    match ty {
-        Type::Path(type_path) => {
-            let type_name = &type_path.path.segments.last().unwrap().ident;
-            match type_name.to_string().as_str() {
-                "String" => quote! { leptos_forms_rs::core::types::FieldType::Text },
-                "i32" | "i64" | "u32" | "u64" | "f32" | "f64" => {
-                    quote! { leptos_forms_rs::core::types::FieldType::Number(leptos_forms_rs::core::types::NumberType { min: None, max: None, step: None }) }
-                }
-                "bool" => quote! { leptos_forms_rs::core::types::FieldType::Boolean },
-                _ => quote! { leptos_forms_rs::core::types::FieldType::Text },
+    fn is_required(&self) -> bool {
+        self.validators
+            .iter()
+            .any(|validator| matches!(validator, FieldValidator::Required))
+    }
+
+    /// Default value expression, falling back to serde's defaults before the
+    /// field type's own `FormValue::default_value`
+    fn default_expr(&self, struct_attrs: &StructAttributes) -> proc_macro2::TokenStream {
+        let ty = &self.ty;
+        let ident = &self.ident;
+        match (&self.default, &self.serde_default) {
+            (
+                Some(Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit),
+                    ..
+                })),
+                _,
+            ) => quote! { <#ty as ::core::convert::From<&str>>::from(#lit) },
+            (Some(expr), _) => quote! { #expr },
+            (None, Some(SerdeDefault::Path(path))) => quote! { #path() },
+            (None, Some(SerdeDefault::Default)) => {
+                quote! { <#ty as ::core::default::Default>::default() }
+            }
+            (None, None) if struct_attrs.serde_default.is_some() => {
+                quote! { serde_defaults.#ident }
+            }
+            (None, None) if self.skip => quote! { <#ty as ::core::default::Default>::default() },
+            (None, None) => {
+                quote! { <#ty as ::leptos_forms_rs::core::FormValue>::default_value() }
             }
         }
-        _ => quote! { leptos_forms_rs::core::types::FieldType::Text },
     }
 }
 
-/// Extract validators from field attributes
-fn extract_validators(attrs: &[Attribute]) -> proc_macro2::TokenStream {
-    let mut validators = Vec::new();
-
-    for attr in attrs {
-        if attr.path().is_ident("form") {
-            if let Meta::List(meta_list) = &attr.meta {
-                // Parse the tokens manually for now
-                // This is a simplified version - in a real implementation you'd want more robust parsing
-                let tokens = meta_list.tokens.to_string();
-                if tokens.contains("required") {
-                    validators
-                        .push(quote! { leptos_forms_rs::core::types::ValidatorConfig::Required });
-                }
-                if tokens.contains("email") {
-                    validators
-                        .push(quote! { leptos_forms_rs::core::types::ValidatorConfig::Email });
-                }
-                if tokens.contains("url") {
-                    validators.push(quote! { leptos_forms_rs::core::types::ValidatorConfig::Url });
-                }
-                // For now, we'll use simple string matching for numeric values
-                if let Some(min_length) = extract_number_from_tokens(&tokens, "min_length") {
-                    validators.push(quote! { leptos_forms_rs::core::types::ValidatorConfig::MinLength(#min_length) });
-                }
-                if let Some(max_length) = extract_number_from_tokens(&tokens, "max_length") {
-                    validators.push(quote! { leptos_forms_rs::core::types::ValidatorConfig::MaxLength(#max_length) });
+/// Field attributes copied into `FieldMetadata::attributes` for rendering
+const PRESENTATION_ATTRIBUTES: &[&str] = &[
+    "label",
+    "placeholder",
+    "help_text",
+    "group",
+    "order",
+    "hidden",
+    "readonly",
+];
+
+/// `rename_all` rules supported by serde
+const RENAME_RULES: &[&str] = &[
+    "lowercase",
+    "UPPERCASE",
+    "PascalCase",
+    "camelCase",
+    "snake_case",
+    "SCREAMING_SNAKE_CASE",
+    "kebab-case",
+    "SCREAMING-KEBAB-CASE",
+];
+
+/// Apply a serde `rename_all` rule to a snake_case field name
+fn apply_rename_rule(rule: &str, name: &str) -> String {
+    match rule {
+        "lowercase" | "snake_case" => name.to_string(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.to_ascii_uppercase().replace('_', "-"),
+        "PascalCase" | "camelCase" => {
+            let mut renamed = String::new();
+            let mut capitalize = rule == "PascalCase";
+            for ch in name.chars() {
+                if ch == '_' {
+                    capitalize = true;
+                } else if capitalize {
+                    renamed.push(ch.to_ascii_uppercase());
+                    capitalize = false;
+                } else {
+                    renamed.push(ch);
                 }
             }
+            renamed
         }
+        _ => name.to_string(),
     }
+}
 
-    if validators.is_empty() {
-        quote! {}
-    } else {
-        quote! { #(#validators),* }
+/// Read a serde name given either as `key = "name"` or `key(serialize = "name")`
+fn serialize_name(meta: &ParseNestedMeta) -> syn::Result<Option<LitStr>> {
+    if meta.input.peek(Token![=]) {
+        return Ok(Some(meta.value()?.parse()?));
     }
+
+    let mut name = None;
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("serialize") {
+            name = Some(nested.value()?.parse()?);
+            Ok(())
+        } else {
+            skip_meta(&nested)
+        }
+    })?;
+    Ok(name)
 }
 
-/// Helper function to extract numbers from token strings
-fn extract_number_from_tokens(tokens: &str, key: &str) -> Option<usize> {
-    let pattern = format!("{} = ", key);
-    if let Some(start) = tokens.find(&pattern) {
-        let start = start + pattern.len();
-        if let Some(end) = tokens[start..].find(|c: char| !c.is_ascii_digit()) {
-            tokens[start..start + end].parse::<usize>().ok()
+/// Consume a serde attribute the derive does not interpret
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| skip_meta(&nested))?;
+    }
+    Ok(())
+}
+
+/// Generate `Validator` values for a field
+fn generate_validators(field: &FormField) -> proc_macro2::TokenStream {
+    let validators = field.validators.iter().map(|validator| match validator {
+        FieldValidator::Required => quote! { ::leptos_forms_rs::validation::Validator::Required },
+        FieldValidator::Email => quote! { ::leptos_forms_rs::validation::Validator::Email },
+        FieldValidator::Url => quote! { ::leptos_forms_rs::validation::Validator::Url },
+        FieldValidator::MinLength(len) => {
+            quote! { ::leptos_forms_rs::validation::Validator::MinLength((#len) as usize) }
+        }
+        FieldValidator::MaxLength(len) => {
+            quote! { ::leptos_forms_rs::validation::Validator::MaxLength((#len) as usize) }
+        }
+        FieldValidator::Min(min) => {
+            quote! { ::leptos_forms_rs::validation::Validator::Min((#min) as f64) }
+        }
+        FieldValidator::Max(max) => {
+            quote! { ::leptos_forms_rs::validation::Validator::Max((#max) as f64) }
+        }
+        FieldValidator::Pattern(pattern) => {
+            quote! { ::leptos_forms_rs::validation::Validator::Pattern(#pattern.to_string()) }
+        }
+    });
+
+    quote! { #(#validators),* }
+}
+
+/// Generate field metadata from struct fields
+fn generate_field_metadata(
+    fields: &[FormField],
+    struct_attrs: &StructAttributes,
+) -> proc_macro2::TokenStream {
+    let field_metadata = fields.iter().map(|field| {
+        let key = &field.key;
+        let ty = &field.ty;
+        let validators = generate_validators(field);
+        let is_required = field.is_required();
+        let default_value = if field.default.is_some() || field.serde_default.is_some() {
+            let default_expr = field.default_expr(struct_attrs);
+            quote! {
+                Some(<#ty as ::leptos_forms_rs::core::FormValue>::to_field_value(&#default_expr))
+            }
         } else {
-            tokens[start..].parse::<usize>().ok()
+            quote! { None }
+        };
+
+        let attributes = field.attributes.iter().map(|(key, value)| {
+            quote! { (#key.to_string(), #value.to_string()) }
+        });
+        let condition = |path: &Option<Path>| match path {
+            Some(path) => quote_spanned! {path.span()=> Some(#path()) },
+            None => quote! { None },
+        };
+        let visible_when = condition(&field.visible_when);
+        let enabled_when = condition(&field.enabled_when);
+        let dependencies = &field.depends_on;
+        let sensitive = field.sensitive;
+
+        quote! {
+            ::leptos_forms_rs::core::FieldMetadata {
+                name: #key.to_string(),
+                attributes: [#(#attributes),*].into_iter().collect(),
+                field_type: <#ty as ::leptos_forms_rs::core::FormValue>::field_type(),
+                validators: vec![#validators],
+                is_required: #is_required,
+                default_value: #default_value,
+                nested_fields: <#ty as ::leptos_forms_rs::core::FormValue>::nested_fields(),
+                visible_when: #visible_when,
+                enabled_when: #enabled_when,
+                dependencies: vec![#(#dependencies.to_string()),*],
+                sensitive: #sensitive,
+            }
         }
-    } else {
-        None
+    });
+
+    quote! {
+        #(#field_metadata),*
     }
 }
 
-/// Check if a field has a specific validator
-fn has_validator(attrs: &[Attribute], validator_name: &str) -> proc_macro2::TokenStream {
-    for attr in attrs {
-        if attr.path().is_ident("form") {
-            if let Meta::List(meta_list) = &attr.meta {
-                let tokens = meta_list.tokens.to_string();
-                if tokens.contains(validator_name) {
-                    return quote! { true };
-                }
+/// Generate `Form::computed_fields` for fields declared with `#[form(computed = path)]`
+fn generate_computed_fields(fields: &[FormField]) -> proc_macro2::TokenStream {
+    let computed = fields
+        .iter()
+        .filter_map(|field| {
+            let compute = field.computed.as_ref()?;
+            let key = &field.key;
+            let inputs = &field.depends_on;
+            let include = field
+                .include_in_payload
+                .as_ref()
+                .map(|include| quote! { .include_in_payload(#include) });
+            Some(quote_spanned! {compute.span()=>
+                ::leptos_forms_rs::core::ComputedField::new(#key, #compute)
+                    .depends_on(<[&str]>::iter(&[#(#inputs),*]).copied())
+                    #include
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if computed.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        fn computed_fields() -> Vec<::leptos_forms_rs::core::ComputedField<Self>> {
+            vec![#(#computed),*]
+        }
+    }
+}
+
+/// Generate field accessors for get_field_value method
+fn generate_field_accessors(fields: &[FormField]) -> proc_macro2::TokenStream {
+    let accessors = fields.iter().map(|field| {
+        let field_name = &field.ident;
+        let key = &field.key;
+
+        quote! {
+            #key => {
+                ::leptos_forms_rs::core::FormValue::to_field_value(&self.#field_name)
             }
         }
+    });
+
+    quote! {
+        #(#accessors),*
     }
-    quote! { false }
 }
 
-/// Extract default value from field attributes
-fn extract_default_value(attrs: &[Attribute], field_type: &Type) -> proc_macro2::TokenStream {
-    for attr in attrs {
-        if attr.path().is_ident("form") {
-            if let Meta::List(meta_list) = &attr.meta {
-                let tokens = meta_list.tokens.to_string();
-                if tokens.contains("default = true") {
-                    return quote! { true };
-                }
-                if tokens.contains("default = false") {
-                    return quote! { false };
-                }
-                if tokens.contains("default = \"en\"") {
-                    return quote! { "en".to_string() };
+/// Generate field setters for set_field_value method
+fn generate_field_setters(fields: &[FormField]) -> proc_macro2::TokenStream {
+    let setters = fields.iter().map(|field| {
+        let field_name = &field.ident;
+        let key = &field.key;
+
+        quote! {
+            #key => {
+                if let Ok(value) = ::leptos_forms_rs::core::FormValue::from_field_value(value) {
+                    self.#field_name = value;
                 }
             }
         }
+    });
+
+    quote! {
+        #(#setters),*
     }
+}
+
+/// Generate the field assignments used when building a nested form from an object
+fn generate_object_setters(fields: &[FormField]) -> proc_macro2::TokenStream {
+    let setters = fields.iter().map(|field| {
+        let field_name = &field.ident;
+        let key = &field.key;
 
-    // Use the appropriate default based on the field type
-    match field_type {
-        Type::Path(type_path) => {
-            let type_name = &type_path.path.segments.last().unwrap().ident;
-            match type_name.to_string().as_str() {
-                "String" => quote! { String::new() },
-                "bool" => quote! { false },
-                "i32" | "i64" | "u32" | "u64" | "f32" | "f64" => quote! { 0 },
-                _ => quote! { Default::default() },
+        quote! {
+            if let Some(value) = object.remove(#key) {
+                form.#field_name = ::leptos_forms_rs::core::FormValue::from_field_value(value)
+                    .map_err(|error| format!("{}: {}", #key, error))?;
             }
         }
-        _ => quote! { Default::default() },
+    });
+
+    quote! {
+        #(#setters)*
     }
 }
 
-/// Helper function to convert Rust values to FieldValue
-#[allow(dead_code)]
-fn convert_to_field_value(_value: &impl std::fmt::Debug) -> proc_macro2::TokenStream {
+/// Generate default values for the struct
+fn generate_default_values(
+    fields: &[FormField],
+    skipped: &[FormField],
+    struct_attrs: &StructAttributes,
+) -> proc_macro2::TokenStream {
+    let defaults = fields.iter().chain(skipped).map(|field| {
+        let field_name = &field.ident;
+        let default_value = field.default_expr(struct_attrs);
+
+        quote! {
+            #field_name: #default_value
+        }
+    });
+
+    // A struct-level `#[serde(default)]` supplies the defaults of unannotated fields
+    let serde_defaults = match &struct_attrs.serde_default {
+        Some(SerdeDefault::Default) => {
+            quote! { let serde_defaults = <Self as ::core::default::Default>::default(); }
+        }
+        Some(SerdeDefault::Path(path)) => quote! { let serde_defaults: Self = #path(); },
+        None => quote! {},
+    };
+
     quote! {
-        match value {
-            s if std::any::Any::is::<String>(s) => {
-                leptos_forms_rs::core::types::FieldValue::String(s.to_string())
+        #serde_defaults
+        Self {
+            #(#defaults),*
+        }
+    }
+}
+
+/// Generate validation implementation
+fn generate_validation_impl(
+    fields: &[FormField],
+    struct_attrs: &StructAttributes,
+) -> proc_macro2::TokenStream {
+    let has_validators = fields.iter().any(|field| !field.validators.is_empty());
+    let engine = if has_validators {
+        quote! { let engine = ::leptos_forms_rs::validation::ValidationRuleEngine::new(); }
+    } else {
+        quote! {}
+    };
+
+    let validations = fields.iter().map(|field| {
+        let field_name = &field.ident;
+        let key = &field.key;
+        let field_path = quote! {
+            ::leptos_forms_rs::core::field_path(path, #key)
+        };
+
+        let validator_checks = if field.validators.is_empty() {
+            quote! {}
+        } else {
+            let validators = generate_validators(field);
+            quote! {
+                let field_value = ::leptos_forms_rs::core::FormValue::to_field_value(&self.#field_name);
+                for error in engine.validate_field(&field_path, &field_value, &[#validators]) {
+                    errors.add_field_error(&field_path, error);
+                }
+            }
+        };
+
+        // Spanned on the function path so signature mismatches point at the attribute
+        let custom_checks = field.custom_validators.iter().map(|validator| {
+            quote_spanned! {validator.span()=>
+                let result: ::core::result::Result<(), ::std::string::String> =
+                    #validator(&self.#field_name);
+                if let Err(error) = result {
+                    errors.add_field_error(&field_path, error);
+                }
+            }
+        });
+
+        let checks = quote! {
+            {
+                let field_path = #field_path;
+                #validator_checks
+                #(#custom_checks)*
+                ::leptos_forms_rs::core::FormValue::validate_at(&self.#field_name, &field_path, errors);
             }
-            b if std::any::Any::is::<bool>(b) => {
-                leptos_forms_rs::core::types::FieldValue::Boolean(*b)
+        };
+
+        // Fields hidden by their `visible_when` rule are not validated
+        match &field.visible_when {
+            Some(condition) => quote_spanned! {condition.span()=>
+                if #condition().evaluate(self) #checks
+            },
+            None => checks,
+        }
+    });
+
+    let struct_checks = struct_attrs.validators.iter().map(|validator| {
+        quote_spanned! {validator.span()=>
+            let result: ::core::result::Result<(), ::leptos_forms_rs::validation::ValidationErrors> =
+                #validator(self);
+            if let Err(struct_errors) = result {
+                errors.merge_at(path, struct_errors);
             }
-            _ => leptos_forms_rs::core::types::FieldValue::Null,
         }
+    });
+
+    quote! {
+        #engine
+        #(#validations)*
+        #(#struct_checks)*
     }
 }
@@ -0,0 +1,40 @@
+//! Write the JSON Schema and TypeScript declarations for a serialized `FormSchema`
+//!
+//! Usage: `form-schema <schema.json> <output-dir>`
+//!
+//! The input is a `FormSchema` serialized with serde, e.g. written from a test
+//! or build script with `serde_json::to_string(&MyForm::schema())`. Build
+//! scripts can call `FormSchema::write_schema_files` directly instead.
+
+use leptos_forms_rs::FormSchema;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [input, output_dir] = args.as_slice() else {
+        eprintln!("Usage: form-schema <schema.json> <output-dir>");
+        return ExitCode::FAILURE;
+    };
+
+    let schema: FormSchema = match std::fs::read_to_string(input)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+    {
+        Ok(schema) => schema,
+        Err(error) => {
+            eprintln!("Failed to read form schema from {}: {}", input, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(error) = schema.write_schema_files(output_dir) {
+        eprintln!("Failed to write schema files to {}: {}", output_dir, error);
+        return ExitCode::FAILURE;
+    }
+
+    println!(
+        "Wrote {0}.schema.json and {0}.d.ts to {1}",
+        schema.name, output_dir
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,681 @@
+use crate::components::{FieldArray, FileUploadInput, MarkdownInput};
+use crate::core::dynamic::empty_value;
+use crate::core::*;
+use crate::validation::ValidationErrors;
+use leptos::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Everything a per-field override needs to render and update its field
+#[derive(Clone)]
+pub struct FieldSlot {
+    /// Field path, e.g. `billing_address.city` or `contacts[1].email`
+    pub path: String,
+    /// `name` attribute for the field's control, e.g. `contacts[1][email]`
+    pub name: String,
+    pub metadata: FieldMetadata,
+    pub value: Signal<FieldValue>,
+    pub errors: Signal<Vec<String>>,
+    /// Set while the field's `enabled_when` rule, or an enclosing one, fails
+    pub disabled: Signal<bool>,
+    pub on_change: Callback<FieldValue>,
+}
+
+type FieldRenderer = Arc<dyn Fn(FieldSlot) -> AnyView + Send + Sync>;
+type FieldReader = Arc<dyn Fn(&str) -> FieldValue + Send + Sync>;
+type FieldWriter = Arc<dyn Fn(&str, FieldValue) + Send + Sync>;
+type FieldCheck = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+type FieldTouch = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Renderers replacing `AutoForm`'s default input for individual fields
+///
+/// Keys are field paths, so nested fields are overridden with paths such as
+/// `billing_address.city` and array items with indexed paths such as `contacts[0]`.
+#[derive(Clone, Default)]
+pub struct FieldOverrides {
+    renderers: HashMap<String, FieldRenderer>,
+}
+
+impl FieldOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the field at `path` with `render` instead of the default input
+    pub fn with<F, V>(mut self, path: impl Into<String>, render: F) -> Self
+    where
+        F: Fn(FieldSlot) -> V + Send + Sync + 'static,
+        V: IntoView + 'static,
+    {
+        self.renderers
+            .insert(path.into(), Arc::new(move |slot| render(slot).into_any()));
+        self
+    }
+
+    /// Whether a renderer is registered for `path`
+    pub fn contains(&self, path: &str) -> bool {
+        self.renderers.contains_key(path)
+    }
+
+    fn get(&self, path: &str) -> Option<FieldRenderer> {
+        self.renderers.get(path).cloned()
+    }
+}
+
+/// A run of fields rendered together, inside a fieldset when `name` is set
+#[derive(Debug, Clone)]
+pub struct FieldGroup {
+    pub name: Option<String>,
+    pub fields: Vec<FieldMetadata>,
+}
+
+/// Arrange fields for rendering
+///
+/// Hidden fields are dropped, fields with an `order` come first sorted by it
+/// and the rest keep their declaration order. Fields sharing a `group` are
+/// gathered where the group's first field appears.
+pub fn layout_fields(fields: &[FieldMetadata]) -> Vec<FieldGroup> {
+    let mut visible: Vec<&FieldMetadata> = fields.iter().filter(|f| !f.is_hidden()).collect();
+    visible.sort_by_key(|field| field.order().unwrap_or(i32::MAX));
+
+    let mut groups: Vec<FieldGroup> = Vec::new();
+    for field in visible {
+        let name = field.group().map(str::to_string);
+        let existing = match &name {
+            Some(_) => groups.iter_mut().find(|group| group.name == name),
+            None => groups.last_mut().filter(|group| group.name.is_none()),
+        };
+        match existing {
+            Some(group) => group.fields.push(field.clone()),
+            None => groups.push(FieldGroup {
+                name,
+                fields: vec![field.clone()],
+            }),
+        }
+    }
+    groups
+}
+
+/// Convert the raw string of an `<input>` into a value of the field's type
+pub fn parse_input_value(field_type: &FieldType, raw: String) -> FieldValue {
+    match field_type {
+        FieldType::Number(_) if raw.trim().is_empty() => FieldValue::Null,
+        FieldType::Number(number) if number.step == Some(1.0) => raw
+            .trim()
+            .parse()
+            .map(FieldValue::Integer)
+            .unwrap_or(FieldValue::String(raw)),
+        FieldType::Number(_) => raw
+            .trim()
+            .parse()
+            .map(FieldValue::Number)
+            .unwrap_or(FieldValue::String(raw)),
+        FieldType::Date | FieldType::DateTime if raw.is_empty() => FieldValue::Null,
+        _ => FieldValue::String(raw),
+    }
+}
+
+/// Whether the fields include a file upload, which needs a multipart body
+pub fn has_file_fields(fields: &[FieldMetadata]) -> bool {
+    fields.iter().any(|field| {
+        let mut field_type = &field.field_type;
+        while let FieldType::Array(item_type) = field_type {
+            field_type = item_type;
+        }
+        matches!(field_type, FieldType::File(_)) || has_file_fields(&field.nested_fields)
+    })
+}
+
+/// Text shown in an input for a value
+pub(crate) fn display_value(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Null => String::new(),
+        FieldValue::String(s) => s.clone(),
+        FieldValue::DateTime(dt) => dt.format("%Y-%m-%dT%H:%M").to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Type-erased access to the form, shared by every rendered field
+#[derive(Clone)]
+struct FieldContext {
+    read: FieldReader,
+    write: FieldWriter,
+    touch: FieldTouch,
+    visible: FieldCheck,
+    enabled: FieldCheck,
+    errors: Memo<ValidationErrors>,
+    overrides: FieldOverrides,
+    /// Option sources of select fields, keyed by field path
+    options: HashMap<String, FieldOptions>,
+}
+
+impl FieldContext {
+    fn value_signal(&self, path: &str) -> Signal<FieldValue> {
+        let read = self.read.clone();
+        let path = path.to_string();
+        Signal::derive(move || read(&path))
+    }
+
+    fn errors_signal(&self, path: &str) -> Signal<Vec<String>> {
+        let errors = self.errors;
+        let path = path.to_string();
+        Signal::derive(move || {
+            errors.with(|errors| errors.get_field_error(&path).cloned().unwrap_or_default())
+        })
+    }
+
+    fn visible_memo(&self, path: &str) -> Memo<bool> {
+        let visible = self.visible.clone();
+        let path = path.to_string();
+        Memo::new(move |_| visible(&path))
+    }
+
+    fn disabled_signal(&self, path: &str) -> Signal<bool> {
+        let enabled = self.enabled.clone();
+        let path = path.to_string();
+        Memo::new(move |_| !enabled(&path)).into()
+    }
+
+    fn change_callback(&self, path: &str) -> Callback<FieldValue> {
+        let write = self.write.clone();
+        let path = path.to_string();
+        Callback::new(move |value| write(&path, value))
+    }
+
+    fn touch_callback(&self, path: &str) -> Callback<()> {
+        let touch = self.touch.clone();
+        let path = path.to_string();
+        Callback::new(move |_| touch(&path))
+    }
+}
+
+/// Form rendered entirely from `T::schema()`
+///
+/// Each field gets the input matching its `FieldType`: selects, checkboxes,
+/// `FileUploadInput`, `MarkdownInput`, a fieldset for nested forms and a
+/// `FieldArray` for arrays. The `label`, `placeholder`, `help_text`, `order`,
+/// `group`, `hidden` and `readonly` attributes of the field metadata are respected, and
+/// `overrides` replaces the input of individual fields.
+///
+/// Select fields listed in `options` offer the options of their source
+/// instead of the static ones of their `FieldType`.
+///
+/// Fields appear and become editable as their `visible_when` and
+/// `enabled_when` rules change; hidden fields are left out of validation and
+/// of the data passed to `on_submit_data`.
+///
+/// With an `action`, the form also works without JavaScript: controls are
+/// named in bracket notation (`contacts[1][email]`) and show their values in
+/// the server-rendered HTML, so the browser can post the form and the server
+/// can render it again from `read_submission`. Once hydrated, invalid forms
+/// are stopped in the browser, and valid ones are posted to the `action`
+/// unless `on_submit` or `on_submit_data` handles them.
+#[component]
+pub fn AutoForm<T: Form + PartialEq + Clone + Send + Sync>(
+    form: FormHandle<T>,
+    #[prop(optional)] overrides: Option<FieldOverrides>,
+    /// Option sources of select fields, from `use_field_options`
+    #[prop(optional)]
+    options: Option<Vec<FieldOptions>>,
+    /// Called with the form values when a valid form is submitted
+    #[prop(optional)]
+    on_submit: Option<Callback<T>>,
+    /// Called with the values of the visible fields when a valid form is submitted
+    #[prop(optional)]
+    on_submit_data: Option<Callback<HashMap<String, FieldValue>>>,
+    #[prop(optional, into)] submit_label: Option<String>,
+    #[prop(optional)] class: Option<String>,
+    /// URL the form is posted to when it is not handled in the browser
+    #[prop(optional, into)]
+    action: Option<String>,
+    /// HTTP method of the `action`, `post` by default
+    #[prop(optional, into)]
+    method: Option<String>,
+) -> impl IntoView {
+    let values = form.values();
+    let write_form = form.clone();
+    let touch_form = form.clone();
+    let context = FieldContext {
+        read: Arc::new(move |path: &str| {
+            values
+                .with(|values| get_form_path_value(values, path))
+                .unwrap_or_default()
+        }),
+        write: Arc::new(move |path: &str, value| write_form.set_field_path_value(path, value)),
+        touch: Arc::new(move |path: &str| touch_form.mark_touched(path)),
+        visible: Arc::new(move |path: &str| values.with(|values| is_field_visible(values, path))),
+        enabled: Arc::new(move |path: &str| values.with(|values| is_field_enabled(values, path))),
+        errors: form.errors(),
+        overrides: overrides.unwrap_or_default(),
+        options: options
+            .into_iter()
+            .flatten()
+            .map(|options| (options.field().to_string(), options))
+            .collect(),
+    };
+
+    let schema = T::schema();
+    let form_class = class.unwrap_or_else(|| "auto-form".to_string());
+    let submit_label = submit_label.unwrap_or_else(|| "Submit".to_string());
+    let state = form.state();
+    let handled_in_browser = on_submit.is_some() || on_submit_data.is_some();
+    let posts_natively = action.is_some() && !handled_in_browser;
+    let method = action
+        .as_ref()
+        .map(|_| method.unwrap_or_else(|| "post".to_string()));
+    let enctype = (action.is_some() && has_file_fields(&schema.field_metadata))
+        .then_some("multipart/form-data");
+
+    view! {
+        <form
+            class=form_class
+            action=action
+            method=method
+            enctype=enctype
+            novalidate=true
+            on:submit=move |ev| {
+                if form.validate().is_err() {
+                    ev.prevent_default();
+                    return;
+                }
+                if posts_natively {
+                    return;
+                }
+                ev.prevent_default();
+                if let Some(on_submit) = on_submit {
+                    on_submit.run(state.with_untracked(|state| state.values.clone()));
+                }
+                if let Some(on_submit_data) = on_submit_data {
+                    on_submit_data.run(form.submission_data());
+                }
+            }
+        >
+            {render_fields(&context, &schema.field_metadata, "")}
+            <button type="submit" class="form-submit">{submit_label}</button>
+        </form>
+    }
+}
+
+fn render_fields(context: &FieldContext, fields: &[FieldMetadata], prefix: &str) -> AnyView {
+    layout_fields(fields)
+        .into_iter()
+        .map(|group| {
+            let fields = group
+                .fields
+                .iter()
+                .map(|field| render_field(context, field, &field_path(prefix, &field.name)))
+                .collect::<Vec<_>>();
+            match group.name {
+                Some(name) => view! {
+                    <fieldset class="auto-form-group">
+                        <legend>{name}</legend>
+                        {fields}
+                    </fieldset>
+                }
+                .into_any(),
+                None => view! { <div class="auto-form-fields">{fields}</div> }.into_any(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .into_any()
+}
+
+fn render_field(context: &FieldContext, field: &FieldMetadata, path: &str) -> AnyView {
+    if field.visible_when.is_none() {
+        return render_shown_field(context, field, path);
+    }
+
+    // Only rebuilt when the field is shown or hidden, not on every change
+    let visible = context.visible_memo(path);
+    let context = context.clone();
+    let field = field.clone();
+    let path = path.to_string();
+    (move || {
+        visible
+            .get()
+            .then(|| render_shown_field(&context, &field, &path))
+    })
+    .into_any()
+}
+
+fn render_shown_field(context: &FieldContext, field: &FieldMetadata, path: &str) -> AnyView {
+    let slot = FieldSlot {
+        path: path.to_string(),
+        name: input_name(path),
+        metadata: field.clone(),
+        value: context.value_signal(path),
+        errors: context.errors_signal(path),
+        disabled: context.disabled_signal(path),
+        on_change: context.change_callback(path),
+    };
+    if let Some(render) = context.overrides.get(path) {
+        return render(slot);
+    }
+
+    match &field.field_type {
+        FieldType::Nested(_) => view! {
+            <fieldset class="auto-form-nested" disabled=move || slot.disabled.get()>
+                <legend>{field.label()}</legend>
+                {render_fields(context, &field.nested_fields, path)}
+            </fieldset>
+        }
+        .into_any(),
+        FieldType::Array(item_type) => render_array(context, field, item_type, path),
+        _ => {
+            let errors = slot.errors;
+            let is_required = field.is_required;
+            let help_text = field.attributes.get("help_text").cloned();
+            let touch = context.touch_callback(path);
+            view! {
+                <div class="form-field" on:focusout=move |_| touch.run(())>
+                    <label for=path.to_string() class="form-label">
+                        {field.label()}
+                        {is_required.then(|| view! { <span class="required">" *"</span> })}
+                    </label>
+                    <div class="form-input-wrapper">{render_input(field, slot, context.options.get(path).cloned())}</div>
+                    {move || {
+                        let errors = errors.get();
+                        (!errors.is_empty())
+                            .then(|| view! { <div class="form-error">{errors.join(", ")}</div> })
+                    }}
+                    {help_text.map(|help| view! { <div class="form-help">{help}</div> })}
+                </div>
+            }
+            .into_any()
+        }
+    }
+}
+
+fn render_input(
+    field: &FieldMetadata,
+    slot: FieldSlot,
+    dynamic_options: Option<FieldOptions>,
+) -> AnyView {
+    let FieldSlot {
+        path,
+        name,
+        value,
+        disabled,
+        on_change,
+        ..
+    } = slot;
+    let placeholder = field.attributes.get("placeholder").cloned();
+    let required = field.is_required;
+    // Controls without a `readonly` attribute are disabled instead
+    let readonly = field.is_readonly();
+    // Sensitive values never reach the rendered markup
+    let secret = field.is_sensitive();
+
+    match &field.field_type {
+        FieldType::Boolean => view! {
+            <input
+                type="checkbox"
+                id=path
+                name=name
+                value="true"
+                class="form-checkbox"
+                disabled=move || readonly || disabled.get()
+                checked=matches!(value.get_untracked(), FieldValue::Boolean(true))
+                prop:checked=move || matches!(value.get(), FieldValue::Boolean(true))
+                on:change=move |ev| on_change.run(FieldValue::Boolean(event_target_checked(&ev)))
+            />
+        }
+        .into_any(),
+        FieldType::Select(options) => {
+            let (options, loading, error) = match dynamic_options {
+                Some(dynamic) => (
+                    dynamic.options().into(),
+                    Some(dynamic.is_loading()),
+                    Some(dynamic.error()),
+                ),
+                None => (Signal::stored(options.clone()), None, None),
+            };
+            view! {
+                <select
+                    id=path
+                    name=name
+                    required=required
+                    disabled=move || readonly || disabled.get()
+                    aria-busy=move || loading.is_some_and(|loading| loading.get()).then_some("true")
+                    class="form-select"
+                    on:change=move |ev| on_change.run(FieldValue::String(event_target_value(&ev)))
+                >
+                    {(!required).then(|| view! { <option value="">""</option> })}
+                    {move || {
+                        options
+                            .get()
+                            .into_iter()
+                            .map(|option| {
+                                let option_value = option.value.clone();
+                                view! {
+                                    <option
+                                        value=option.value
+                                        disabled=option.disabled
+                                        selected=move || display_value(&value.get()) == option_value
+                                    >
+                                        {option.label}
+                                    </option>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </select>
+                {move || {
+                    error
+                        .and_then(|error| error.get())
+                        .map(|error| view! { <div class="form-error">{error.to_string()}</div> })
+                }}
+            }
+            .into_any()
+        }
+        FieldType::MultiSelect(options) => {
+            let options = options.clone();
+            view! {
+                <fieldset class="form-multi-select" disabled=move || readonly || disabled.get()>
+                    {options
+                        .into_iter()
+                        .map(|option| {
+                            let item = FieldValue::String(option.value.clone());
+                            let selected_item = item.clone();
+                            let is_selected = move || {
+                                value.with(|value| {
+                                    value
+                                        .as_array()
+                                        .is_some_and(|items| items.contains(&selected_item))
+                                })
+                            };
+                            let initially_selected = value.with_untracked(|value| {
+                                value.as_array().is_some_and(|items| items.contains(&item))
+                            });
+                            let toggled = option.value.clone();
+                            view! {
+                                <label class="form-checkbox-label">
+                                    <input
+                                        type="checkbox"
+                                        name=format!("{}[]", name)
+                                        value=option.value
+                                        disabled=option.disabled
+                                        checked=initially_selected
+                                        prop:checked=is_selected
+                                        on:change=move |ev| {
+                                            let item = FieldValue::String(toggled.clone());
+                                            let mut items = value
+                                                .get_untracked()
+                                                .as_array()
+                                                .cloned()
+                                                .unwrap_or_default();
+                                            items.retain(|existing| *existing != item);
+                                            if event_target_checked(&ev) {
+                                                items.push(item);
+                                            }
+                                            on_change.run(FieldValue::Array(items));
+                                        }
+                                    />
+                                    {option.label}
+                                </label>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </fieldset>
+            }
+            .into_any()
+        }
+        FieldType::File(constraints) => {
+            let constraints = constraints.clone();
+            (move || {
+                view! {
+                    <FileUploadInput
+                        name=name.clone()
+                        value=value
+                        _on_change=on_change
+                        required=required
+                        disabled=readonly || disabled.get()
+                        constraints=constraints.clone()
+                        multiple=constraints.multiple
+                    />
+                }
+            })
+            .into_any()
+        }
+        FieldType::Markdown => {
+            let placeholder = placeholder.unwrap_or_default();
+            (move || {
+                view! {
+                    <MarkdownInput
+                        name=name.clone()
+                        value=value
+                        _on_change=on_change
+                        placeholder=placeholder.clone()
+                        required=required
+                        disabled=readonly || disabled.get()
+                    />
+                }
+            })
+            .into_any()
+        }
+        FieldType::RichText | FieldType::Code => view! {
+            <textarea
+                id=path
+                name=name
+                placeholder=placeholder
+                required=required
+                readonly=readonly
+                disabled=move || disabled.get()
+                class="form-textarea"
+                prop:value=move || display_value(&value.get())
+                on:input=move |ev| on_change.run(FieldValue::String(event_target_value(&ev)))
+            >
+                {(!secret).then(|| display_value(&value.get_untracked()))}
+            </textarea>
+        }
+        .into_any(),
+        field_type => {
+            let field_type = field_type.clone();
+            let (input_type, min, max, step) = match &field_type {
+                FieldType::Email => ("email", None, None, None),
+                FieldType::Password => ("password", None, None, None),
+                FieldType::Number(number) => ("number", number.min, number.max, number.step),
+                FieldType::Date => ("date", None, None, None),
+                FieldType::DateTime => ("datetime-local", None, None, None),
+                _ => ("text", None, None, None),
+            };
+            view! {
+                <input
+                    type=input_type
+                    id=path
+                    name=name
+                    value=(!secret).then(|| display_value(&value.get_untracked()))
+                    placeholder=placeholder
+                    required=required
+                    min=min
+                    max=max
+                    step=step
+                    readonly=readonly
+                    disabled=move || disabled.get()
+                    class="form-input"
+                    prop:value=move || display_value(&value.get())
+                    on:input=move |ev| {
+                        on_change.run(parse_input_value(&field_type, event_target_value(&ev)))
+                    }
+                />
+            }
+            .into_any()
+        }
+    }
+}
+
+fn render_array(
+    context: &FieldContext,
+    field: &FieldMetadata,
+    item_type: &FieldType,
+    path: &str,
+) -> AnyView {
+    let value = context.value_signal(path);
+    let on_change = context.change_callback(path);
+    let length = Memo::new(move |_| value.with(|v| v.as_array().map_or(0, Vec::len)));
+
+    let item_field = FieldMetadata {
+        name: field.name.clone(),
+        field_type: item_type.clone(),
+        nested_fields: field.nested_fields.clone(),
+        attributes: field.attributes.clone(),
+        ..Default::default()
+    };
+    let new_item = empty_value(&item_field);
+
+    let update = move |mutate: &dyn Fn(&mut Vec<FieldValue>)| {
+        let mut items = value
+            .get_untracked()
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        mutate(&mut items);
+        on_change.run(FieldValue::Array(items));
+    };
+
+    let on_add = Callback::new(move |_| update(&|items| items.push(new_item.clone())));
+    let on_remove = Callback::new(move |index: usize| {
+        update(&|items| {
+            if index < items.len() {
+                items.remove(index);
+            }
+        })
+    });
+    let on_move = Callback::new(move |(from, to): (usize, usize)| {
+        update(&|items| {
+            if from < items.len() && to < items.len() {
+                let item = items.remove(from);
+                items.insert(to, item);
+            }
+        })
+    });
+    let on_clear = Callback::new(move |_| update(&|items| items.clear()));
+
+    let context = context.clone();
+    let path = path.to_string();
+    let label = field.label();
+    let render_item = Callback::new(move |index: usize| {
+        let item_path = format!("{}[{}]", path, index);
+        let item_field = FieldMetadata {
+            name: format!("{} {}", item_field.label(), index + 1),
+            ..item_field.clone()
+        };
+        render_field(&context, &item_field, &item_path)
+    });
+
+    (move || {
+        view! {
+            <FieldArray
+                field_name=label.clone()
+                items=vec![FieldValue::Null; length.get()]
+                on_add=on_add
+                on_remove=on_remove
+                on_move=on_move
+                on_clear=on_clear
+                render_item=render_item
+            />
+        }
+    })
+    .into_any()
+}
@@ -8,6 +8,21 @@ pub fn FieldArray<U: Clone + Send + Sync + Default + 'static>(
     #[prop(into)] items: Vec<U>,
     #[prop(optional)] class: Option<String>,
     #[prop(optional)] _children: Option<Children>,
+    /// Called when "Add Item" is clicked
+    #[prop(optional)]
+    on_add: Option<Callback<()>>,
+    /// Called with the index of the item to remove
+    #[prop(optional)]
+    on_remove: Option<Callback<usize>>,
+    /// Called with `(from, to)` when an item is moved
+    #[prop(optional)]
+    on_move: Option<Callback<(usize, usize)>>,
+    /// Called when "Clear All" is clicked
+    #[prop(optional)]
+    on_clear: Option<Callback<()>>,
+    /// Renders the editor for the item at an index instead of a placeholder
+    #[prop(optional)]
+    render_item: Option<Callback<usize, AnyView>>,
 ) -> impl IntoView {
     // For now, we'll create a dummy array handle
     // In a real implementation, this would get the form handle from context
@@ -18,24 +33,24 @@ pub fn FieldArray<U: Clone + Send + Sync + Default + 'static>(
         clear_array: Callback::new(|_: ()| {}),
     };
 
-    let add_item = move |_| {
-        // For now, just log - this will be implemented when we have a real form handle
-        log::info!("Add item clicked");
+    let add_item = move |_| match on_add {
+        Some(on_add) => on_add.run(()),
+        None => log::info!("Add item clicked"),
     };
 
-    let remove_item = move |index: usize| {
-        // For now, just log - this will be implemented when we have a real form handle
-        log::info!("Remove item clicked at index: {}", index);
+    let remove_item = move |index: usize| match on_remove {
+        Some(on_remove) => on_remove.run(index),
+        None => log::info!("Remove item clicked at index: {}", index),
     };
 
-    let move_item = move |(from, to): (usize, usize)| {
-        // For now, just log - this will be implemented when we have a real form handle
-        log::info!("Move item from {} to {}", from, to);
+    let move_item = move |(from, to): (usize, usize)| match on_move {
+        Some(on_move) => on_move.run((from, to)),
+        None => log::info!("Move item from {} to {}", from, to),
     };
 
-    let clear_all = move |_| {
-        // For now, just log - this will be implemented when we have a real form handle
-        log::info!("Clear all clicked");
+    let clear_all = move |_| match on_clear {
+        Some(on_clear) => on_clear.run(()),
+        None => log::info!("Clear all clicked"),
     };
 
     let array_class = class.unwrap_or_else(|| "field-array".to_string());
@@ -62,9 +77,15 @@ pub fn FieldArray<U: Clone + Send + Sync + Default + 'static>(
                     view! {
                         <div class="field-array-item">
                             <div class="item-content">
-                                <div class="item-placeholder">
-                                    {format!("Item {}", item_index + 1)}
-                                </div>
+                                {match render_item {
+                                    Some(render_item) => render_item.run(item_index),
+                                    None => view! {
+                                        <div class="item-placeholder">
+                                            {format!("Item {}", item_index + 1)}
+                                        </div>
+                                    }
+                                    .into_any(),
+                                }}
                             </div>
 
                             <div class="item-controls">
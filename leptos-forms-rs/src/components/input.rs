@@ -34,6 +34,8 @@ pub fn Input(
         })
         .unwrap_or("text");
 
+    // Passwords never reach the rendered markup
+    let value = value.filter(|_| input_type != "password");
     let is_required = required.unwrap_or(false);
     let is_disabled = disabled.unwrap_or(false);
     let input_class = class.unwrap_or_else(|| "form-input".to_string());
@@ -113,6 +113,7 @@ pub fn MarkdownInput(
                         classes.join(" ")
                     }}
                     style={format!("min-height: {}px; max-height: {}px;", min_height, max_height)}
+                    on:input=move |ev| _on_change.run(FieldValue::String(event_target_value(&ev)))
                 >{current_value}</textarea>
             </div>
 
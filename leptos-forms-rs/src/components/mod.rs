@@ -1,10 +1,14 @@
 use crate::core::*;
 use crate::hooks::*;
+use crate::submission::protection::{BotProtection, CsrfProtection, RENDERED_AT_FIELD};
 use crate::validation::ValidationErrors;
+use auto_form::display_value;
 use leptos::prelude::*;
 use leptos::task::spawn_local;
+use std::collections::BTreeMap;
 use web_sys;
 
+pub mod auto_form;
 pub mod checkbox;
 pub mod code_input;
 pub mod field;
@@ -20,6 +24,10 @@ pub mod rich_text_input;
 pub mod select;
 pub mod textarea;
 
+pub use auto_form::{
+    has_file_fields, layout_fields, parse_input_value, AutoForm, FieldGroup, FieldOverrides,
+    FieldSlot,
+};
 pub use code_input::CodeInput;
 pub use field_array::FieldArray;
 pub use file_upload_input::FileUploadInput;
@@ -28,15 +36,43 @@ pub use markdown_input::MarkdownInput;
 pub use rich_text_input::RichTextInput;
 
 /// Main Form component
+///
+/// Without an `action` the form is handled in the browser only: submitting
+/// it runs `FormHandle::submit`, which validates and calls the
+/// `on_submit` listeners; listeners that send the values report success
+/// with `FormHandle::finish_successful_submission`. With an `action` it also works without
+/// JavaScript; the browser posts it to the `action`, and once hydrated only
+/// valid forms are posted.
+///
+/// With `csrf` the token is rendered in a hidden field; when rendering on
+/// the server it must come from `CsrfProtection::server_token`. With
+/// `bot_protection` the form also carries a honeypot field hidden from
+/// people and the time it was rendered; submissions that fill in the
+/// honeypot are dropped silently, and the server checks the honeypot and the
+/// time to submit with `read_protected_submission`.
 #[component]
 pub fn Form<T: Form + PartialEq + Clone + Send + Sync>(
     form: FormHandle<T>,
     #[prop(optional)] class: Option<String>,
     #[prop(optional)] id: Option<String>,
     #[prop(optional)] novalidate: Option<bool>,
+    /// URL the form is posted to
+    #[prop(optional, into)]
+    action: Option<String>,
+    /// HTTP method of the `action`, `post` by default
+    #[prop(optional, into)]
+    method: Option<String>,
+    /// Encoding of the posted body, e.g. `multipart/form-data` for uploads
+    #[prop(optional, into)]
+    enctype: Option<String>,
+    /// CSRF token to render in a hidden field
+    #[prop(optional)]
+    csrf: Option<CsrfProtection>,
+    /// Honeypot and minimum time-to-submit checks against bots
+    #[prop(optional)]
+    bot_protection: Option<BotProtection>,
     #[prop(optional)] _children: Option<Children>,
 ) -> impl IntoView {
-    let _ = &form;
     let form_id = id.unwrap_or_else(|| format!("form-{}", std::any::type_name::<T>()));
     let form_class = class.unwrap_or_else(|| "leptos-form".to_string());
     let novalidate_attr = if novalidate.unwrap_or(true) {
@@ -44,17 +80,93 @@ pub fn Form<T: Form + PartialEq + Clone + Send + Sync>(
     } else {
         ""
     };
+    let posts_natively = action.is_some();
+    let method = action
+        .as_ref()
+        .map(|_| method.unwrap_or_else(|| "post".to_string()));
+
+    let csrf_field = csrf.map(|csrf| {
+        if !csrf.is_readable() {
+            log::warn!(
+                "The CSRF token of {} cannot be read while rendering on the server; \
+                 give it a server_token",
+                form_id
+            );
+        }
+        let name = csrf.field_name().to_string();
+        // The token read on the server is the one the hydrating client renders
+        let token = SharedValue::new(move || csrf.token().unwrap_or_default()).into_inner();
+        view! { <input type="hidden" name=name value=token/> }
+    });
+    let honeypot_ref = NodeRef::<leptos::html::Input>::new();
+    let honeypot_field = bot_protection
+        .as_ref()
+        .and_then(|bots| bots.honeypot_field())
+        .map(|name| {
+            view! {
+                <div
+                    class="leptos-form-honeypot"
+                    aria-hidden="true"
+                    style="position: absolute; left: -10000px; width: 1px; height: 1px; overflow: hidden;"
+                >
+                    <label>
+                        "Leave this field empty"
+                        <input
+                            type="text"
+                            name=name.to_string()
+                            value=""
+                            tabindex="-1"
+                            autocomplete="off"
+                            node_ref=honeypot_ref
+                        />
+                    </label>
+                </div>
+            }
+        });
+    let rendered_at_field = bot_protection
+        .as_ref()
+        .filter(|bots| bots.min_time().is_some())
+        .map(|bots| {
+            let stamp = form.share_render_stamp(bots);
+            view! { <input type="hidden" name=RENDERED_AT_FIELD value=stamp/> }
+        });
 
     view! {
         <form
             id=form_id
             class=form_class
+            action=action
+            method=method
+            enctype=enctype
             novalidate=novalidate_attr
             on:submit=move |ev| {
+                // The time to submit is left to the server, which knows when
+                // it rendered the form
+                if let Some(bots) = &bot_protection {
+                    let honeypot = honeypot_ref.get_untracked().map(|input| input.value());
+                    if bots.check_honeypot(honeypot.as_deref()).is_err() {
+                        // Bots are told nothing about why nothing happened
+                        ev.prevent_default();
+                        return;
+                    }
+                }
+                if posts_natively {
+                    if form.validate().is_err() {
+                        ev.prevent_default();
+                    }
+                    return;
+                }
                 ev.prevent_default();
-                // Handle form submission
+                // Nothing is sent here; listeners that send the values report
+                // success themselves
+                if form.submit().is_ok() {
+                    form.finish_submission();
+                }
             }
         >
+            {csrf_field}
+            {honeypot_field}
+            {rendered_at_field}
             {match _children {
                 Some(children) => children(),
                 None => view! { <div class="hidden">{String::new()}</div> }.into_any()
@@ -64,6 +176,10 @@ pub fn Form<T: Form + PartialEq + Clone + Send + Sync>(
 }
 
 /// FormField component for rendering form fields
+///
+/// The input writes to the field at `name` as it is edited, and its errors
+/// are shown once the user has left it. The field is hidden and disabled
+/// while its `visible_when` and `enabled_when` rules fail.
 #[component]
 pub fn FormField<T: Form + PartialEq + Clone + Send + Sync>(
     form: FormHandle<T>,
@@ -85,20 +201,35 @@ pub fn FormField<T: Form + PartialEq + Clone + Send + Sync>(
     let help_text_clone = help_text.clone();
     let field_type_clone = field_type.clone();
 
-    let _field_value = use_field_value(&form, &name);
+    let values = form.values();
+    let value_path = name.clone();
+    let field_value = Memo::new(move |_| {
+        values
+            .with(|values| get_form_path_value(values, &value_path))
+            .unwrap_or_default()
+    });
+    // Sensitive values never reach the rendered markup
+    let secret = matches!(field_type, Some(FieldType::Password))
+        || form
+            .values()
+            .with_untracked(|values| sensitive_field_paths(values).contains(&name));
     let field_error = use_field_error(&form, &name);
     let field_dirty = use_field_dirty(&form);
     let _ = &field_dirty;
-    let field_touched = use_field_touched(&form);
+    let field_touched = use_field_is_touched(&form, &name);
+    let field_visible = form.field_visible(&name);
+    let field_enabled = form.field_enabled(&name);
+    let touch_form = form.clone();
+    let touch_name = name.clone();
+    let input_form = form.clone();
+    let input_path = name.clone();
 
     let field_class = class.unwrap_or_else(|| "form-field".to_string());
     let is_required = required.unwrap_or(false);
     let is_disabled = disabled.unwrap_or(false);
 
-    let show_error = move || !field_error.get().is_empty() && field_touched.get();
-
     view! {
-        <div class=field_class>
+        <div class=field_class hidden=move || !field_visible.get()>
             {move || {
                 if let Some(label_text) = label_clone.clone() {
                     view! {
@@ -124,7 +255,7 @@ pub fn FormField<T: Form + PartialEq + Clone + Send + Sync>(
             <div class="form-input-wrapper">
                 {move || {
                     // Clone variables for use inside this closure
-                    let name_clone = name_clone2.clone();
+                    let input_name = input_name(&name_clone2);
                     let field_type_clone = field_type_clone.clone();
                     let placeholder_clone = placeholder_clone.clone();
 
@@ -141,27 +272,60 @@ pub fn FormField<T: Form + PartialEq + Clone + Send + Sync>(
                         "text"
                     };
 
+                    let (value, checked) = match field_value.get_untracked() {
+                        FieldValue::Boolean(checked) => (Some("true".to_string()), checked),
+                        FieldValue::Null => (None, false),
+                        _ if secret => (None, false),
+                        other => (Some(display_value(&other)), false),
+                    };
+                    let is_checkbox = input_type == "checkbox";
+                    let parse_type = field_type_clone.unwrap_or(FieldType::Text);
+
                     view! {
                         <input
                             type=input_type
-                            name=name_clone
+                            name=input_name
+                            value=value
+                            checked=checked
                             placeholder=placeholder_clone.unwrap_or_default()
-                            disabled=is_disabled
+                            disabled=move || is_disabled || !field_enabled.get()
                             required=is_required
                             class="form-input"
+                            prop:value=move || {
+                                field_value.with(|value| match value {
+                                    FieldValue::Boolean(_) => "true".to_string(),
+                                    other => display_value(other),
+                                })
+                            }
+                            prop:checked=move || {
+                                field_value.with(|value| matches!(value, FieldValue::Boolean(true)))
+                            }
+                            on:input={
+                                let input_form = input_form.clone();
+                                let input_path = input_path.clone();
+                                move |ev| {
+                                    let value = if is_checkbox {
+                                        FieldValue::Boolean(event_target_checked(&ev))
+                                    } else {
+                                        parse_input_value(&parse_type, event_target_value(&ev))
+                                    };
+                                    input_form.set_field_path_value(&input_path, value);
+                                }
+                            }
+                            on:blur={
+                                let touch_form = touch_form.clone();
+                                let touch_name = touch_name.clone();
+                                move |_| touch_form.mark_touched(&touch_name)
+                            }
                         />
                     }
                 }}
             </div>
 
-            {if show_error() {
-                view! {
-                    <div class="form-error">
-                        {field_error.get().join(", ")}
-                    </div>
-                }
-            } else {
-                view! { <div class="hidden">{String::new()}</div> }
+            {move || {
+                let errors = field_error.get();
+                (field_touched.get() && !errors.is_empty())
+                    .then(|| view! { <div class="form-error">{errors.join(", ")}</div> })
             }}
 
                             {if let Some(help) = help_text_clone {
@@ -369,7 +533,13 @@ pub fn FormDebug<T: Form + PartialEq + Clone + Send + Sync + std::fmt::Debug>(
 
                 <div class="debug-section">
                     <h4>"Form Values"</h4>
-                    <pre>{move || format!("{:#?}", values.get())}</pre>
+                    <pre>
+                        {move || {
+                            let values: BTreeMap<String, FieldValue> =
+                                redacted_form_data(&values.get()).into_iter().collect();
+                            format!("{:#?}", values)
+                        }}
+                    </pre>
                 </div>
 
                 <div class="debug-section">
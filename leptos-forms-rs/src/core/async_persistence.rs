@@ -0,0 +1,380 @@
+use crate::core::convert::field_path;
+use crate::core::drafts::DraftLoad;
+use crate::core::sensitive::json_path_mut;
+use crate::core::traits::Form;
+use crate::error::FormError;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Result of an asynchronous storage operation
+pub type PersistenceFuture<'a, R> = Pin<Box<dyn Future<Output = Result<R, FormError>> + 'a>>;
+
+/// Asynchronous storage for form drafts, e.g. in IndexedDB
+///
+/// Unlike [`FormPersistence`](crate::core::FormPersistence), failures are
+/// reported as `FormError::PersistenceError`, telling apart storage that ran
+/// out of space so that [`QuotaFallback`] can try a smaller backend.
+///
+/// ```rust,ignore
+/// let storage = QuotaFallback::new(
+///     FileBlobPersistence::new(IndexedDbPersistence::new("signup"), IndexedDbBlobStore::new()),
+///     LocalStoragePersistence::new("signup"),
+/// );
+/// spawn_local(async move {
+///     if let Some(values) = storage.load().await? {
+///         form.set_values(values);
+///     }
+/// });
+/// ```
+pub trait AsyncFormPersistence<T: Form> {
+    /// Key the draft is stored under
+    fn key(&self) -> &str;
+
+    /// Save form state to storage
+    fn save<'a>(&'a self, form: &'a T) -> PersistenceFuture<'a, ()>;
+
+    /// Load form state from storage
+    fn load(&self) -> PersistenceFuture<'_, Option<T>> {
+        Box::pin(async move { self.load_draft().await.map(DraftLoad::into_values) })
+    }
+
+    /// Load form state from storage, reporting how the saved draft fit the form
+    fn load_draft(&self) -> PersistenceFuture<'_, DraftLoad<T>>;
+
+    /// Clear saved form state
+    fn clear(&self) -> PersistenceFuture<'_, ()>;
+
+    /// Check if form state exists in storage
+    fn exists(&self) -> PersistenceFuture<'_, bool>;
+}
+
+/// Storage for the contents of files, kept out of drafts
+pub trait BlobStore {
+    fn put<'a>(&'a self, id: &'a str, bytes: &'a [u8]) -> PersistenceFuture<'a, ()>;
+
+    fn get<'a>(&'a self, id: &'a str) -> PersistenceFuture<'a, Option<Vec<u8>>>;
+
+    /// Ids of the blobs starting with `prefix`
+    fn ids_with_prefix<'a>(&'a self, prefix: &'a str) -> PersistenceFuture<'a, Vec<String>>;
+
+    fn remove<'a>(&'a self, id: &'a str) -> PersistenceFuture<'a, ()>;
+
+    /// Remove every blob whose id starts with `prefix`
+    fn remove_prefix<'a>(&'a self, prefix: &'a str) -> PersistenceFuture<'a, ()>;
+}
+
+/// Blobs held in memory, for tests and server rendering
+///
+/// Clones share their blobs.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBlobStore {
+    blobs: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+}
+
+impl MemoryBlobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ids of the stored blobs, in order
+    pub fn ids(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>> {
+        self.blobs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl BlobStore for MemoryBlobStore {
+    fn put<'a>(&'a self, id: &'a str, bytes: &'a [u8]) -> PersistenceFuture<'a, ()> {
+        self.lock().insert(id.to_string(), bytes.to_vec());
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn get<'a>(&'a self, id: &'a str) -> PersistenceFuture<'a, Option<Vec<u8>>> {
+        let bytes = self.lock().get(id).cloned();
+        Box::pin(std::future::ready(Ok(bytes)))
+    }
+
+    fn ids_with_prefix<'a>(&'a self, prefix: &'a str) -> PersistenceFuture<'a, Vec<String>> {
+        let ids = self
+            .lock()
+            .keys()
+            .filter(|id| id.starts_with(prefix))
+            .cloned()
+            .collect();
+        Box::pin(std::future::ready(Ok(ids)))
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> PersistenceFuture<'a, ()> {
+        self.lock().remove(id);
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn remove_prefix<'a>(&'a self, prefix: &'a str) -> PersistenceFuture<'a, ()> {
+        self.lock().retain(|id, _| !id.starts_with(prefix));
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
+
+/// Persistence that keeps the bytes of `FileData` values in a blob store
+///
+/// Drafts only hold the name, size and type of each file, which keeps them
+/// small enough for Web Storage. The bytes are stored under the draft's key
+/// and the path of the file, e.g. `signup/attachments[0]`.
+pub struct FileBlobPersistence<P> {
+    inner: P,
+    blobs: Arc<dyn BlobStore>,
+}
+
+impl<P> FileBlobPersistence<P> {
+    pub fn new(inner: P, blobs: impl BlobStore + 'static) -> Self {
+        Self {
+            inner,
+            blobs: Arc::new(blobs),
+        }
+    }
+
+    /// The persistence holding the drafts themselves
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    fn blob_prefix<T: Form>(&self) -> String
+    where
+        P: AsyncFormPersistence<T>,
+    {
+        format!("{}/", self.inner.key())
+    }
+
+    /// Put the bytes of the files in `values` back from the blob store
+    async fn attach_files<T: Form>(&self, values: T) -> Result<T, FormError>
+    where
+        P: AsyncFormPersistence<T>,
+    {
+        let mut json = serde_json::to_value(&values)
+            .map_err(|e| FormError::serialization_error(e.to_string(), None))?;
+        let mut paths = Vec::new();
+        collect_files(&json, "", &mut |path, file| {
+            let is_stripped = file["data"].as_array().is_some_and(Vec::is_empty)
+                && file["size"].as_u64().is_some_and(|size| size > 0);
+            if is_stripped {
+                paths.push(path.to_string());
+            }
+        });
+        if paths.is_empty() {
+            return Ok(values);
+        }
+
+        let prefix = self.blob_prefix::<T>();
+        for path in paths {
+            let Some(bytes) = self.blobs.get(&format!("{}{}", prefix, path)).await? else {
+                log::warn!("The contents of {} are no longer stored", path);
+                continue;
+            };
+            if let Some(data) =
+                json_path_mut(&mut json, &path).and_then(|file| file.get_mut("data"))
+            {
+                *data = Value::from(bytes);
+            }
+        }
+        serde_json::from_value(json)
+            .map_err(|e| FormError::serialization_error(e.to_string(), None))
+    }
+}
+
+impl<T: Form, P: AsyncFormPersistence<T>> AsyncFormPersistence<T> for FileBlobPersistence<P> {
+    fn key(&self) -> &str {
+        self.inner.key()
+    }
+
+    fn save<'a>(&'a self, form: &'a T) -> PersistenceFuture<'a, ()> {
+        Box::pin(async move {
+            let mut json = serde_json::to_value(form)
+                .map_err(|e| FormError::serialization_error(e.to_string(), None))?;
+            let mut paths = Vec::new();
+            collect_files(&json, "", &mut |path, file| {
+                if file["data"].as_array().is_some_and(|data| !data.is_empty()) {
+                    paths.push(path.to_string());
+                }
+            });
+
+            // The previous draft keeps its files until this one is saved
+            let prefix = self.blob_prefix::<T>();
+            let ids: Vec<String> = paths
+                .iter()
+                .map(|path| format!("{}{}", prefix, path))
+                .collect();
+            for (path, id) in paths.iter().zip(&ids) {
+                let Some(data) =
+                    json_path_mut(&mut json, path).and_then(|file| file.get_mut("data"))
+                else {
+                    continue;
+                };
+                let bytes: Vec<u8> = serde_json::from_value(data.take()).map_err(|e| {
+                    FormError::serialization_error(e.to_string(), Some(path.clone()))
+                })?;
+                self.blobs.put(id, &bytes).await?;
+                *data = Value::Array(Vec::new());
+            }
+
+            let stripped: T = serde_json::from_value(json)
+                .map_err(|e| FormError::serialization_error(e.to_string(), None))?;
+            self.inner.save(&stripped).await?;
+
+            for stale in self.blobs.ids_with_prefix(&prefix).await? {
+                if !ids.contains(&stale) {
+                    self.blobs.remove(&stale).await?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn load_draft(&self) -> PersistenceFuture<'_, DraftLoad<T>> {
+        Box::pin(async move {
+            Ok(match self.inner.load_draft().await? {
+                DraftLoad::Restored(values) => {
+                    DraftLoad::Restored(self.attach_files(values).await?)
+                }
+                DraftLoad::Migrated {
+                    values,
+                    from_version,
+                } => DraftLoad::Migrated {
+                    values: self.attach_files(values).await?,
+                    from_version,
+                },
+                DraftLoad::Partial { values, skipped } => DraftLoad::Partial {
+                    values: self.attach_files(values).await?,
+                    skipped,
+                },
+                other => other,
+            })
+        })
+    }
+
+    fn clear(&self) -> PersistenceFuture<'_, ()> {
+        Box::pin(async move {
+            self.inner.clear().await?;
+            self.blobs.remove_prefix(&self.blob_prefix::<T>()).await
+        })
+    }
+
+    fn exists(&self) -> PersistenceFuture<'_, bool> {
+        self.inner.exists()
+    }
+}
+
+/// Serialized `FileData` values within `value`, by path
+fn collect_files(value: &Value, path: &str, found: &mut dyn FnMut(&str, &Value)) {
+    match value {
+        Value::Object(_) if is_file_data(value) => found(path, value),
+        Value::Object(object) => {
+            for (name, nested) in object {
+                collect_files(nested, &field_path(path, name), found);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_files(item, &format!("{}[{}]", path, index), found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_file_data(value: &Value) -> bool {
+    let Value::Object(object) = value else {
+        return false;
+    };
+    object.len() == 4
+        && ["name", "size", "mime_type"]
+            .iter()
+            .all(|key| object.contains_key(*key))
+        && object.get("data").is_some_and(Value::is_array)
+}
+
+/// Persistence that moves to a smaller backend when the primary one is full
+///
+/// Drafts are saved to `primary` until it reports that its quota is
+/// exceeded; that draft is then saved to `fallback` instead. Loading prefers
+/// the primary backend, and clearing clears both.
+pub struct QuotaFallback<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P, F> QuotaFallback<P, F> {
+    pub fn new(primary: P, fallback: F) -> Self {
+        Self { primary, fallback }
+    }
+
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    pub fn fallback(&self) -> &F {
+        &self.fallback
+    }
+}
+
+impl<T, P, F> AsyncFormPersistence<T> for QuotaFallback<P, F>
+where
+    T: Form,
+    P: AsyncFormPersistence<T>,
+    F: AsyncFormPersistence<T>,
+{
+    fn key(&self) -> &str {
+        self.primary.key()
+    }
+
+    fn save<'a>(&'a self, form: &'a T) -> PersistenceFuture<'a, ()> {
+        Box::pin(async move {
+            match self.primary.save(form).await {
+                Err(error) if error.is_quota_exceeded() => {
+                    log::warn!("{}; saving the draft to the fallback storage", error);
+                    // An older draft left in the primary storage would
+                    // shadow this one on load
+                    if let Err(error) = self.primary.clear().await {
+                        log::warn!("Failed to clear the full storage: {}", error);
+                    }
+                    self.fallback.save(form).await
+                }
+                Err(error) => Err(error),
+                Ok(()) => {
+                    if let Err(error) = self.fallback.clear().await {
+                        log::warn!("Failed to clear the fallback storage: {}", error);
+                    }
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn load_draft(&self) -> PersistenceFuture<'_, DraftLoad<T>> {
+        Box::pin(async move {
+            match self.primary.load_draft().await? {
+                DraftLoad::Empty => self.fallback.load_draft().await,
+                draft => Ok(draft),
+            }
+        })
+    }
+
+    fn clear(&self) -> PersistenceFuture<'_, ()> {
+        Box::pin(async move {
+            let primary = self.primary.clear().await;
+            self.fallback.clear().await?;
+            primary
+        })
+    }
+
+    fn exists(&self) -> PersistenceFuture<'_, bool> {
+        Box::pin(async move { Ok(self.primary.exists().await? || self.fallback.exists().await?) })
+    }
+}
@@ -0,0 +1,131 @@
+use crate::core::convert::get_form_path_value;
+use crate::core::traits::Form;
+use crate::core::types::FieldValue;
+use crate::validation::Validator;
+use std::fmt;
+use std::sync::Arc;
+
+type ComputeFn<T> = Arc<dyn Fn(&T) -> FieldValue + Send + Sync>;
+
+/// A read-only field whose value is derived from other fields
+///
+/// Computed fields are declared by a form's `Form::computed_fields` (the
+/// derive's `#[form(computed = path)]`) or registered at runtime with
+/// `FormHandle::computed`. `FormHandle` recomputes the value only when one of
+/// the declared inputs changes, or any other field when none are declared.
+///
+/// ```rust,ignore
+/// let total = ComputedField::new("total", |order: &Order| {
+///     let subtotal: f64 = order.items.iter().map(|i| i.qty as f64 * i.price).sum();
+///     FieldValue::Number(subtotal * (1.0 + order.tax_rate))
+/// })
+/// .depends_on(["items", "tax_rate"])
+/// .validate_with(Validator::Max(10_000.0));
+/// ```
+pub struct ComputedField<T> {
+    name: String,
+    compute: ComputeFn<T>,
+    inputs: Vec<String>,
+    validators: Vec<Validator>,
+    in_payload: bool,
+}
+
+impl<T: Form> ComputedField<T> {
+    pub fn new<F>(name: impl Into<String>, compute: F) -> Self
+    where
+        F: Fn(&T) -> FieldValue + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            compute: Arc::new(compute),
+            inputs: Vec::new(),
+            validators: Vec::new(),
+            in_payload: true,
+        }
+    }
+
+    /// Field paths the value is derived from, such as `items` or `address.country`
+    pub fn depends_on<I, S>(mut self, inputs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inputs.extend(inputs.into_iter().map(Into::into));
+        self
+    }
+
+    /// Check the computed value with `validator` when the form is validated
+    pub fn validate_with(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Whether the value is part of the submitted payload, which it is by default
+    pub fn include_in_payload(mut self, include: bool) -> Self {
+        self.in_payload = include;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    pub fn validators(&self) -> &[Validator] {
+        &self.validators
+    }
+
+    pub fn is_in_payload(&self) -> bool {
+        self.in_payload
+    }
+
+    /// Compute the value for `form`
+    pub fn compute(&self, form: &T) -> FieldValue {
+        (self.compute)(form)
+    }
+
+    /// Current values of the inputs, used to decide whether to recompute
+    ///
+    /// Without declared inputs every field except this one counts as an input.
+    pub fn input_values(&self, form: &T) -> Vec<FieldValue> {
+        if self.inputs.is_empty() {
+            T::field_metadata()
+                .iter()
+                .filter(|field| field.name != self.name)
+                .map(|field| form.get_field_value(&field.name))
+                .collect()
+        } else {
+            self.inputs
+                .iter()
+                .map(|input| get_form_path_value(form, input).unwrap_or_default())
+                .collect()
+        }
+    }
+}
+
+// Implemented by hand so that `T` needs no `Clone` or `Debug` of its own
+impl<T> Clone for ComputedField<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            compute: self.compute.clone(),
+            inputs: self.inputs.clone(),
+            validators: self.validators.clone(),
+            in_payload: self.in_payload,
+        }
+    }
+}
+
+impl<T> fmt::Debug for ComputedField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComputedField")
+            .field("name", &self.name)
+            .field("inputs", &self.inputs)
+            .field("validators", &self.validators)
+            .field("in_payload", &self.in_payload)
+            .finish()
+    }
+}
@@ -0,0 +1,469 @@
+use crate::core::traits::{FieldMetadata, Form};
+use crate::core::types::{FieldType, FieldValue, FileData, NumberType};
+use crate::validation::ValidationErrors;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+
+/// Conversion between a Rust field type and its dynamic `FieldValue` representation
+///
+/// The `Form` derive relies on this trait to describe, read, write and validate
+/// each struct field. Types deriving `Form` implement it as `FieldType::Nested`,
+/// which is what allows forms to be composed from other forms.
+pub trait FormValue: Sized {
+    /// Field type reported in the form metadata
+    fn field_type() -> FieldType;
+
+    /// Metadata of the nested fields, empty for scalar values
+    fn nested_fields() -> Vec<FieldMetadata> {
+        Vec::new()
+    }
+
+    /// Value used when a new form is created
+    fn default_value() -> Self;
+
+    /// Convert the value into a `FieldValue`
+    fn to_field_value(&self) -> FieldValue;
+
+    /// Build the value from a `FieldValue`
+    fn from_field_value(value: FieldValue) -> Result<Self, String>;
+
+    /// Validate nested values, reporting errors under `path`
+    fn validate_at(&self, _path: &str, _errors: &mut ValidationErrors) {}
+}
+
+/// Join a field name onto a parent path, e.g. `contacts[1]` + `email`
+pub fn field_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+/// `name` attribute for the control of the field at `path`
+///
+/// Uses the bracket notation that servers decode into nested objects and
+/// arrays, e.g. `contacts[1].email` becomes `contacts[1][email]`.
+pub fn input_name(path: &str) -> String {
+    let mut segments = parse_field_path(path).into_iter();
+    let mut name = match segments.next() {
+        Some(FieldPathSegment::Key(key)) => key,
+        Some(FieldPathSegment::Index(index)) => index.to_string(),
+        None => return String::new(),
+    };
+    for segment in segments {
+        match segment {
+            FieldPathSegment::Key(key) => name.push_str(&format!("[{}]", key)),
+            FieldPathSegment::Index(index) => name.push_str(&format!("[{}]", index)),
+        }
+    }
+    name
+}
+
+/// One step of a field path: an object key or an array index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Split a path such as `contacts[1].email` into its segments
+pub fn parse_field_path(path: &str) -> Vec<FieldPathSegment> {
+    let mut segments = Vec::new();
+    for part in path.split('.').filter(|part| !part.is_empty()) {
+        let (key, mut rest) = match part.find('[') {
+            Some(start) => part.split_at(start),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(FieldPathSegment::Key(key.to_string()));
+        }
+        while let Some(end) = rest.find(']') {
+            match rest[1..end].parse() {
+                Ok(index) => segments.push(FieldPathSegment::Index(index)),
+                Err(_) => segments.push(FieldPathSegment::Key(rest[1..end].to_string())),
+            }
+            rest = &rest[end + 1..];
+        }
+    }
+    segments
+}
+
+impl FieldValue {
+    /// Value found by following `segments` into nested objects and arrays
+    pub fn get_path(&self, segments: &[FieldPathSegment]) -> Option<&FieldValue> {
+        segments
+            .iter()
+            .try_fold(self, |value, segment| match (value, segment) {
+                (FieldValue::Object(object), FieldPathSegment::Key(key)) => object.get(key),
+                (FieldValue::Array(items), FieldPathSegment::Index(index)) => items.get(*index),
+                _ => None,
+            })
+    }
+
+    /// Replace the value at `segments`, returning whether the path existed
+    ///
+    /// Missing object keys are created, but arrays are never extended.
+    pub fn set_path(&mut self, segments: &[FieldPathSegment], new_value: FieldValue) -> bool {
+        let Some((segment, rest)) = segments.split_first() else {
+            *self = new_value;
+            return true;
+        };
+        let child = match (self, segment) {
+            (FieldValue::Object(object), FieldPathSegment::Key(key)) => {
+                object.entry(key.clone()).or_insert(FieldValue::Null)
+            }
+            (FieldValue::Array(items), FieldPathSegment::Index(index)) => {
+                match items.get_mut(*index) {
+                    Some(item) => item,
+                    None => return false,
+                }
+            }
+            _ => return false,
+        };
+        child.set_path(rest, new_value)
+    }
+}
+
+/// Read a form value by path, e.g. `billing_address.city` or `contacts[1].email`
+pub fn get_form_path_value<T: Form>(form: &T, path: &str) -> Option<FieldValue> {
+    let segments = parse_field_path(path);
+    let (FieldPathSegment::Key(field_name), rest) = segments.split_first()? else {
+        return None;
+    };
+    T::field_metadata().iter().find(|f| &f.name == field_name)?;
+    form.get_field_value(field_name).get_path(rest).cloned()
+}
+
+/// Read a value by path from an object of field values, such as a nested form
+pub fn get_object_path_value(
+    values: &HashMap<String, FieldValue>,
+    path: &str,
+) -> Option<FieldValue> {
+    let segments = parse_field_path(path);
+    let (FieldPathSegment::Key(field_name), rest) = segments.split_first()? else {
+        return None;
+    };
+    values.get(field_name)?.get_path(rest).cloned()
+}
+
+/// Write a form value by path, rewriting the enclosing top-level field
+pub fn set_form_path_value<T: Form>(form: &mut T, path: &str, value: FieldValue) -> bool {
+    let segments = parse_field_path(path);
+    let Some((FieldPathSegment::Key(field_name), rest)) = segments.split_first() else {
+        return false;
+    };
+    let mut field_value = form.get_field_value(field_name);
+    if !field_value.set_path(rest, value) {
+        return false;
+    }
+    form.set_field_value(field_name, field_value);
+    true
+}
+
+fn type_mismatch(expected: &str, value: &FieldValue) -> String {
+    format!("Expected {}, got {:?}", expected, value)
+}
+
+impl FormValue for String {
+    fn field_type() -> FieldType {
+        FieldType::Text
+    }
+
+    fn default_value() -> Self {
+        String::new()
+    }
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::String(self.clone())
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::String(s) => Ok(s),
+            FieldValue::Null => Ok(String::new()),
+            FieldValue::Number(_)
+            | FieldValue::Integer(_)
+            | FieldValue::Boolean(_)
+            | FieldValue::Date(_)
+            | FieldValue::DateTime(_) => Ok(value.to_string()),
+            other => Err(type_mismatch("a string", &other)),
+        }
+    }
+}
+
+impl FormValue for bool {
+    fn field_type() -> FieldType {
+        FieldType::Boolean
+    }
+
+    fn default_value() -> Self {
+        false
+    }
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Boolean(*self)
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::Boolean(b) => Ok(b),
+            FieldValue::Null => Ok(false),
+            FieldValue::String(s) => match s.as_str() {
+                "true" | "on" | "1" => Ok(true),
+                "false" | "off" | "0" | "" => Ok(false),
+                _ => Err(format!("Invalid boolean value: {}", s)),
+            },
+            other => Err(type_mismatch("a boolean", &other)),
+        }
+    }
+}
+
+macro_rules! impl_integer_form_value {
+    ($($ty:ty),*) => {
+        $(
+            impl FormValue for $ty {
+                fn field_type() -> FieldType {
+                    FieldType::Number(NumberType {
+                        min: None,
+                        max: None,
+                        step: Some(1.0),
+                    })
+                }
+
+                fn default_value() -> Self {
+                    0
+                }
+
+                fn to_field_value(&self) -> FieldValue {
+                    // Values beyond `i64` only fit a float
+                    i64::try_from(*self)
+                        .map(FieldValue::Integer)
+                        .unwrap_or(FieldValue::Number(*self as f64))
+                }
+
+                fn from_field_value(value: FieldValue) -> Result<Self, String> {
+                    let out_of_range = || format!("Value out of range for {}", stringify!($ty));
+                    match value {
+                        FieldValue::Integer(i) => <$ty>::try_from(i).map_err(|_| out_of_range()),
+                        FieldValue::Number(n) if n.fract() == 0.0 => {
+                            if n >= <$ty>::MIN as f64 && n <= <$ty>::MAX as f64 {
+                                Ok(n as $ty)
+                            } else {
+                                Err(out_of_range())
+                            }
+                        }
+                        FieldValue::String(s) => s
+                            .trim()
+                            .parse::<$ty>()
+                            .map_err(|_| format!("Invalid integer: {}", s)),
+                        other => Err(type_mismatch("an integer", &other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_form_value!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_float_form_value {
+    ($($ty:ty),*) => {
+        $(
+            impl FormValue for $ty {
+                fn field_type() -> FieldType {
+                    FieldType::Number(NumberType {
+                        min: None,
+                        max: None,
+                        step: None,
+                    })
+                }
+
+                fn default_value() -> Self {
+                    0.0
+                }
+
+                fn to_field_value(&self) -> FieldValue {
+                    FieldValue::Number(*self as f64)
+                }
+
+                fn from_field_value(value: FieldValue) -> Result<Self, String> {
+                    match value {
+                        FieldValue::Number(n) => Ok(n as $ty),
+                        FieldValue::Integer(i) => Ok(i as $ty),
+                        FieldValue::String(s) => s
+                            .trim()
+                            .parse::<$ty>()
+                            .map_err(|_| format!("Invalid number: {}", s)),
+                        other => Err(type_mismatch("a number", &other)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_float_form_value!(f32, f64);
+
+impl FormValue for NaiveDate {
+    fn field_type() -> FieldType {
+        FieldType::Date
+    }
+
+    fn default_value() -> Self {
+        NaiveDate::default()
+    }
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Date(*self)
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::Date(d) => Ok(d),
+            FieldValue::DateTime(dt) => Ok(dt.date_naive()),
+            FieldValue::String(s) => NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date: {}", s)),
+            other => Err(type_mismatch("a date", &other)),
+        }
+    }
+}
+
+impl FormValue for DateTime<Utc> {
+    fn field_type() -> FieldType {
+        FieldType::DateTime
+    }
+
+    fn default_value() -> Self {
+        DateTime::<Utc>::default()
+    }
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::DateTime(*self)
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::DateTime(dt) => Ok(dt),
+            FieldValue::String(s) => DateTime::parse_from_rfc3339(s.trim())
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|_| format!("Invalid date-time: {}", s)),
+            other => Err(type_mismatch("a date-time", &other)),
+        }
+    }
+}
+
+impl FormValue for FileData {
+    fn field_type() -> FieldType {
+        FieldType::File(crate::core::types::FileConstraints {
+            max_size: None,
+            accept: Vec::new(),
+            multiple: false,
+        })
+    }
+
+    fn default_value() -> Self {
+        FileData {
+            name: String::new(),
+            size: 0,
+            mime_type: String::new(),
+            data: Vec::new(),
+        }
+    }
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::File(self.clone())
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::File(file) => Ok(file),
+            other => Err(type_mismatch("a file", &other)),
+        }
+    }
+}
+
+impl FormValue for FieldValue {
+    fn field_type() -> FieldType {
+        FieldType::Text
+    }
+
+    fn default_value() -> Self {
+        FieldValue::Null
+    }
+
+    fn to_field_value(&self) -> FieldValue {
+        self.clone()
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        Ok(value)
+    }
+}
+
+impl<T: FormValue> FormValue for Option<T> {
+    fn field_type() -> FieldType {
+        T::field_type()
+    }
+
+    fn nested_fields() -> Vec<FieldMetadata> {
+        T::nested_fields()
+    }
+
+    fn default_value() -> Self {
+        None
+    }
+
+    fn to_field_value(&self) -> FieldValue {
+        match self {
+            Some(value) => value.to_field_value(),
+            None => FieldValue::Null,
+        }
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::Null => Ok(None),
+            FieldValue::String(ref s) if s.is_empty() => Ok(None),
+            other => T::from_field_value(other).map(Some),
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors) {
+        if let Some(value) = self {
+            value.validate_at(path, errors);
+        }
+    }
+}
+
+impl<T: FormValue> FormValue for Vec<T> {
+    fn field_type() -> FieldType {
+        FieldType::Array(Box::new(T::field_type()))
+    }
+
+    fn nested_fields() -> Vec<FieldMetadata> {
+        T::nested_fields()
+    }
+
+    fn default_value() -> Self {
+        Vec::new()
+    }
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Array(self.iter().map(FormValue::to_field_value).collect())
+    }
+
+    fn from_field_value(value: FieldValue) -> Result<Self, String> {
+        match value {
+            FieldValue::Array(items) => items.into_iter().map(T::from_field_value).collect(),
+            FieldValue::Null => Ok(Vec::new()),
+            other => Err(type_mismatch("an array", &other)),
+        }
+    }
+
+    fn validate_at(&self, path: &str, errors: &mut ValidationErrors) {
+        for (index, item) in self.iter().enumerate() {
+            item.validate_at(&format!("{}[{}]", path, index), errors);
+        }
+    }
+}
@@ -0,0 +1,496 @@
+use crate::core::sensitive::{blank_json, json_path_mut, json_sensitive_paths, FieldEncryptor};
+use crate::core::traits::Form;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+type Migration = Arc<dyn Fn(Value) -> Result<Value, String> + Send + Sync>;
+
+/// What to do with a saved draft that cannot be upgraded to the current form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DraftFallback {
+    /// Delete the draft and start from the form's initial values
+    #[default]
+    Discard,
+    /// Restore the fields that still fit and leave the rest at their defaults
+    Partial,
+    /// Keep the draft and leave the decision to the user
+    Prompt,
+}
+
+/// Schema version of saved drafts and the migrations that upgrade old ones
+///
+/// Drafts are saved with the version and the `FormSchema::fingerprint` of the
+/// form. A draft of an older version is passed through the migration of each
+/// version in turn, so a migration registered for version 1 upgrades version
+/// 1 drafts to version 2:
+///
+/// ```rust,ignore
+/// let versioning = DraftVersioning::new(2)
+///     .migrate(1, |mut draft| {
+///         // `name` was split into `first_name` and `last_name`
+///         let name = draft["name"].take();
+///         draft["first_name"] = name;
+///         draft["last_name"] = "".into();
+///         Ok(draft)
+///     })
+///     .fallback(DraftFallback::Partial);
+/// let storage = LocalStoragePersistence::new("signup").with_versioning(versioning);
+/// ```
+#[derive(Clone, Default)]
+pub struct DraftVersioning {
+    version: u32,
+    migrations: BTreeMap<u32, Migration>,
+    fallback: DraftFallback,
+}
+
+impl DraftVersioning {
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            ..Default::default()
+        }
+    }
+
+    /// Upgrade the values of a `from_version` draft to `from_version + 1`
+    pub fn migrate<F>(mut self, from_version: u32, migration: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        self.migrations.insert(from_version, Arc::new(migration));
+        self
+    }
+
+    pub fn fallback(mut self, fallback: DraftFallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn fallback_policy(&self) -> DraftFallback {
+        self.fallback
+    }
+
+    /// Run the migrations from `version` up to the current version
+    fn upgrade(&self, version: u32, mut values: Value) -> Result<Value, String> {
+        if version > self.version {
+            return Err(format!(
+                "Draft version {} is newer than the form's version {}",
+                version, self.version
+            ));
+        }
+        for step in version..self.version {
+            let migration = self
+                .migrations
+                .get(&step)
+                .ok_or_else(|| format!("No migration from draft version {}", step))?;
+            values = migration(values)
+                .map_err(|e| format!("Migration from draft version {} failed: {}", step, e))?;
+        }
+        Ok(values)
+    }
+}
+
+impl fmt::Debug for DraftVersioning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DraftVersioning")
+            .field("version", &self.version)
+            .field("migrations", &self.migrations.keys().collect::<Vec<_>>())
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+/// Decides which of the auto-saves scheduled after edits may still run
+///
+/// Every edit schedules a save and only the one for the latest edit runs.
+/// Clearing the draft cancels the saves already scheduled, so a draft
+/// cleared on submit is not written back a moment later.
+#[derive(Debug, Clone, Default)]
+pub struct AutoSaveSchedule {
+    latest: Arc<AtomicU64>,
+}
+
+impl AutoSaveSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule a save for a new edit, returning its ticket
+    pub fn schedule(&self) -> u64 {
+        self.latest.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Whether the save with `ticket` should still run
+    pub fn is_due(&self, ticket: u64) -> bool {
+        self.latest.load(Ordering::SeqCst) == ticket
+    }
+
+    /// Cancel every save scheduled so far
+    pub fn cancel(&self) {
+        self.latest.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// A saved draft that could not be restored, kept for the user to decide on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaleDraft {
+    pub version: u32,
+    pub fingerprint: Option<String>,
+    pub values: Value,
+    /// Why the draft could not be restored as is
+    pub reason: String,
+}
+
+impl StaleDraft {
+    /// The fields of the draft that still fit `T`, over its default values
+    ///
+    /// Returns the restored form and the names of the fields left out.
+    pub fn partial<T: Form>(&self) -> (T, Vec<String>) {
+        restore_partial(&self.values)
+    }
+}
+
+/// Outcome of loading a saved draft
+#[derive(Debug, Clone, PartialEq)]
+pub enum DraftLoad<T> {
+    /// Nothing was saved
+    Empty,
+    Restored(T),
+    /// The draft was upgraded from an older version
+    Migrated {
+        values: T,
+        from_version: u32,
+    },
+    /// Only some fields could be restored, the rest keep their defaults
+    Partial {
+        values: T,
+        skipped: Vec<String>,
+    },
+    /// The draft did not fit the form and was deleted
+    Discarded {
+        reason: String,
+    },
+    /// The draft did not fit the form and awaits a decision
+    Stale(StaleDraft),
+}
+
+impl<T> DraftLoad<T> {
+    /// The restored values, if any
+    pub fn into_values(self) -> Option<T> {
+        match self {
+            Self::Restored(values)
+            | Self::Migrated { values, .. }
+            | Self::Partial { values, .. } => Some(values),
+            Self::Empty | Self::Discarded { .. } | Self::Stale(_) => None,
+        }
+    }
+}
+
+/// What is known about a saved draft without restoring it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DraftInfo {
+    /// Storage key the draft is saved under
+    pub key: String,
+    /// Name of the form the draft was saved from
+    pub form_name: Option<String>,
+    /// Number of fields saved in the draft
+    pub field_count: usize,
+    pub schema_version: u32,
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the draft was last saved, i.e. last edited
+    pub updated_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl DraftInfo {
+    /// Whether the draft outlived its time to live at `now`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// What is known about the draft `json` saved under `key`, if it is one
+    pub(crate) fn read(key: &str, json: &str) -> Option<Self> {
+        let saved: Value = serde_json::from_str(json).ok()?;
+        Some(
+            match serde_json::from_value::<DraftEnvelope>(saved.clone()) {
+                Ok(envelope) => Self {
+                    key: key.to_string(),
+                    form_name: envelope.form_name,
+                    field_count: field_count(&envelope.values),
+                    schema_version: envelope.schema_version,
+                    created_at: envelope.created_at,
+                    updated_at: envelope.updated_at,
+                    expires_at: envelope.expires_at,
+                },
+                // Drafts saved before versioning hold the bare values
+                Err(_) if saved.is_object() => Self {
+                    key: key.to_string(),
+                    form_name: None,
+                    field_count: field_count(&saved),
+                    schema_version: 0,
+                    created_at: None,
+                    updated_at: None,
+                    expires_at: None,
+                },
+                Err(_) => return None,
+            },
+        )
+    }
+}
+
+fn field_count(values: &Value) -> usize {
+    values.as_object().map_or(0, |values| values.len())
+}
+
+/// How drafts are stored: the values along with the schema they were saved for
+#[derive(Serialize, Deserialize)]
+struct DraftEnvelope {
+    schema_version: u32,
+    fingerprint: String,
+    values: Value,
+    /// Encrypted values of sensitive fields by path, blanked in `values`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    sealed: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    form_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// How a persistence backend turns forms into drafts and back
+#[derive(Clone, Default)]
+pub(crate) struct DraftCodec {
+    /// Set by the app; without it drafts are restored whenever they deserialize
+    pub(crate) versioning: Option<DraftVersioning>,
+    pub(crate) encryptor: Option<Arc<dyn FieldEncryptor>>,
+    /// How long a draft is kept after it was last saved
+    pub(crate) ttl: Option<Duration>,
+}
+
+impl fmt::Debug for DraftCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DraftCodec")
+            .field("versioning", &self.versioning)
+            .field("encrypted", &self.encryptor.is_some())
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl DraftCodec {
+    /// Serialize `form` as a draft of the current version
+    ///
+    /// Sensitive fields are blanked, and kept encrypted when there is an
+    /// encryptor. `previous` is the draft being replaced, if any, whose
+    /// creation time is carried over.
+    pub(crate) fn encode<T: Form>(
+        &self,
+        form: &T,
+        previous: Option<&str>,
+    ) -> Result<String, String> {
+        let mut values = serde_json::to_value(form)
+            .map_err(|e| format!("Failed to serialize form data: {}", e))?;
+        let mut sealed = BTreeMap::new();
+        for path in json_sensitive_paths(&T::field_metadata(), &values) {
+            let Some(value) = json_path_mut(&mut values, &path) else {
+                continue;
+            };
+            if let Some(encryptor) = &self.encryptor {
+                let ciphertext = encryptor
+                    .encrypt(&value.to_string())
+                    .map_err(|e| format!("Failed to encrypt {}: {}", path, e))?;
+                sealed.insert(path, ciphertext);
+            }
+            *value = blank_json(value);
+        }
+
+        let now = Utc::now();
+        let created_at = previous
+            .and_then(|previous| serde_json::from_str::<DraftEnvelope>(previous).ok())
+            .and_then(|previous| previous.created_at)
+            .unwrap_or(now);
+        let expires_at = self
+            .ttl
+            .and_then(|ttl| chrono::Duration::from_std(ttl).ok())
+            .and_then(|ttl| now.checked_add_signed(ttl));
+        let schema = T::schema();
+        let envelope = DraftEnvelope {
+            schema_version: self.versioning.as_ref().map_or(0, |v| v.version),
+            fingerprint: schema.fingerprint(),
+            values,
+            sealed,
+            form_name: Some(schema.name),
+            created_at: Some(created_at),
+            updated_at: Some(now),
+            expires_at,
+        };
+        serde_json::to_string(&envelope)
+            .map_err(|e| format!("Failed to serialize form data: {}", e))
+    }
+
+    /// Restore a saved draft, upgrading it or applying the fallback policy
+    ///
+    /// Only text that is not JSON at all is an error. Drafts saved before
+    /// versioning was introduced count as version 0. Expired drafts are
+    /// discarded. Sensitive values that cannot be decrypted, e.g. after a key
+    /// change, stay blank. When the app set up versioning, a draft of the
+    /// current version saved from a form of another shape does not fit
+    /// either.
+    pub(crate) fn decode<T: Form>(&self, json: &str) -> Result<DraftLoad<T>, String> {
+        let unversioned = DraftVersioning::default();
+        let versioning = self.versioning.as_ref().unwrap_or(&unversioned);
+        let saved: Value = serde_json::from_str(json)
+            .map_err(|e| format!("Failed to restore saved form data: {}", e))?;
+        let (version, fingerprint, mut values, sealed) =
+            match serde_json::from_value::<DraftEnvelope>(saved.clone()) {
+                Ok(envelope) => {
+                    if let Some(expires_at) = envelope.expires_at.filter(|at| *at <= Utc::now()) {
+                        return Ok(DraftLoad::Discarded {
+                            reason: format!("Draft expired at {}", expires_at.to_rfc3339()),
+                        });
+                    }
+                    (
+                        envelope.schema_version,
+                        Some(envelope.fingerprint),
+                        envelope.values,
+                        envelope.sealed,
+                    )
+                }
+                Err(_) => (0, None, saved, BTreeMap::new()),
+            };
+
+        if let Some(encryptor) = &self.encryptor {
+            for (path, ciphertext) in sealed {
+                let plaintext = encryptor.decrypt(&ciphertext).and_then(|plaintext| {
+                    serde_json::from_str(&plaintext).map_err(|e| e.to_string())
+                });
+                match (plaintext, json_path_mut(&mut values, &path)) {
+                    (Ok(plaintext), Some(value)) => *value = plaintext,
+                    (Err(error), _) => log::warn!("Failed to decrypt {}: {}", path, error),
+                    (Ok(_), None) => {}
+                }
+            }
+        }
+
+        // Fields that survive the migrations are worth restoring in part even
+        // when the upgraded draft as a whole does not fit
+        let (upgraded, restored) = match versioning.upgrade(version, values.clone()) {
+            Ok(upgraded) => {
+                let restored = serde_json::from_value::<T>(upgraded.clone())
+                    .map_err(|e| format!("Saved form data no longer fits the form: {}", e));
+                (upgraded, restored)
+            }
+            Err(reason) => (values.clone(), Err(reason)),
+        };
+        // A form changed without a new version no longer matches its drafts,
+        // even when they happen to deserialize
+        let restored = restored.and_then(|form| match &fingerprint {
+            Some(saved)
+                if self.versioning.is_some()
+                    && version == versioning.version
+                    && !saved.is_empty()
+                    && *saved != T::schema().fingerprint() =>
+            {
+                Err("The form changed since the draft was saved".to_string())
+            }
+            _ => Ok(form),
+        });
+
+        match restored {
+            Ok(form) if version == versioning.version => Ok(DraftLoad::Restored(form)),
+            Ok(form) => Ok(DraftLoad::Migrated {
+                values: form,
+                from_version: version,
+            }),
+            Err(reason) => Ok(match versioning.fallback {
+                DraftFallback::Discard => DraftLoad::Discarded { reason },
+                DraftFallback::Partial => {
+                    let (form, skipped) = restore_partial(&upgraded);
+                    DraftLoad::Partial {
+                        values: form,
+                        skipped,
+                    }
+                }
+                DraftFallback::Prompt => DraftLoad::Stale(StaleDraft {
+                    version,
+                    fingerprint,
+                    values,
+                    reason,
+                }),
+            }),
+        }
+    }
+}
+
+/// Copy the fields of `values` that still fit `T` onto its default values
+///
+/// Each saved field is checked once against the shape of its default, then
+/// the form is read in one go. Only values of the right shape the form still
+/// rejects, like a number out of range, are tried one by one.
+fn restore_partial<T: Form>(values: &Value) -> (T, Vec<String>) {
+    let defaults = T::default_values();
+    let (Ok(Value::Object(mut current)), Value::Object(saved)) =
+        (serde_json::to_value(&defaults), values)
+    else {
+        return (defaults, Vec::new());
+    };
+
+    let mut skipped = Vec::new();
+    let mut fitting = Vec::new();
+    for (name, value) in saved {
+        match current.get_mut(name) {
+            Some(default) if same_shape(default, value) => {
+                fitting.push((name, std::mem::replace(default, value.clone())));
+            }
+            _ => skipped.push(name.clone()),
+        }
+    }
+
+    let mut merged = Value::Object(current);
+    if T::deserialize(&merged).is_err() {
+        // Start over from the defaults and add the saved values one by one
+        let saved: Vec<(&String, Value)> = fitting
+            .into_iter()
+            .map(|(name, default)| (name, std::mem::replace(&mut merged[name.as_str()], default)))
+            .collect();
+        for (name, value) in saved {
+            let default = std::mem::replace(&mut merged[name.as_str()], value);
+            if T::deserialize(&merged).is_err() {
+                merged[name.as_str()] = default;
+                skipped.push(name.clone());
+            }
+        }
+    }
+    skipped.sort();
+
+    let form = T::deserialize(&merged).unwrap_or(defaults);
+    (form, skipped)
+}
+
+/// Whether `saved` has the JSON type of `default`, down to nested fields
+fn same_shape(default: &Value, saved: &Value) -> bool {
+    match (default, saved) {
+        // An optional field, or a value of any type
+        (Value::Null, _) => true,
+        (Value::Bool(_), Value::Bool(_)) | (Value::String(_), Value::String(_)) => true,
+        (Value::Number(_), Value::Number(_)) => true,
+        (Value::Array(_), Value::Array(_)) => true,
+        (Value::Object(default), Value::Object(saved)) => default
+            .iter()
+            .all(|(name, value)| saved.get(name).is_none_or(|saved| same_shape(value, saved))),
+        _ => false,
+    }
+}
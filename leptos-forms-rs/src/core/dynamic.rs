@@ -0,0 +1,236 @@
+use crate::core::traits::{FieldMetadata, Form, FormSchema};
+use crate::core::types::{FieldType, FieldValue};
+use crate::validation::{validate_values, ValidationErrors};
+use leptos::prelude::{provide_context, use_context, Owner};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// The schema of the dynamic forms of slot `S`, as context
+struct SlotSchema<S> {
+    schema: Arc<FormSchema>,
+    slot: PhantomData<fn() -> S>,
+}
+
+impl<S> Clone for SlotSchema<S> {
+    fn clone(&self) -> Self {
+        Self {
+            schema: self.schema.clone(),
+            slot: PhantomData,
+        }
+    }
+}
+
+/// A form whose fields come from a `FormSchema` known only at runtime
+///
+/// Values are kept in a `HashMap<String, FieldValue>` and serialize as a plain
+/// JSON object. Each form holds the schema it was created from. Because `Form`
+/// also describes fields through associated functions, the schema is
+/// provided as context too, to the component creating the form and its
+/// children, so forms rendered for different users or requests never see
+/// each other's schema. Components showing several dynamic forms at once give
+/// each its own slot marker type `S`.
+///
+/// ```rust,ignore
+/// let schema: FormSchema = serde_json::from_str(&server_json)?;
+/// let form = use_form(DynamicForm::from_schema(schema));
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DynamicForm<S: 'static = ()> {
+    values: HashMap<String, FieldValue>,
+    #[serde(skip)]
+    schema: Option<Arc<FormSchema>>,
+    #[serde(skip)]
+    slot: PhantomData<S>,
+}
+
+impl<S: 'static> DynamicForm<S> {
+    /// Provide `schema` for this slot to the current component and its
+    /// children
+    ///
+    /// Server handlers reading a dynamic form's submission call this within
+    /// the reactive owner of the request.
+    pub fn provide_schema(schema: FormSchema) {
+        Self::provide(Arc::new(schema));
+    }
+
+    /// Schema provided for this slot in the current scope, if any
+    pub fn schema_in_scope() -> Option<Arc<FormSchema>> {
+        use_context::<SlotSchema<S>>().map(|slot| slot.schema)
+    }
+
+    /// Create a form holding the default values of `schema`
+    ///
+    /// The schema is provided for this slot to the current component and its
+    /// children as well.
+    pub fn from_schema(schema: FormSchema) -> Self {
+        let schema = Arc::new(schema);
+        Self::provide(schema.clone());
+        Self {
+            values: default_object(&schema.field_metadata),
+            schema: Some(schema),
+            slot: PhantomData,
+        }
+    }
+
+    /// Create a form from existing values, keeping only fields in the schema
+    pub fn from_values(values: HashMap<String, FieldValue>) -> Self {
+        let mut form = Self::default_values();
+        for (name, value) in values {
+            form.set_field_value(&name, value);
+        }
+        form
+    }
+
+    /// Current field values
+    pub fn values(&self) -> &HashMap<String, FieldValue> {
+        &self.values
+    }
+
+    /// Consume the form and return its field values
+    pub fn into_values(self) -> HashMap<String, FieldValue> {
+        self.values
+    }
+
+    fn provide(schema: Arc<FormSchema>) {
+        // Without an owner there is no scope to provide it to
+        if Owner::current().is_some() {
+            provide_context(SlotSchema::<S> {
+                schema,
+                slot: PhantomData,
+            });
+        }
+    }
+
+    /// The form's own schema, or the one in scope for forms read back from
+    /// JSON
+    fn own_schema(&self) -> Option<Arc<FormSchema>> {
+        self.schema.clone().or_else(Self::schema_in_scope)
+    }
+
+    fn fields(&self) -> Vec<FieldMetadata> {
+        self.own_schema()
+            .map(|schema| schema.field_metadata.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Initial value for a field without an explicit default
+pub(crate) fn empty_value(field: &FieldMetadata) -> FieldValue {
+    match &field.field_type {
+        FieldType::Text
+        | FieldType::Email
+        | FieldType::Password
+        | FieldType::RichText
+        | FieldType::Markdown
+        | FieldType::Code
+        | FieldType::Select(_) => FieldValue::String(String::new()),
+        FieldType::Boolean => FieldValue::Boolean(false),
+        FieldType::MultiSelect(_) | FieldType::Array(_) => FieldValue::Array(Vec::new()),
+        FieldType::Nested(_) => FieldValue::Object(default_object(&field.nested_fields)),
+        FieldType::Number(_) | FieldType::Date | FieldType::DateTime | FieldType::File(_) => {
+            FieldValue::Null
+        }
+    }
+}
+
+fn default_object(fields: &[FieldMetadata]) -> HashMap<String, FieldValue> {
+    fields
+        .iter()
+        .map(|field| {
+            let value = field
+                .default_value
+                .clone()
+                .unwrap_or_else(|| empty_value(field));
+            (field.name.clone(), value)
+        })
+        .collect()
+}
+
+impl<S: 'static> Form for DynamicForm<S> {
+    fn field_metadata() -> Vec<FieldMetadata> {
+        Self::schema_in_scope()
+            .map(|schema| schema.field_metadata.clone())
+            .unwrap_or_default()
+    }
+
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        validate_values(&self.fields(), &self.values, "", &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn default_values() -> Self {
+        let schema = Self::schema_in_scope();
+        Self {
+            values: schema
+                .as_ref()
+                .map(|schema| default_object(&schema.field_metadata))
+                .unwrap_or_default(),
+            schema,
+            slot: PhantomData,
+        }
+    }
+
+    fn schema() -> FormSchema {
+        Self::schema_in_scope()
+            .map(|schema| schema.as_ref().clone())
+            .unwrap_or_else(|| FormSchema {
+                name: "DynamicForm".to_string(),
+                field_metadata: Vec::new(),
+            })
+    }
+
+    fn get_field_value(&self, field_name: &str) -> FieldValue {
+        self.values
+            .get(field_name)
+            .cloned()
+            .unwrap_or(FieldValue::Null)
+    }
+
+    fn set_field_value(&mut self, field_name: &str, value: FieldValue) {
+        let is_known = self.fields().iter().any(|field| field.name == field_name);
+        if is_known {
+            self.values.insert(field_name.to_string(), value);
+        }
+    }
+}
+
+// Implemented by hand so that slot marker types need no derives of their own
+impl<S: 'static> Clone for DynamicForm<S> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            schema: self.schema.clone(),
+            slot: PhantomData,
+        }
+    }
+}
+
+impl<S: 'static> PartialEq for DynamicForm<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<S: 'static> fmt::Debug for DynamicForm<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynamicForm")
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+impl<S: 'static> Default for DynamicForm<S> {
+    fn default() -> Self {
+        Self::default_values()
+    }
+}
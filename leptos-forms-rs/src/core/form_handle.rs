@@ -1,10 +1,22 @@
-use crate::core::traits::Form;
-use crate::core::traits::FormState;
+use crate::core::computed::ComputedField;
+use crate::core::sensitive::without_sensitive_values;
+use crate::core::traits::{FieldMetadata, Form};
+use crate::core::traits::{FormSnapshot, FormState};
 use crate::core::types::FieldValue;
+use crate::core::visibility::{
+    clear_hidden_fields, hidden_field_paths, is_field_enabled, is_field_visible, is_within_path,
+    visible_form_data, HiddenFieldPolicy,
+};
 use crate::error::FormError;
+use crate::submission::protection::BotProtection;
 use crate::validation::{ValidationErrors, Validator};
+use chrono::{DateTime, Utc};
 use leptos::prelude::GetUntracked;
 use leptos::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+type SubmitListener<T> = Arc<dyn Fn(&T) + Send + Sync>;
 
 /// Form handle for managing form state and operations
 pub struct FormHandle<T: Form>
@@ -13,14 +25,267 @@ where
     T: std::marker::Sync,
 {
     state: RwSignal<FormState<T>>,
+    hidden_field_policy: RwSignal<HiddenFieldPolicy>,
+    computed: StoredValue<Vec<ComputedSlot<T>>>,
+    /// Values of runtime computed fields that have no field of their own in `T`
+    computed_values: RwSignal<HashMap<String, FieldValue>>,
+    submit_listeners: StoredValue<Vec<SubmitListener<T>>>,
+    success_listeners: StoredValue<Vec<SubmitListener<T>>>,
+    rendered_at: DateTime<Utc>,
+    render_stamp: StoredValue<Option<String>>,
+}
+
+/// A computed field with the inputs its current value was computed from
+struct ComputedSlot<T> {
+    field: ComputedField<T>,
+    inputs: Option<Vec<FieldValue>>,
+    /// Whether `T` has a field of the same name to write the value to
+    writes_field: bool,
+}
+
+impl<T: Form> ComputedSlot<T> {
+    fn new(field: ComputedField<T>, metadata: &[FieldMetadata]) -> Self {
+        let writes_field = metadata.iter().any(|meta| meta.name == field.name());
+        Self {
+            field,
+            inputs: None,
+            writes_field,
+        }
+    }
 }
 
 impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
     /// Create a new form handle
     pub fn new(form: T) -> Self {
-        let initial_state = FormState::new(form);
-        let state = RwSignal::new(initial_state);
-        Self { state }
+        let computed_fields = T::computed_fields();
+        let computed = if computed_fields.is_empty() {
+            Vec::new()
+        } else {
+            let metadata = T::field_metadata();
+            computed_fields
+                .into_iter()
+                .map(|field| ComputedSlot::new(field, &metadata))
+                .collect()
+        };
+        let handle = Self {
+            state: RwSignal::new(FormState::new(T::default_values())),
+            hidden_field_policy: RwSignal::new(HiddenFieldPolicy::default()),
+            computed: StoredValue::new(computed),
+            computed_values: RwSignal::new(HashMap::new()),
+            submit_listeners: StoredValue::new(Vec::new()),
+            success_listeners: StoredValue::new(Vec::new()),
+            rendered_at: Utc::now(),
+            render_stamp: StoredValue::new(None),
+        };
+
+        let mut values = form;
+        handle.apply_field_rules(&mut values);
+        handle.state.set(FormState::new(values));
+        handle
+    }
+
+    /// Create a form handle from the state the server rendered
+    ///
+    /// On the server, `init` builds the form and its state is serialized
+    /// into the page. While hydrating, the form `init` builds on the client
+    /// takes that state over, so both sides render the same values, errors
+    /// and touched fields while the client keeps its computed fields and
+    /// listeners. Anywhere else `init` simply runs. Sensitive fields are left
+    /// out of the serialized state, so they start blank when hydrating.
+    ///
+    /// ```rust,ignore
+    /// let form = FormHandle::hydrated(move || match submission {
+    ///     Some(submission) => submission.into_form(),
+    ///     None => FormHandle::new(Signup::default_values()),
+    /// });
+    /// ```
+    pub fn hydrated(init: impl FnOnce() -> Self) -> Self {
+        let handle = init();
+        let rendered = handle.clone();
+        let snapshot = SharedValue::new(move || {
+            let mut snapshot = rendered.snapshot();
+            snapshot.values = without_sensitive_values(&snapshot.values);
+            snapshot
+        })
+        .into_inner();
+        let hydrating =
+            Owner::current_shared_context().is_some_and(|context| context.during_hydration());
+        if hydrating {
+            handle.restore_snapshot(snapshot);
+        }
+        handle
+    }
+
+    /// Create a form handle starting from a snapshot of another form's state
+    pub fn from_snapshot(snapshot: FormSnapshot<T>) -> Self {
+        let handle = Self::new(snapshot.values);
+        handle.state.update(|state| {
+            state.errors = snapshot.errors;
+            state.touched = snapshot.touched;
+        });
+        handle
+    }
+
+    /// Take over the values, errors and touched fields of `snapshot`
+    fn restore_snapshot(&self, snapshot: FormSnapshot<T>) {
+        self.state.update(|state| {
+            state.values = snapshot.values;
+            state.errors = snapshot.errors;
+            state.touched = snapshot.touched;
+        });
+    }
+
+    /// When the form was created, which bot checks take as the time it was
+    /// shown
+    pub fn rendered_at(&self) -> DateTime<Utc> {
+        self.rendered_at
+    }
+
+    /// The render time field value submitted with the form for `bots`
+    ///
+    /// This is the stamp shared by [`share_render_stamp`](Self::share_render_stamp)
+    /// when there is one, so the server sees the time it signed.
+    pub fn render_stamp(&self, bots: &BotProtection) -> String {
+        self.render_stamp
+            .get_value()
+            .unwrap_or_else(|| bots.render_stamp(self.rendered_at))
+    }
+
+    /// Stamp the render time with `bots`, on the server while rendering, and
+    /// hand the same stamp to the hydrating client
+    ///
+    /// The `Form` component does this for its `bot_protection`; call it when
+    /// submitting with [`HttpSubmitter`](crate::submission::HttpSubmitter)
+    /// without that component.
+    pub fn share_render_stamp(&self, bots: &BotProtection) -> String {
+        let bots = bots.clone();
+        let rendered_at = self.rendered_at;
+        let stamp = SharedValue::new(move || bots.render_stamp(rendered_at)).into_inner();
+        self.render_stamp.set_value(Some(stamp.clone()));
+        stamp
+    }
+
+    /// The values, errors and touched fields of the form
+    pub fn snapshot(&self) -> FormSnapshot<T> {
+        self.state.get_untracked().into()
+    }
+
+    /// Register a read-only field computed from the form values
+    ///
+    /// The value is written to the field of the same name when `T` has one and
+    /// otherwise kept alongside the form. Without declared inputs it is
+    /// recomputed whenever another field changes; use [`Self::add_computed`]
+    /// with [`ComputedField::depends_on`] to narrow that down.
+    pub fn computed<F>(&self, name: &str, compute: F) -> Memo<FieldValue>
+    where
+        F: Fn(&T) -> FieldValue + Send + Sync + 'static,
+    {
+        self.add_computed(ComputedField::new(name, compute))
+    }
+
+    /// Register a configured computed field, returning its value signal
+    pub fn add_computed(&self, field: ComputedField<T>) -> Memo<FieldValue> {
+        let name = field.name().to_string();
+        self.computed.update_value(|slots| {
+            slots.retain(|slot| slot.field.name() != name);
+            slots.push(ComputedSlot::new(field, &T::field_metadata()));
+        });
+
+        let current_state = self.state.get_untracked();
+        let mut new_values = current_state.values.clone();
+        self.recompute(&mut new_values);
+        if new_values != current_state.values {
+            self.state.set(FormState {
+                values: new_values,
+                ..current_state
+            });
+        }
+        self.computed_value(&name)
+    }
+
+    /// Current value of a computed field
+    pub fn computed_value(&self, name: &str) -> Memo<FieldValue> {
+        let state = self.state;
+        let computed_values = self.computed_values;
+        let name = name.to_string();
+        let in_form = T::field_metadata().iter().any(|field| field.name == name);
+        Memo::new(move |_| {
+            if in_form {
+                state.with(|state| state.values.get_field_value(&name))
+            } else {
+                computed_values.with(|values| values.get(&name).cloned().unwrap_or_default())
+            }
+        })
+    }
+
+    /// Whether `name` is a computed field, which cannot be set directly
+    pub fn is_computed_field(&self, name: &str) -> bool {
+        self.computed
+            .with_value(|slots| slots.iter().any(|slot| slot.field.name() == name))
+    }
+
+    /// Recompute the computed fields whose inputs changed since their last run
+    ///
+    /// Fields run in registration order, so a computed field may read the
+    /// value of one registered before it.
+    fn recompute(&self, values: &mut T) {
+        if self.computed.with_value(Vec::is_empty) {
+            return;
+        }
+        let mut detached = Vec::new();
+
+        self.computed.update_value(|slots| {
+            for slot in slots.iter_mut() {
+                let inputs = slot.field.input_values(values);
+                if slot.inputs.as_ref() == Some(&inputs) {
+                    continue;
+                }
+                let value = slot.field.compute(values);
+                slot.inputs = Some(inputs);
+
+                let name = slot.field.name();
+                if slot.writes_field {
+                    values.set_field_value(name, value);
+                } else {
+                    detached.push((name.to_string(), value));
+                }
+            }
+        });
+
+        if !detached.is_empty() {
+            self.computed_values
+                .update(|values| values.extend(detached));
+        }
+    }
+
+    /// Reset fields to their defaults when their `visible_when` rule hides
+    /// them, instead of keeping their values
+    pub fn set_hidden_field_policy(&self, policy: HiddenFieldPolicy) {
+        self.hidden_field_policy.set(policy);
+        if policy == HiddenFieldPolicy::Clear {
+            let current_state = self.state.get_untracked();
+            let mut new_values = current_state.values.clone();
+            self.apply_field_rules(&mut new_values);
+            if new_values != current_state.values {
+                self.state.set(FormState {
+                    values: new_values,
+                    ..current_state
+                });
+            }
+        }
+    }
+
+    /// How the values of hidden fields are treated
+    pub fn hidden_field_policy(&self) -> HiddenFieldPolicy {
+        self.hidden_field_policy.get_untracked()
+    }
+
+    /// Apply the hidden field policy and computed fields to values about to be stored
+    fn apply_field_rules(&self, values: &mut T) {
+        if self.hidden_field_policy.get_untracked() == HiddenFieldPolicy::Clear {
+            clear_hidden_fields(values);
+        }
+        self.recompute(values);
     }
 
     /// Helper function to update array field and set form state
@@ -36,11 +301,13 @@ impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
             mutator(&mut array);
 
             new_values.set_field_value(field_name, FieldValue::Array(array));
+            self.apply_field_rules(&mut new_values);
 
             let new_state = FormState {
                 values: new_values,
                 is_dirty: true,
                 is_submitting: current_state.is_submitting,
+                touched: current_state.touched,
                 errors: current_state.errors,
             };
 
@@ -83,6 +350,27 @@ impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
         Memo::new(move |_| state.get().is_submitting)
     }
 
+    /// Whether the user has left the field at `path` after focusing it
+    pub fn field_touched(&self, path: &str) -> Memo<bool> {
+        let state = self.state;
+        let path = path.to_string();
+        Memo::new(move |_| state.with(|state| state.is_touched(&path)))
+    }
+
+    /// Whether the user has left any field after focusing it
+    pub fn is_touched(&self) -> Memo<bool> {
+        let state = self.state;
+        Memo::new(move |_| state.with(|state| !state.touched.is_empty()))
+    }
+
+    /// Record that the user has left the field at `path`
+    pub fn mark_touched(&self, path: &str) {
+        let state = self.state.get_untracked();
+        if !state.is_touched(path) {
+            self.state.set(state.mark_touched(path));
+        }
+    }
+
     /// Get a field value
     pub fn get_field_value(&self, field_name: &str) -> Option<FieldValue> {
         let state = self.state.get_untracked();
@@ -100,23 +388,104 @@ impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
     pub fn set_field_value(&self, field_name: &str, value: FieldValue) {
         let current_state = self.state.get_untracked();
 
+        if self.is_computed_field(field_name) {
+            return;
+        }
+
         // Create a new form instance with the updated field
         let mut new_form = current_state.values.clone();
 
         // Update the field using the form's set_field_value method
         new_form.set_field_value(field_name, value);
+        self.apply_field_rules(&mut new_form);
 
         // Create new state with updated form and mark as dirty
         let new_state = FormState {
             values: new_form,
             is_dirty: true,
             is_submitting: current_state.is_submitting,
+            touched: current_state.touched,
             errors: current_state.errors,
         };
 
         self.state.set(new_state);
     }
 
+    /// Replace all values at once, e.g. with a restored draft
+    pub fn set_values(&self, values: T) {
+        let current_state = self.state.get_untracked();
+        let mut new_values = values;
+        self.apply_field_rules(&mut new_values);
+        self.state.set(FormState {
+            values: new_values,
+            is_dirty: true,
+            is_submitting: current_state.is_submitting,
+            touched: current_state.touched,
+            errors: current_state.errors,
+        });
+    }
+
+    /// Get a value by path, e.g. `billing_address.city` or `contacts[1].email`
+    pub fn get_field_path_value(&self, path: &str) -> Option<FieldValue> {
+        let state = self.state.get_untracked();
+        crate::core::get_form_path_value(&state.values, path)
+    }
+
+    /// Set a value by path, updating the nested object or array item it belongs to
+    pub fn set_field_path_value(&self, path: &str, value: FieldValue) {
+        if self.is_computed_field(path) {
+            return;
+        }
+        let current_state = self.state.get_untracked();
+        let mut new_form = current_state.values.clone();
+
+        if crate::core::set_form_path_value(&mut new_form, path, value) {
+            self.apply_field_rules(&mut new_form);
+            self.state.set(FormState {
+                values: new_form,
+                is_dirty: true,
+                is_submitting: current_state.is_submitting,
+                touched: current_state.touched,
+                errors: current_state.errors,
+            });
+        }
+    }
+
+    /// Whether the field at `path` is shown, following `visible_when` rules
+    pub fn field_visible(&self, path: &str) -> Memo<bool> {
+        let state = self.state;
+        let path = path.to_string();
+        Memo::new(move |_| state.with(|state| is_field_visible(&state.values, &path)))
+    }
+
+    /// Whether the field at `path` can be edited, following `enabled_when` rules
+    pub fn field_enabled(&self, path: &str) -> Memo<bool> {
+        let state = self.state;
+        let path = path.to_string();
+        Memo::new(move |_| state.with(|state| is_field_enabled(&state.values, &path)))
+    }
+
+    /// Current values without the hidden fields, as they should be submitted
+    ///
+    /// Computed fields are included unless configured otherwise.
+    pub fn submission_data(&self) -> HashMap<String, FieldValue> {
+        let mut data = self
+            .state
+            .with_untracked(|state| visible_form_data(&state.values));
+        let detached = self.computed_values.get_untracked();
+        self.computed.with_value(|slots| {
+            for slot in slots {
+                let name = slot.field.name();
+                if !slot.field.is_in_payload() {
+                    data.remove(name);
+                } else if let Some(value) = detached.get(name) {
+                    data.insert(name.to_string(), value.clone());
+                }
+            }
+        });
+        data
+    }
+
     /// Validate a specific field
     pub fn validate_field(&self, field_name: &str) -> Result<(), ValidationErrors> {
         let state = self.state.get();
@@ -124,6 +493,10 @@ impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
         let binding = T::schema();
         let metadata = binding.get_field(field_name);
 
+        if !is_field_visible(form_data, field_name) {
+            return Ok(());
+        }
+
         if let Some(field_meta) = metadata {
             let _default_value = FieldValue::String(String::new());
             let field_value = form_data.get_field_value(field_name);
@@ -147,9 +520,14 @@ impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
         let form_data = &state.values;
         let metadata = T::schema().field_metadata.clone();
         let mut errors = ValidationErrors::new();
+        let hidden = hidden_field_paths(form_data);
+        let is_hidden = |path: &str| hidden.iter().any(|prefix| is_within_path(path, prefix));
 
         for field_meta in metadata {
             let field_name = &field_meta.name;
+            if is_hidden(field_name) {
+                continue;
+            }
             let field_value = form_data.get_field_value(field_name);
 
             // Validate field
@@ -160,9 +538,31 @@ impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
             }
         }
 
+        // Validators attached to computed fields
+        let engine = crate::validation::ValidationRuleEngine::new();
+        let detached = self.computed_values.get_untracked();
+        self.computed.with_value(|slots| {
+            for slot in slots {
+                let name = slot.field.name();
+                if is_hidden(name) {
+                    continue;
+                }
+                let value = detached
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| form_data.get_field_value(name));
+                for error in engine.validate_field(name, &value, slot.field.validators()) {
+                    errors.add_field_error(name, error);
+                }
+            }
+        });
+
         // Also call the form's own validate method for custom validation logic
         if let Err(form_errors) = form_data.validate() {
             for (field_name, error_msgs) in form_errors.field_errors {
+                if is_hidden(&field_name) {
+                    continue;
+                }
                 for error_msg in error_msgs {
                     errors.add_field_error(&field_name, error_msg);
                 }
@@ -195,15 +595,63 @@ impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
         let new_state = state.mark_submitting();
         self.state.set(new_state.clone());
 
+        let listeners = self.submit_listeners.get_value();
+        for listener in listeners {
+            listener(&new_state.values);
+        }
+
         // In a real implementation, you would send the data here
         // For now, just return the values
         Ok(new_state.values)
     }
 
+    /// Mark a submission started by [`submit`](Self::submit) as finished
+    pub fn finish_submission(&self) {
+        let state = self.state.get_untracked();
+        self.state.set(state.mark_not_submitting());
+    }
+
+    /// Mark a submission as finished and accepted, running the
+    /// [`on_submit_success`](Self::on_submit_success) listeners
+    pub fn finish_successful_submission(&self) {
+        self.finish_submission();
+        let values = self.state.with_untracked(|state| state.values.clone());
+        for listener in self.success_listeners.get_value() {
+            listener(&values);
+        }
+    }
+
+    /// Replace the validation errors, e.g. with those reported by a server
+    pub fn set_errors(&self, errors: ValidationErrors) {
+        let state = self.state.get_untracked();
+        self.state.set(state.with_errors(errors));
+    }
+
+    /// Run `listener` with the values of every submission that passes
+    /// validation, before it is sent anywhere
+    pub fn on_submit(&self, listener: impl Fn(&T) + Send + Sync + 'static) {
+        self.submit_listeners
+            .update_value(|listeners| listeners.push(Arc::new(listener)));
+    }
+
+    /// Run `listener` with the values of every submission that was accepted
+    ///
+    /// The submission adapters report success once the server accepted the
+    /// submission; failed, rejected or queued submissions are not reported.
+    pub fn on_submit_success(&self, listener: impl Fn(&T) + Send + Sync + 'static) {
+        self.success_listeners
+            .update_value(|listeners| listeners.push(Arc::new(listener)));
+    }
+
     /// Reset the form to initial values
     pub fn reset(&self) {
-        let _state = self.state.get_untracked();
-        let initial_values = T::default_values();
+        let mut initial_values = T::default_values();
+        self.computed.update_value(|slots| {
+            for slot in slots.iter_mut() {
+                slot.inputs = None;
+            }
+        });
+        self.apply_field_rules(&mut initial_values);
         let new_state = FormState::new(initial_values);
         self.state.set(new_state);
     }
@@ -374,7 +822,16 @@ impl<T: Form + Send + Sync + PartialEq> FormHandle<T> {
 
 impl<T: Form + Send + Sync> Clone for FormHandle<T> {
     fn clone(&self) -> Self {
-        Self { state: self.state }
+        Self {
+            state: self.state,
+            hidden_field_policy: self.hidden_field_policy,
+            computed: self.computed,
+            computed_values: self.computed_values,
+            submit_listeners: self.submit_listeners,
+            success_listeners: self.success_listeners,
+            rendered_at: self.rendered_at,
+            render_stamp: self.render_stamp,
+        }
     }
 }
 
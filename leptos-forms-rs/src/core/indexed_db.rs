@@ -0,0 +1,515 @@
+use crate::core::async_persistence::{AsyncFormPersistence, BlobStore, PersistenceFuture};
+use crate::core::drafts::{DraftCodec, DraftLoad, DraftVersioning};
+use crate::core::sensitive::FieldEncryptor;
+use crate::core::traits::Form;
+use crate::error::FormError;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Database used unless another one is chosen
+pub const DEFAULT_DATABASE: &str = "leptos-forms";
+
+const DRAFTS: &str = "drafts";
+const BLOBS: &str = "blobs";
+const STORAGE_TYPE: &str = "IndexedDB";
+
+/// Persist form values in IndexedDB
+///
+/// IndexedDB allows far larger drafts than Web Storage, and together with
+/// [`IndexedDbBlobStore`] keeps the contents of files as binary data. Drafts
+/// are versioned and have sensitive fields left out or encrypted just like
+/// with `LocalStoragePersistence`. Outside the browser every operation fails
+/// with an error instead of panicking.
+#[derive(Debug, Clone)]
+pub struct IndexedDbPersistence {
+    key: String,
+    database: String,
+    codec: DraftCodec,
+}
+
+impl IndexedDbPersistence {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            database: DEFAULT_DATABASE.to_string(),
+            codec: DraftCodec::default(),
+        }
+    }
+
+    /// Store drafts in the database `name` instead of [`DEFAULT_DATABASE`]
+    pub fn database(mut self, name: impl Into<String>) -> Self {
+        self.database = name.into();
+        self
+    }
+
+    /// Save drafts with a schema version and upgrade older ones on load
+    pub fn with_versioning(mut self, versioning: DraftVersioning) -> Self {
+        self.codec.versioning = Some(versioning);
+        self
+    }
+
+    /// Keep sensitive fields in drafts, encrypted, instead of leaving them out
+    pub fn with_encryptor(mut self, encryptor: impl FieldEncryptor + 'static) -> Self {
+        self.codec.encryptor = Some(Arc::new(encryptor));
+        self
+    }
+
+    /// Expire drafts `ttl` after they were last saved
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.codec.ttl = Some(ttl);
+        self
+    }
+
+    pub fn database_name(&self) -> &str {
+        &self.database
+    }
+}
+
+impl<T: Form> AsyncFormPersistence<T> for IndexedDbPersistence {
+    fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn save<'a>(&'a self, form: &'a T) -> PersistenceFuture<'a, ()> {
+        Box::pin(async move {
+            let previous = idb::get_text(&self.database, DRAFTS, &self.key)
+                .await
+                .ok()
+                .flatten();
+            let json = self
+                .codec
+                .encode(form, previous.as_deref())
+                .map_err(|e| FormError::serialization_error(e, None))?;
+            idb::put_text(&self.database, DRAFTS, &self.key, &json).await
+        })
+    }
+
+    fn load_draft(&self) -> PersistenceFuture<'_, DraftLoad<T>> {
+        Box::pin(async move {
+            let Some(json) = idb::get_text(&self.database, DRAFTS, &self.key).await? else {
+                return Ok(DraftLoad::Empty);
+            };
+            let draft = self
+                .codec
+                .decode(&json)
+                .map_err(|e| FormError::serialization_error(e, None))?;
+            if matches!(draft, DraftLoad::Discarded { .. }) {
+                idb::delete(&self.database, DRAFTS, &self.key).await?;
+            }
+            Ok(draft)
+        })
+    }
+
+    fn clear(&self) -> PersistenceFuture<'_, ()> {
+        Box::pin(idb::delete(&self.database, DRAFTS, &self.key))
+    }
+
+    fn exists(&self) -> PersistenceFuture<'_, bool> {
+        Box::pin(async move {
+            let json = idb::get_text(&self.database, DRAFTS, &self.key).await?;
+            Ok(json.is_some())
+        })
+    }
+}
+
+/// Keep the contents of files in IndexedDB, as binary data
+///
+/// Use with `FileBlobPersistence`; blobs share the database of the drafts
+/// unless another one is chosen.
+#[derive(Debug, Clone)]
+pub struct IndexedDbBlobStore {
+    database: String,
+}
+
+impl IndexedDbBlobStore {
+    pub fn new() -> Self {
+        Self {
+            database: DEFAULT_DATABASE.to_string(),
+        }
+    }
+
+    /// Store blobs in the database `name` instead of [`DEFAULT_DATABASE`]
+    pub fn database(mut self, name: impl Into<String>) -> Self {
+        self.database = name.into();
+        self
+    }
+}
+
+impl Default for IndexedDbBlobStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlobStore for IndexedDbBlobStore {
+    fn put<'a>(&'a self, id: &'a str, bytes: &'a [u8]) -> PersistenceFuture<'a, ()> {
+        Box::pin(idb::put_bytes(&self.database, BLOBS, id, bytes))
+    }
+
+    fn get<'a>(&'a self, id: &'a str) -> PersistenceFuture<'a, Option<Vec<u8>>> {
+        Box::pin(idb::get_bytes(&self.database, BLOBS, id))
+    }
+
+    fn ids_with_prefix<'a>(&'a self, prefix: &'a str) -> PersistenceFuture<'a, Vec<String>> {
+        Box::pin(idb::keys_with_prefix(&self.database, BLOBS, prefix))
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> PersistenceFuture<'a, ()> {
+        Box::pin(idb::delete(&self.database, BLOBS, id))
+    }
+
+    fn remove_prefix<'a>(&'a self, prefix: &'a str) -> PersistenceFuture<'a, ()> {
+        Box::pin(idb::delete_prefix(&self.database, BLOBS, prefix))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod idb {
+    use super::{BLOBS, DRAFTS, STORAGE_TYPE};
+    use crate::error::FormError;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{
+        IdbDatabase, IdbKeyRange, IdbObjectStore, IdbRequest, IdbTransaction, IdbTransactionMode,
+    };
+
+    /// Bumped whenever the object stores change
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn error(action: &str, cause: &JsValue) -> FormError {
+        let message = format!("Failed to {}: {:?}", action, cause);
+        let quota_exceeded = cause
+            .dyn_ref::<web_sys::DomException>()
+            .is_some_and(|e| e.name() == "QuotaExceededError");
+        if quota_exceeded {
+            FormError::quota_exceeded(message, STORAGE_TYPE)
+        } else {
+            FormError::persistence_error(message, STORAGE_TYPE)
+        }
+    }
+
+    thread_local! {
+        /// One connection per database, shared by every operation
+        static CONNECTIONS: RefCell<HashMap<String, IdbDatabase>> = RefCell::default();
+    }
+
+    /// Wait for `request` to succeed, with its result
+    async fn settle(request: &IdbRequest) -> Result<JsValue, JsValue> {
+        let promise = js_sys::Promise::new(&mut |resolve, reject| {
+            let succeeded = request.clone();
+            let on_success = Closure::once_into_js(move |_: web_sys::Event| {
+                let result = succeeded.result().unwrap_or(JsValue::UNDEFINED);
+                let _ = resolve.call1(&JsValue::NULL, &result);
+            });
+            let failed = request.clone();
+            let on_error = Closure::once_into_js(move |_: web_sys::Event| {
+                let cause = failed
+                    .error()
+                    .ok()
+                    .flatten()
+                    .map(JsValue::from)
+                    .unwrap_or(JsValue::UNDEFINED);
+                let _ = reject.call1(&JsValue::NULL, &cause);
+            });
+            request.set_onsuccess(Some(on_success.unchecked_ref()));
+            request.set_onerror(Some(on_error.unchecked_ref()));
+        });
+        JsFuture::from(promise).await
+    }
+
+    /// Wait for `transaction` to commit
+    ///
+    /// Writes are only durable once their transaction completes, and errors
+    /// raised while committing, such as running out of quota, abort the
+    /// transaction rather than fail the request.
+    async fn committed(transaction: &IdbTransaction) -> Result<(), JsValue> {
+        let promise = js_sys::Promise::new(&mut |resolve, reject| {
+            let on_complete = Closure::once_into_js(move |_: web_sys::Event| {
+                let _ = resolve.call0(&JsValue::NULL);
+            });
+            let aborted = transaction.clone();
+            let on_abort = Closure::once_into_js(move |_: web_sys::Event| {
+                let cause = aborted
+                    .error()
+                    .map(JsValue::from)
+                    .unwrap_or(JsValue::UNDEFINED);
+                let _ = reject.call1(&JsValue::NULL, &cause);
+            });
+            transaction.set_oncomplete(Some(on_complete.unchecked_ref()));
+            transaction.set_onabort(Some(on_abort.unchecked_ref()));
+        });
+        JsFuture::from(promise).await.map(|_| ())
+    }
+
+    /// Stop sharing `db`, so the next operation opens the database again
+    fn forget(database: &str, db: &IdbDatabase) {
+        CONNECTIONS.with(|connections| {
+            let mut connections = connections.borrow_mut();
+            if connections.get(database) == Some(db) {
+                connections.remove(database);
+            }
+        });
+    }
+
+    async fn open(database: &str) -> Result<IdbDatabase, FormError> {
+        let shared = CONNECTIONS.with(|connections| connections.borrow().get(database).cloned());
+        if let Some(db) = shared {
+            return Ok(db);
+        }
+
+        let factory = web_sys::window()
+            .and_then(|window| window.indexed_db().ok().flatten())
+            .ok_or_else(|| {
+                FormError::persistence_error("IndexedDB is not available", STORAGE_TYPE)
+            })?;
+        let request = factory
+            .open_with_u32(database, SCHEMA_VERSION)
+            .map_err(|e| error("open the database", &e))?;
+
+        let upgrading = request.clone();
+        let on_upgrade = Closure::once_into_js(move |_: web_sys::Event| {
+            let Ok(db) = upgrading.result().map(IdbDatabase::unchecked_from_js) else {
+                return;
+            };
+            for store in [DRAFTS, BLOBS] {
+                if !db.object_store_names().contains(store) {
+                    let _ = db.create_object_store(store);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+        let db = settle(&request)
+            .await
+            .map(IdbDatabase::unchecked_from_js)
+            .map_err(|e| error("open the database", &e))?;
+
+        // Step aside when another tab upgrades the database
+        let name = database.to_string();
+        let closing = db.clone();
+        let on_version_change = Closure::once_into_js(move |_: web_sys::Event| {
+            closing.close();
+            forget(&name, &closing);
+        });
+        db.set_onversionchange(Some(on_version_change.unchecked_ref()));
+
+        // Another operation may have opened it meanwhile
+        let shared = CONNECTIONS.with(|connections| {
+            connections
+                .borrow_mut()
+                .entry(database.to_string())
+                .or_insert_with(|| db.clone())
+                .clone()
+        });
+        if shared != db {
+            db.close();
+        }
+        Ok(shared)
+    }
+
+    async fn transaction(
+        database: &str,
+        store: &str,
+        mode: IdbTransactionMode,
+    ) -> Result<IdbTransaction, FormError> {
+        let db = open(database).await?;
+        db.transaction_with_str_and_mode(store, mode).map_err(|e| {
+            // The browser may have closed the connection
+            forget(database, &db);
+            error("start a transaction", &e)
+        })
+    }
+
+    async fn get(database: &str, store: &str, key: &str) -> Result<JsValue, FormError> {
+        let request = transaction(database, store, IdbTransactionMode::Readonly)
+            .await?
+            .object_store(store)
+            .and_then(|store| store.get(&JsValue::from_str(key)))
+            .map_err(|e| error(&format!("read {}", key), &e))?;
+        settle(&request)
+            .await
+            .map_err(|e| error(&format!("read {}", key), &e))
+    }
+
+    /// Make `request` in a read-write transaction of its own, waiting for it
+    /// to commit
+    async fn write(
+        database: &str,
+        store: &str,
+        action: &str,
+        request: impl FnOnce(&IdbObjectStore) -> Result<IdbRequest, JsValue>,
+    ) -> Result<(), FormError> {
+        let transaction = transaction(database, store, IdbTransactionMode::Readwrite).await?;
+        transaction
+            .object_store(store)
+            .and_then(|store| request(&store))
+            .map_err(|e| error(action, &e))?;
+        committed(&transaction).await.map_err(|e| error(action, &e))
+    }
+
+    async fn put(database: &str, store: &str, key: &str, value: &JsValue) -> Result<(), FormError> {
+        write(database, store, &format!("write {}", key), |store| {
+            store.put_with_key(value, &JsValue::from_str(key))
+        })
+        .await
+    }
+
+    pub(super) async fn get_text(
+        database: &str,
+        store: &str,
+        key: &str,
+    ) -> Result<Option<String>, FormError> {
+        Ok(get(database, store, key).await?.as_string())
+    }
+
+    pub(super) async fn put_text(
+        database: &str,
+        store: &str,
+        key: &str,
+        text: &str,
+    ) -> Result<(), FormError> {
+        put(database, store, key, &JsValue::from_str(text)).await
+    }
+
+    pub(super) async fn get_bytes(
+        database: &str,
+        store: &str,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, FormError> {
+        let value = get(database, store, key).await?;
+        if value.is_undefined() || value.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(js_sys::Uint8Array::new(&value).to_vec()))
+    }
+
+    pub(super) async fn put_bytes(
+        database: &str,
+        store: &str,
+        key: &str,
+        bytes: &[u8],
+    ) -> Result<(), FormError> {
+        put(
+            database,
+            store,
+            key,
+            &js_sys::Uint8Array::from(bytes).into(),
+        )
+        .await
+    }
+
+    pub(super) async fn delete(database: &str, store: &str, key: &str) -> Result<(), FormError> {
+        write(database, store, &format!("remove {}", key), |store| {
+            store.delete(&JsValue::from_str(key))
+        })
+        .await
+    }
+
+    /// Every key starting with `prefix`, which sorts between it and the
+    /// prefix followed by the highest code unit
+    fn prefix_range(prefix: &str) -> Result<IdbKeyRange, JsValue> {
+        IdbKeyRange::bound(
+            &JsValue::from_str(prefix),
+            &JsValue::from_str(&format!("{}\u{ffff}", prefix)),
+        )
+    }
+
+    pub(super) async fn keys_with_prefix(
+        database: &str,
+        store: &str,
+        prefix: &str,
+    ) -> Result<Vec<String>, FormError> {
+        let action = format!("list {}*", prefix);
+        let range = prefix_range(prefix).map_err(|e| error(&action, &e))?;
+        let request = transaction(database, store, IdbTransactionMode::Readonly)
+            .await?
+            .object_store(store)
+            .and_then(|store| store.get_all_keys_with_key(&range))
+            .map_err(|e| error(&action, &e))?;
+        let keys = settle(&request).await.map_err(|e| error(&action, &e))?;
+        Ok(js_sys::Array::from(&keys)
+            .iter()
+            .filter_map(|key| key.as_string())
+            .collect())
+    }
+
+    pub(super) async fn delete_prefix(
+        database: &str,
+        store: &str,
+        prefix: &str,
+    ) -> Result<(), FormError> {
+        let action = format!("remove {}*", prefix);
+        let range = prefix_range(prefix).map_err(|e| error(&action, &e))?;
+        write(database, store, &action, |store| store.delete(&range)).await
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod idb {
+    use super::STORAGE_TYPE;
+    use crate::error::FormError;
+
+    fn unavailable<R>() -> Result<R, FormError> {
+        Err(FormError::persistence_error(
+            "IndexedDB is only available in the browser",
+            STORAGE_TYPE,
+        ))
+    }
+
+    pub(super) async fn get_text(
+        _database: &str,
+        _store: &str,
+        _key: &str,
+    ) -> Result<Option<String>, FormError> {
+        unavailable()
+    }
+
+    pub(super) async fn put_text(
+        _database: &str,
+        _store: &str,
+        _key: &str,
+        _text: &str,
+    ) -> Result<(), FormError> {
+        unavailable()
+    }
+
+    pub(super) async fn get_bytes(
+        _database: &str,
+        _store: &str,
+        _key: &str,
+    ) -> Result<Option<Vec<u8>>, FormError> {
+        unavailable()
+    }
+
+    pub(super) async fn put_bytes(
+        _database: &str,
+        _store: &str,
+        _key: &str,
+        _bytes: &[u8],
+    ) -> Result<(), FormError> {
+        unavailable()
+    }
+
+    pub(super) async fn delete(_database: &str, _store: &str, _key: &str) -> Result<(), FormError> {
+        unavailable()
+    }
+
+    pub(super) async fn keys_with_prefix(
+        _database: &str,
+        _store: &str,
+        _prefix: &str,
+    ) -> Result<Vec<String>, FormError> {
+        unavailable()
+    }
+
+    pub(super) async fn delete_prefix(
+        _database: &str,
+        _store: &str,
+        _prefix: &str,
+    ) -> Result<(), FormError> {
+        unavailable()
+    }
+}
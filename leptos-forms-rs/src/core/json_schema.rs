@@ -0,0 +1,734 @@
+use crate::core::traits::{FieldMetadata, FormSchema};
+use crate::core::types::{FieldType, FieldValue, NumberType, SelectOption};
+use crate::error::FormError;
+use crate::validation::Validator;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// JSON Schema dialect emitted by `FormSchema::to_json_schema`
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+impl FormSchema {
+    /// Describe the form payload as a JSON Schema 2020-12 document
+    ///
+    /// Required fields, `min`/`max`, length limits, `pattern`, `email` and `url`
+    /// come from each field's validators, and select options become `enum`.
+    /// Optional fields also accept `null`, matching how `Option` fields serialize.
+    pub fn to_json_schema(&self) -> Value {
+        let mut schema = object_schema(&self.field_metadata);
+        schema.insert("$schema".to_string(), json!(JSON_SCHEMA_DIALECT));
+        schema.insert("title".to_string(), json!(self.name));
+        Value::Object(schema)
+    }
+
+    /// Describe the form payload as TypeScript declarations
+    ///
+    /// The form becomes an exported interface named after the schema, and every
+    /// nested form gets an interface of its own.
+    pub fn to_typescript(&self) -> String {
+        let mut interfaces = Vec::new();
+        collect_interfaces(&self.name, &self.field_metadata, &mut interfaces);
+
+        let mut output =
+            String::from("// Generated by leptos-forms-rs from the Rust form definitions\n");
+        for (name, fields) in interfaces {
+            output.push('\n');
+            output.push_str(&typescript_interface(&name, &fields));
+        }
+        output
+    }
+
+    /// Write `<name>.schema.json` and `<name>.d.ts` into `dir`
+    ///
+    /// Intended for build scripts and small export binaries, so that the Rust
+    /// form definitions remain the single source of truth.
+    pub fn write_schema_files(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let schema = serde_json::to_string_pretty(&self.to_json_schema())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        std::fs::write(
+            dir.join(format!("{}.schema.json", self.name)),
+            schema + "\n",
+        )?;
+        std::fs::write(
+            dir.join(format!("{}.d.ts", self.name)),
+            self.to_typescript(),
+        )?;
+        Ok(())
+    }
+}
+
+fn object_schema(fields: &[FieldMetadata]) -> Map<String, Value> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    for field in fields {
+        if field.is_required || field.validators.contains(&Validator::Required) {
+            required.push(json!(field.name));
+        }
+        properties.insert(field.name.clone(), field_schema(field));
+    }
+
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!("object"));
+    schema.insert("properties".to_string(), Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".to_string(), Value::Array(required));
+    }
+    schema.insert("additionalProperties".to_string(), json!(false));
+    schema
+}
+
+fn field_schema(field: &FieldMetadata) -> Value {
+    let mut schema = type_schema(&field.field_type, &field.nested_fields);
+    let is_string = schema.get("type") == Some(&json!("string"));
+    let is_required = field.is_required || field.validators.contains(&Validator::Required);
+
+    for validator in &field.validators {
+        match validator {
+            Validator::Required if is_string => {
+                schema.entry("minLength").or_insert(json!(1));
+            }
+            Validator::Email => {
+                schema.insert("format".to_string(), json!("email"));
+            }
+            Validator::Url => {
+                schema.insert("format".to_string(), json!("uri"));
+            }
+            Validator::MinLength(min) => {
+                schema.insert("minLength".to_string(), json!(min));
+            }
+            Validator::MaxLength(max) => {
+                schema.insert("maxLength".to_string(), json!(max));
+            }
+            Validator::Min(min) => {
+                schema.insert("minimum".to_string(), json!(min));
+            }
+            Validator::Max(max) => {
+                schema.insert("maximum".to_string(), json!(max));
+            }
+            Validator::Range(min, max) => {
+                schema.insert("minimum".to_string(), json!(min));
+                schema.insert("maximum".to_string(), json!(max));
+            }
+            Validator::Pattern(pattern) => {
+                schema.insert("pattern".to_string(), json!(pattern));
+            }
+            Validator::Required | Validator::Custom(_) => {}
+        }
+    }
+
+    if let Some(label) = field.attributes.get("label") {
+        schema.insert("title".to_string(), json!(label));
+    }
+    if field.is_readonly() {
+        schema.insert("readOnly".to_string(), json!(true));
+    }
+    if field.is_sensitive() {
+        schema.insert("writeOnly".to_string(), json!(true));
+    }
+
+    if !is_required {
+        if let Some(Value::String(ty)) = schema.get("type").cloned() {
+            schema.insert("type".to_string(), json!([ty, "null"]));
+        }
+        // An enum restricts the value further than its type does
+        if let Some(Value::Array(values)) = schema.get_mut("enum") {
+            values.push(Value::Null);
+        }
+    }
+
+    Value::Object(schema)
+}
+
+fn type_schema(field_type: &FieldType, nested_fields: &[FieldMetadata]) -> Map<String, Value> {
+    let mut schema = Map::new();
+    match field_type {
+        FieldType::Text
+        | FieldType::Password
+        | FieldType::RichText
+        | FieldType::Markdown
+        | FieldType::Code => {
+            schema.insert("type".to_string(), json!("string"));
+        }
+        FieldType::Email => {
+            schema.insert("type".to_string(), json!("string"));
+            schema.insert("format".to_string(), json!("email"));
+        }
+        FieldType::Number(number) => {
+            schema.insert("type".to_string(), json!(number_type_name(number)));
+            if let Some(min) = number.min {
+                schema.insert("minimum".to_string(), json!(min));
+            }
+            if let Some(max) = number.max {
+                schema.insert("maximum".to_string(), json!(max));
+            }
+        }
+        FieldType::Boolean => {
+            schema.insert("type".to_string(), json!("boolean"));
+        }
+        FieldType::Select(options) => {
+            schema.insert("type".to_string(), json!("string"));
+            schema.insert(
+                "enum".to_string(),
+                options.iter().map(|option| json!(option.value)).collect(),
+            );
+        }
+        FieldType::MultiSelect(options) => {
+            let values: Vec<Value> = options.iter().map(|option| json!(option.value)).collect();
+            schema.insert("type".to_string(), json!("array"));
+            schema.insert(
+                "items".to_string(),
+                json!({ "type": "string", "enum": values }),
+            );
+            schema.insert("uniqueItems".to_string(), json!(true));
+        }
+        FieldType::Date => {
+            schema.insert("type".to_string(), json!("string"));
+            schema.insert("format".to_string(), json!("date"));
+        }
+        FieldType::DateTime => {
+            schema.insert("type".to_string(), json!("string"));
+            schema.insert("format".to_string(), json!("date-time"));
+        }
+        FieldType::File(constraints) => {
+            let file = json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "size": { "type": "integer", "minimum": 0 },
+                    "mime_type": { "type": "string" },
+                    "data": { "type": "array", "items": { "type": "integer" } }
+                },
+                "required": ["name", "size", "mime_type", "data"]
+            });
+            if constraints.multiple {
+                schema.insert("type".to_string(), json!("array"));
+                schema.insert("items".to_string(), file);
+            } else if let Value::Object(file) = file {
+                schema = file;
+            }
+        }
+        FieldType::Array(inner) => {
+            schema.insert("type".to_string(), json!("array"));
+            schema.insert(
+                "items".to_string(),
+                Value::Object(type_schema(inner, nested_fields)),
+            );
+        }
+        FieldType::Nested(name) => {
+            schema = object_schema(nested_fields);
+            schema.insert("title".to_string(), json!(name));
+        }
+    }
+    schema
+}
+
+fn number_type_name(number: &NumberType) -> &'static str {
+    if number.step == Some(1.0) {
+        "integer"
+    } else {
+        "number"
+    }
+}
+
+/// Collect the interfaces for a form and every nested form it contains
+fn collect_interfaces(
+    name: &str,
+    fields: &[FieldMetadata],
+    interfaces: &mut Vec<(String, Vec<FieldMetadata>)>,
+) {
+    let name = typescript_identifier(name);
+    if interfaces.iter().any(|(existing, _)| *existing == name) {
+        return;
+    }
+    interfaces.push((name, fields.to_vec()));
+
+    for field in fields {
+        if let Some(nested) = nested_type_name(&field.field_type) {
+            collect_interfaces(nested, &field.nested_fields, interfaces);
+        }
+    }
+}
+
+fn nested_type_name(field_type: &FieldType) -> Option<&str> {
+    match field_type {
+        FieldType::Nested(name) => Some(name),
+        FieldType::Array(inner) => nested_type_name(inner),
+        _ => None,
+    }
+}
+
+fn typescript_interface(name: &str, fields: &[FieldMetadata]) -> String {
+    let mut output = format!("export interface {} {{\n", name);
+    for field in fields {
+        let is_required = field.is_required || field.validators.contains(&Validator::Required);
+        let ty = typescript_type(&field.field_type);
+        if is_required {
+            output.push_str(&format!("  {}: {};\n", typescript_key(&field.name), ty));
+        } else {
+            output.push_str(&format!(
+                "  {}?: {} | null;\n",
+                typescript_key(&field.name),
+                ty
+            ));
+        }
+    }
+    output.push_str("}\n");
+    output
+}
+
+fn typescript_type(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Text
+        | FieldType::Email
+        | FieldType::Password
+        | FieldType::RichText
+        | FieldType::Markdown
+        | FieldType::Code
+        | FieldType::Date
+        | FieldType::DateTime => "string".to_string(),
+        FieldType::Number(_) => "number".to_string(),
+        FieldType::Boolean => "boolean".to_string(),
+        FieldType::Select(options) if !options.is_empty() => select_union(options),
+        FieldType::MultiSelect(options) if !options.is_empty() => {
+            format!("({})[]", select_union(options))
+        }
+        FieldType::Select(_) => "string".to_string(),
+        FieldType::MultiSelect(_) => "string[]".to_string(),
+        FieldType::File(constraints) => {
+            let file = "{ name: string; size: number; mime_type: string; data: number[] }";
+            if constraints.multiple {
+                format!("{}[]", file)
+            } else {
+                file.to_string()
+            }
+        }
+        FieldType::Array(inner) => match inner.as_ref() {
+            FieldType::Select(options) | FieldType::MultiSelect(options) if !options.is_empty() => {
+                format!("({})[]", typescript_type(inner))
+            }
+            _ => format!("{}[]", typescript_type(inner)),
+        },
+        FieldType::Nested(name) => typescript_identifier(name),
+    }
+}
+
+fn select_union(options: &[SelectOption]) -> String {
+    options
+        .iter()
+        .map(|option| Value::String(option.value.clone()).to_string())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Quote property names that are not valid TypeScript identifiers
+fn typescript_key(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        Value::String(name.to_string()).to_string()
+    }
+}
+
+/// Turn a schema name into a valid TypeScript type name
+fn typescript_identifier(name: &str) -> String {
+    let identifier: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    if identifier.is_empty() {
+        "Form".to_string()
+    } else {
+        identifier
+    }
+}
+
+/// A keyword or value `FormSchema::from_json_schema` could not map
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonSchemaIssue {
+    /// JSON pointer to the schema object containing the keyword
+    pub path: String,
+    pub keyword: String,
+    pub message: String,
+}
+
+/// Error returned when a JSON Schema cannot be imported without losing rules
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonSchemaImportError {
+    pub issues: Vec<JsonSchemaIssue>,
+}
+
+impl fmt::Display for JsonSchemaImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cannot import JSON Schema:")?;
+        for issue in &self.issues {
+            let path = if issue.path.is_empty() {
+                "/"
+            } else {
+                &issue.path
+            };
+            write!(f, "\n  at {}: `{}` {}", path, issue.keyword, issue.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for JsonSchemaImportError {}
+
+impl From<JsonSchemaImportError> for FormError {
+    fn from(error: JsonSchemaImportError) -> Self {
+        FormError::configuration_error(error.to_string(), "FormSchema::from_json_schema")
+    }
+}
+
+/// Keywords that only annotate a schema and are safe to ignore
+const ANNOTATION_KEYWORDS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "examples",
+    "readOnly",
+    "writeOnly",
+    "deprecated",
+];
+
+impl FormSchema {
+    /// Build a runtime schema from a JSON Schema object document
+    ///
+    /// Types, the `email`, `uri`, `date` and `date-time` formats, `enum`, length
+    /// limits, `pattern`, `minimum`/`maximum`, nested objects and arrays map onto
+    /// `FieldType`, `Validator` and nested metadata. Any other keyword is
+    /// reported in the error rather than dropped, along with its JSON pointer.
+    /// Fields keep the order of their `properties`.
+    pub fn from_json_schema(schema: &Value) -> Result<Self, JsonSchemaImportError> {
+        let mut importer = JsonSchemaImporter { issues: Vec::new() };
+        let name = schema
+            .get("title")
+            .and_then(Value::as_str)
+            .map(typescript_identifier)
+            .unwrap_or_else(|| "Form".to_string());
+        let field_metadata = importer.object_fields(schema, "");
+
+        if importer.issues.is_empty() {
+            Ok(FormSchema {
+                name,
+                field_metadata,
+            })
+        } else {
+            Err(JsonSchemaImportError {
+                issues: importer.issues,
+            })
+        }
+    }
+}
+
+struct JsonSchemaImporter {
+    issues: Vec<JsonSchemaIssue>,
+}
+
+impl JsonSchemaImporter {
+    fn issue(&mut self, path: &str, keyword: &str, message: impl Into<String>) {
+        self.issues.push(JsonSchemaIssue {
+            path: path.to_string(),
+            keyword: keyword.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Read the JSON `type` keyword, allowing a `"null"` alternative
+    fn schema_type(&mut self, schema: &Map<String, Value>, path: &str) -> Option<String> {
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(ty)) => vec![ty.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            Some(_) => {
+                self.issue(path, "type", "must be a string or an array of strings");
+                return None;
+            }
+            None if schema.contains_key("properties") => vec!["object"],
+            None if schema.contains_key("items") => vec!["array"],
+            None if schema.contains_key("enum") => vec!["string"],
+            None => {
+                self.issue(path, "type", "is missing");
+                return None;
+            }
+        };
+
+        let types: Vec<&str> = types.into_iter().filter(|ty| *ty != "null").collect();
+        match types.as_slice() {
+            [ty] => Some(ty.to_string()),
+            _ => {
+                self.issue(path, "type", "must name exactly one non-null type");
+                None
+            }
+        }
+    }
+
+    fn object_fields(&mut self, schema: &Value, path: &str) -> Vec<FieldMetadata> {
+        let Some(schema) = schema.as_object() else {
+            self.issue(path, "type", "schema must be an object");
+            return Vec::new();
+        };
+        if let Some(ty) = self.schema_type(schema, path) {
+            if ty != "object" {
+                self.issue(path, "type", format!("expected \"object\", got \"{}\"", ty));
+                return Vec::new();
+            }
+        }
+
+        let required: Vec<&str> = match schema.get("required") {
+            Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+            Some(_) => {
+                self.issue(path, "required", "must be an array of property names");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        for keyword in schema.keys() {
+            let known = matches!(
+                keyword.as_str(),
+                "type" | "title" | "description" | "default" | "properties" | "required"
+            );
+            if keyword == "additionalProperties" {
+                if !schema[keyword].is_boolean() {
+                    self.issue(path, keyword, "is only supported as a boolean");
+                }
+            } else if !known && !ANNOTATION_KEYWORDS.contains(&keyword.as_str()) {
+                self.issue(path, keyword, "is not supported on objects");
+            }
+        }
+
+        let Some(properties) = schema.get("properties") else {
+            return Vec::new();
+        };
+        let Some(properties) = properties.as_object() else {
+            self.issue(path, "properties", "must be an object");
+            return Vec::new();
+        };
+
+        properties
+            .iter()
+            .map(|(name, property)| {
+                let property_path = format!("{}/properties/{}", path, escape_pointer(name));
+                let mut field = self.field(name, property, &property_path);
+                if required.contains(&name.as_str()) {
+                    field.is_required = true;
+                    field.validators.insert(0, Validator::Required);
+                }
+                field
+            })
+            .collect()
+    }
+
+    fn field(&mut self, name: &str, schema: &Value, path: &str) -> FieldMetadata {
+        let mut field = FieldMetadata {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let Some(object) = schema.as_object() else {
+            self.issue(path, "type", "property schema must be an object");
+            return field;
+        };
+
+        let (field_type, nested_fields) =
+            self.field_type(name, object, path, &mut field.validators);
+        field.field_type = field_type;
+        field.nested_fields = nested_fields;
+
+        if let Some(default) = object.get("default") {
+            match serde_json::from_value::<FieldValue>(default.clone()) {
+                Ok(value) => field.default_value = Some(value),
+                Err(e) => self.issue(path, "default", format!("could not be read: {}", e)),
+            }
+        }
+        if let Some(title) = object.get("title").and_then(Value::as_str) {
+            field
+                .attributes
+                .insert("label".to_string(), title.to_string());
+        }
+        if let Some(description) = object.get("description").and_then(Value::as_str) {
+            field
+                .attributes
+                .insert("description".to_string(), description.to_string());
+        }
+        if object.get("readOnly") == Some(&Value::Bool(true)) {
+            field
+                .attributes
+                .insert("readonly".to_string(), "true".to_string());
+        }
+        field.sensitive = object.get("writeOnly") == Some(&Value::Bool(true));
+        field
+    }
+
+    /// Map a property schema onto a field type, collecting its validators
+    fn field_type(
+        &mut self,
+        name: &str,
+        schema: &Map<String, Value>,
+        path: &str,
+        validators: &mut Vec<Validator>,
+    ) -> (FieldType, Vec<FieldMetadata>) {
+        let Some(ty) = self.schema_type(schema, path) else {
+            return (FieldType::Text, Vec::new());
+        };
+
+        // Objects validate their own keywords while collecting properties
+        if ty == "object" {
+            let nested_name = schema
+                .get("title")
+                .and_then(Value::as_str)
+                .map(typescript_identifier)
+                .unwrap_or_else(|| pascal_case(name));
+            let fields = self.object_fields(&Value::Object(schema.clone()), path);
+            return (FieldType::Nested(nested_name), fields);
+        }
+
+        let mut field_type = match ty.as_str() {
+            "string" => FieldType::Text,
+            "integer" => FieldType::Number(NumberType {
+                min: None,
+                max: None,
+                step: Some(1.0),
+            }),
+            "number" => FieldType::Number(NumberType {
+                min: None,
+                max: None,
+                step: None,
+            }),
+            "boolean" => FieldType::Boolean,
+            "array" => FieldType::Array(Box::new(FieldType::Text)),
+            other => {
+                self.issue(path, "type", format!("\"{}\" is not supported", other));
+                FieldType::Text
+            }
+        };
+        let mut nested_fields = Vec::new();
+
+        for (keyword, value) in schema {
+            match (keyword.as_str(), &mut field_type) {
+                ("type" | "title" | "description" | "default", _) => {}
+                (keyword, _) if ANNOTATION_KEYWORDS.contains(&keyword) => {}
+                ("format", FieldType::Text) => match value.as_str() {
+                    Some("email") => {
+                        field_type = FieldType::Email;
+                        validators.push(Validator::Email);
+                    }
+                    Some("uri") | Some("url") => validators.push(Validator::Url),
+                    Some("date") => field_type = FieldType::Date,
+                    Some("date-time") => field_type = FieldType::DateTime,
+                    _ => self.issue(
+                        path,
+                        keyword,
+                        format!("{} is not a supported format", value),
+                    ),
+                },
+                ("enum", FieldType::Text | FieldType::Number(_)) => match value.as_array() {
+                    Some(values) => field_type = FieldType::Select(select_options(values)),
+                    None => self.issue(path, keyword, "must be an array"),
+                },
+                ("minLength" | "maxLength", _) if ty == "string" => {
+                    match value.as_u64().map(|n| n as usize) {
+                        Some(n) if keyword == "minLength" => {
+                            validators.push(Validator::MinLength(n))
+                        }
+                        Some(n) => validators.push(Validator::MaxLength(n)),
+                        None => self.issue(path, keyword, "must be a non-negative integer"),
+                    }
+                }
+                ("pattern", _) if ty == "string" => match value.as_str() {
+                    Some(pattern) => validators.push(Validator::Pattern(pattern.to_string())),
+                    None => self.issue(path, keyword, "must be a string"),
+                },
+                ("minimum" | "maximum", FieldType::Number(number)) => match value.as_f64() {
+                    Some(n) if keyword == "minimum" => {
+                        number.min = Some(n);
+                        validators.push(Validator::Min(n));
+                    }
+                    Some(n) => {
+                        number.max = Some(n);
+                        validators.push(Validator::Max(n));
+                    }
+                    None => self.issue(path, keyword, "must be a number"),
+                },
+                ("items", FieldType::Array(_)) => {
+                    let items_path = format!("{}/items", path);
+                    let Some(items) = value.as_object() else {
+                        self.issue(&items_path, "type", "items schema must be an object");
+                        continue;
+                    };
+                    let mut item_validators = Vec::new();
+                    let (item_type, item_fields) =
+                        self.field_type(name, items, &items_path, &mut item_validators);
+                    if !item_validators.is_empty() {
+                        self.issue(
+                            &items_path,
+                            "items",
+                            "validation keywords on array items are not supported",
+                        );
+                    }
+                    field_type = match item_type {
+                        FieldType::Select(options) => FieldType::MultiSelect(options),
+                        item_type => FieldType::Array(Box::new(item_type)),
+                    };
+                    nested_fields = item_fields;
+                }
+                ("uniqueItems", FieldType::MultiSelect(_) | FieldType::Array(_)) => {}
+                (keyword, _) => self.issue(
+                    path,
+                    keyword,
+                    format!("is not supported on \"{}\" properties", ty),
+                ),
+            }
+        }
+
+        (field_type, nested_fields)
+    }
+}
+
+fn select_options(values: &[Value]) -> Vec<SelectOption> {
+    values
+        .iter()
+        // A null value makes the field optional rather than an option
+        .filter(|value| !value.is_null())
+        .map(|value| {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            SelectOption {
+                label: value.clone(),
+                value,
+                disabled: false,
+            }
+        })
+        .collect()
+}
+
+/// Escape a property name for use in a JSON pointer
+fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// Derive a nested form name from a property name, e.g. `billing_address`
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -1,9 +1,50 @@
+pub mod async_persistence;
+pub mod computed;
+pub mod convert;
+pub mod drafts;
+pub mod dynamic;
 pub mod form_handle;
+pub mod indexed_db;
+pub mod json_schema;
+pub mod options;
 pub mod performance;
+pub mod persistence;
+pub mod sensitive;
+pub mod sync;
 pub mod traits;
 pub mod types;
+pub mod visibility;
 
+pub use async_persistence::{
+    AsyncFormPersistence, BlobStore, FileBlobPersistence, MemoryBlobStore, PersistenceFuture,
+    QuotaFallback,
+};
+pub use computed::ComputedField;
+pub use convert::*;
+pub use drafts::{
+    AutoSaveSchedule, DraftFallback, DraftInfo, DraftLoad, DraftVersioning, StaleDraft,
+};
+pub use dynamic::DynamicForm;
 pub use form_handle::FormHandle;
+pub use indexed_db::{IndexedDbBlobStore, IndexedDbPersistence};
+pub use json_schema::{JsonSchemaImportError, JsonSchemaIssue, JSON_SCHEMA_DIALECT};
+pub use options::{FieldOptions, OptionsProvider};
 pub use performance::*;
+pub use persistence::{
+    AsyncStorage, DraftManager, DraftStorageBackend, LocalStorage, LocalStoragePersistence,
+    MemoryPersistence, MemoryStorage, SessionStorage, SessionStoragePersistence,
+    StoragePersistence,
+};
+pub use sensitive::{
+    redacted_form_data, sensitive_field_paths, without_sensitive_values, FieldEncryptor, REDACTED,
+};
+pub use sync::{
+    merge_remote_draft, BroadcastDraftChannel, DraftConflict, DraftListener, DraftMerge,
+    DraftSyncChannel, MemorySyncChannel, StorageEventChannel,
+};
 pub use traits::*;
 pub use types::*;
+pub use visibility::*;
+
+// Re-export the derive macro alongside the trait it implements
+pub use leptos_forms_rs_macro::Form;
@@ -0,0 +1,212 @@
+use crate::core::convert::get_form_path_value;
+use crate::core::form_handle::FormHandle;
+use crate::core::traits::Form;
+use crate::core::types::{FieldValue, SelectOption};
+use crate::error::FormError;
+use leptos::prelude::*;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type OptionsFuture = Pin<Box<dyn Future<Output = Result<Vec<SelectOption>, FormError>>>>;
+type OptionsLoader = Arc<dyn Fn(Vec<FieldValue>) -> OptionsFuture + Send + Sync>;
+
+/// Source of the options of a select field that depend on other fields
+///
+/// The loader receives the values of the `depends_on` fields in order, so a
+/// state picker depending on `country` gets the selected country:
+///
+/// ```rust,ignore
+/// let states = OptionsProvider::new("state", |inputs| async move {
+///     fetch_states(&inputs[0].to_string()).await
+/// })
+/// .depends_on(["country"]);
+/// ```
+#[derive(Clone)]
+pub struct OptionsProvider {
+    field: String,
+    dependencies: Vec<String>,
+    load: OptionsLoader,
+}
+
+impl OptionsProvider {
+    /// Options loaded asynchronously, e.g. from an HTTP endpoint
+    pub fn new<F, Fut>(field: impl Into<String>, load: F) -> Self
+    where
+        F: Fn(Vec<FieldValue>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<SelectOption>, FormError>> + 'static,
+    {
+        Self {
+            field: field.into(),
+            dependencies: Vec::new(),
+            load: Arc::new(move |inputs| Box::pin(load(inputs))),
+        }
+    }
+
+    /// Options computed synchronously, e.g. from a lookup table
+    pub fn from_fn<F>(field: impl Into<String>, options: F) -> Self
+    where
+        F: Fn(&[FieldValue]) -> Vec<SelectOption> + Send + Sync + 'static,
+    {
+        Self::new(field, move |inputs| {
+            let options = options(&inputs);
+            async move { Ok(options) }
+        })
+    }
+
+    /// Field paths whose values the options depend on
+    pub fn depends_on<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.dependencies.extend(fields.into_iter().map(Into::into));
+        self
+    }
+
+    /// Path of the select field the options are for
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn dependencies(&self) -> &[String] {
+        &self.dependencies
+    }
+}
+
+/// Options of one select field together with their loading state
+///
+/// Loaded option lists are cached per combination of dependency values. After
+/// each load a selection that is no longer among the options is cleared, which
+/// in turn refreshes the fields depending on this one.
+#[derive(Clone)]
+pub struct FieldOptions {
+    provider: OptionsProvider,
+    cache: StoredValue<HashMap<String, Vec<SelectOption>>>,
+    /// Bumped on every refresh so that responses to superseded requests are dropped
+    generation: StoredValue<u64>,
+    options: RwSignal<Vec<SelectOption>>,
+    loading: RwSignal<bool>,
+    error: RwSignal<Option<FormError>>,
+}
+
+impl FieldOptions {
+    pub fn new(provider: OptionsProvider) -> Self {
+        Self {
+            provider,
+            cache: StoredValue::new(HashMap::new()),
+            generation: StoredValue::new(0),
+            options: RwSignal::new(Vec::new()),
+            loading: RwSignal::new(false),
+            error: RwSignal::new(None),
+        }
+    }
+
+    /// Path of the select field the options are for
+    pub fn field(&self) -> &str {
+        self.provider.field()
+    }
+
+    pub fn dependencies(&self) -> &[String] {
+        self.provider.dependencies()
+    }
+
+    /// Options currently offered
+    pub fn options(&self) -> ReadSignal<Vec<SelectOption>> {
+        self.options.read_only()
+    }
+
+    /// Whether a load is in flight
+    pub fn is_loading(&self) -> ReadSignal<bool> {
+        self.loading.read_only()
+    }
+
+    /// Error of the last load, if it failed
+    pub fn error(&self) -> ReadSignal<Option<FormError>> {
+        self.error.read_only()
+    }
+
+    /// Forget cached option lists so the next refresh loads again
+    pub fn clear_cache(&self) {
+        self.cache.update_value(HashMap::clear);
+    }
+
+    /// Dependency values as read from `form`
+    pub fn input_values<T: Form>(&self, form: &T) -> Vec<FieldValue> {
+        self.dependencies()
+            .iter()
+            .map(|field| get_form_path_value(form, field).unwrap_or_default())
+            .collect()
+    }
+
+    /// Load the options for the current dependency values
+    ///
+    /// While any dependency is empty there is nothing to choose from, so the
+    /// options are emptied without calling the loader.
+    pub async fn refresh<T: Form + Send + Sync + PartialEq>(&self, form: &FormHandle<T>) {
+        let inputs = form
+            .state()
+            .with_untracked(|state| self.input_values(&state.values));
+        self.generation.update_value(|generation| *generation += 1);
+        let generation = self.generation.get_value();
+
+        if inputs.iter().any(FieldValue::is_empty) {
+            self.loading.set(false);
+            self.error.set(None);
+            self.apply(form, Vec::new());
+            return;
+        }
+
+        let key = serde_json::to_string(&inputs).unwrap_or_default();
+        if let Some(options) = self.cache.with_value(|cache| cache.get(&key).cloned()) {
+            self.loading.set(false);
+            self.error.set(None);
+            self.apply(form, options);
+            return;
+        }
+
+        self.loading.set(true);
+        let result = (self.provider.load)(inputs).await;
+        if self.generation.get_value() != generation {
+            return;
+        }
+        self.loading.set(false);
+
+        match result {
+            Ok(options) => {
+                self.cache
+                    .update_value(|cache| _ = cache.insert(key, options.clone()));
+                self.error.set(None);
+                self.apply(form, options);
+            }
+            Err(error) => self.error.set(Some(error)),
+        }
+    }
+
+    /// Offer `options` and drop a selection they no longer contain
+    fn apply<T: Form + Send + Sync + PartialEq>(
+        &self,
+        form: &FormHandle<T>,
+        options: Vec<SelectOption>,
+    ) {
+        let is_offered = |value: &FieldValue| match value {
+            FieldValue::String(value) => options.iter().any(|option| option.value == *value),
+            _ => false,
+        };
+        let cleared = match form.get_field_path_value(self.field()) {
+            Some(value @ FieldValue::String(_)) if !value.is_empty() && !is_offered(&value) => {
+                Some(FieldValue::String(String::new()))
+            }
+            Some(FieldValue::Array(items)) if !items.iter().all(is_offered) => Some(
+                FieldValue::Array(items.into_iter().filter(is_offered).collect()),
+            ),
+            _ => None,
+        };
+
+        self.options.set(options);
+        if let Some(value) = cleared {
+            form.set_field_path_value(self.field(), value);
+        }
+    }
+}
@@ -0,0 +1,715 @@
+use crate::core::async_persistence::{AsyncFormPersistence, PersistenceFuture};
+use crate::core::drafts::{DraftCodec, DraftInfo, DraftLoad, DraftVersioning};
+use crate::core::sensitive::FieldEncryptor;
+use crate::core::sync::{DraftListener, DraftSyncChannel, StorageEventChannel};
+use crate::core::traits::{Form, FormPersistence};
+use crate::error::FormError;
+use chrono::Utc;
+use sealed::RawStorage;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::ready;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Persist form values as JSON in the browser's `localStorage`
+///
+/// Values survive reloads and browser restarts. Sensitive fields are left
+/// out unless an encryptor is set. Outside the browser every operation fails
+/// with an error instead of panicking.
+pub type LocalStoragePersistence = StoragePersistence<LocalStorage>;
+
+/// Persist form values as JSON in the browser's `sessionStorage`
+///
+/// Values survive reloads but are dropped when the tab is closed. As every
+/// tab has storage of its own, drafts are only shared between tabs through a
+/// channel such as `BroadcastDraftChannel`.
+pub type SessionStoragePersistence = StoragePersistence<SessionStorage>;
+
+/// Persist form values as JSON in memory, for tests and server rendering
+///
+/// Clones share their entries, so a clone with another key stands in for a
+/// second storage key of the same browser, and a clone with another
+/// `MemorySyncChannel` tab for a second tab.
+pub type MemoryPersistence = StoragePersistence<MemoryStorage>;
+
+/// The draft saved under one key of a key-value storage
+///
+/// Used through [`LocalStoragePersistence`], [`SessionStoragePersistence`]
+/// and [`MemoryPersistence`], which share their configuration.
+#[derive(Debug, Clone, Default)]
+pub struct StoragePersistence<S> {
+    slot: DraftSlot,
+    storage: S,
+}
+
+/// The browser's `localStorage`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalStorage;
+
+/// The browser's `sessionStorage`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionStorage;
+
+/// Entries kept in memory, shared by clones
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage(MemoryEntries);
+
+/// A storage drafts can be kept in
+pub trait DraftStorageBackend: sealed::Backend {}
+
+impl<S: sealed::Backend> DraftStorageBackend for S {}
+
+mod sealed {
+    use crate::error::FormError;
+
+    /// Key-value storage holding the serialized drafts
+    pub trait RawStorage {
+        fn get(&self, key: &str) -> Result<Option<String>, FormError>;
+
+        fn set(&self, key: &str, value: &str) -> Result<(), FormError>;
+
+        fn remove(&self, key: &str) -> Result<(), FormError>;
+
+        /// All keys in the storage, in no particular order
+        fn keys(&self) -> Result<Vec<String>, FormError>;
+    }
+
+    pub trait Backend: Clone + Default {
+        fn raw(&self) -> &dyn RawStorage;
+    }
+
+    impl Backend for super::LocalStorage {
+        fn raw(&self) -> &dyn RawStorage {
+            &super::StorageArea::Local
+        }
+    }
+
+    impl Backend for super::SessionStorage {
+        fn raw(&self) -> &dyn RawStorage {
+            &super::StorageArea::Session
+        }
+    }
+
+    impl Backend for super::MemoryStorage {
+        fn raw(&self) -> &dyn RawStorage {
+            &self.0
+        }
+    }
+}
+
+impl<S: DraftStorageBackend> StoragePersistence<S> {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            slot: DraftSlot::new(key),
+            storage: S::default(),
+        }
+    }
+
+    /// Save drafts with a schema version and upgrade older ones on load
+    pub fn with_versioning(mut self, versioning: DraftVersioning) -> Self {
+        self.slot.codec.versioning = Some(versioning);
+        self
+    }
+
+    /// Keep sensitive fields in drafts, encrypted, instead of leaving them out
+    pub fn with_encryptor(mut self, encryptor: impl FieldEncryptor + 'static) -> Self {
+        self.slot.codec.encryptor = Some(Arc::new(encryptor));
+        self
+    }
+
+    /// Expire drafts `ttl` after they were last saved
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.slot.codec.ttl = Some(ttl);
+        self
+    }
+
+    /// Announce saved drafts on `channel` and report those of other tabs
+    pub fn with_sync_channel(mut self, channel: impl DraftSyncChannel + 'static) -> Self {
+        self.slot.sync = Some(Arc::new(channel));
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        &self.slot.key
+    }
+
+    /// This storage behind the asynchronous persistence API, with typed errors
+    pub fn into_async(self) -> AsyncStorage<Self> {
+        AsyncStorage(self)
+    }
+}
+
+impl StoragePersistence<LocalStorage> {
+    /// Report drafts other tabs save, using `storage` events
+    pub fn with_cross_tab_sync(self) -> Self {
+        self.with_sync_channel(StorageEventChannel)
+    }
+}
+
+impl StoragePersistence<MemoryStorage> {
+    /// Fail writes with a quota error once the entries would exceed `bytes`,
+    /// like Web Storage does when it is full
+    pub fn with_quota(mut self, bytes: usize) -> Self {
+        self.storage.0.quota = Some(bytes);
+        self
+    }
+
+    /// Persistence under `key` sharing the entries of this one
+    pub fn with_key(&self, key: impl Into<String>) -> Self {
+        let mut persistence = self.clone();
+        persistence.slot.key = key.into();
+        persistence
+    }
+
+    /// The stored JSON, as it would appear in Web Storage
+    pub fn raw(&self) -> Option<String> {
+        self.entries().lock().get(&self.slot.key).cloned()
+    }
+
+    /// Store `json` as is, e.g. to simulate data written by another version
+    pub fn set_raw(&self, json: impl Into<String>) {
+        self.entries()
+            .lock()
+            .insert(self.slot.key.clone(), json.into());
+    }
+
+    fn entries(&self) -> &MemoryEntries {
+        &self.storage.0
+    }
+}
+
+impl<T: Form, S: DraftStorageBackend> FormPersistence<T> for StoragePersistence<S> {
+    fn save(&self, form: &T) -> Result<(), String> {
+        self.slot
+            .save(self.storage.raw(), form)
+            .map_err(into_message)
+    }
+
+    fn load(&self) -> Result<Option<T>, String> {
+        self.load_draft().map(DraftLoad::into_values)
+    }
+
+    fn load_draft(&self) -> Result<DraftLoad<T>, String> {
+        self.slot
+            .load_draft(self.storage.raw())
+            .map_err(into_message)
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        self.slot.clear(self.storage.raw()).map_err(into_message)
+    }
+
+    fn exists(&self) -> bool {
+        self.slot.exists(self.storage.raw())
+    }
+
+    fn watch(&self, listener: Arc<dyn Fn(Option<T>) + Send + Sync>) -> bool {
+        self.slot.watch(listener)
+    }
+}
+
+/// Web Storage or memory persistence behind [`AsyncFormPersistence`]
+///
+/// Operations complete right away but report `FormError`s, so a full
+/// `localStorage` can be told apart and e.g. passed over by `QuotaFallback`.
+#[derive(Debug, Clone)]
+pub struct AsyncStorage<P>(P);
+
+impl<P> AsyncStorage<P> {
+    pub fn new(persistence: P) -> Self {
+        Self(persistence)
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.0
+    }
+}
+
+impl<T: Form, S: DraftStorageBackend> AsyncFormPersistence<T>
+    for AsyncStorage<StoragePersistence<S>>
+{
+    fn key(&self) -> &str {
+        &self.0.slot.key
+    }
+
+    fn save<'a>(&'a self, form: &'a T) -> PersistenceFuture<'a, ()> {
+        Box::pin(ready(self.0.slot.save(self.0.storage.raw(), form)))
+    }
+
+    fn load_draft(&self) -> PersistenceFuture<'_, DraftLoad<T>> {
+        Box::pin(ready(self.0.slot.load_draft(self.0.storage.raw())))
+    }
+
+    fn clear(&self) -> PersistenceFuture<'_, ()> {
+        Box::pin(ready(self.0.slot.clear(self.0.storage.raw())))
+    }
+
+    fn exists(&self) -> PersistenceFuture<'_, bool> {
+        Box::pin(ready(Ok(self.0.slot.exists(self.0.storage.raw()))))
+    }
+}
+
+/// All drafts saved under a key prefix, e.g. for a "resume your unfinished
+/// applications" page
+///
+/// Listing purges drafts whose time to live has passed. Restoring uses the
+/// versioning and encryptor given to the manager, which should match those
+/// the drafts were saved with.
+///
+/// ```rust,ignore
+/// let manager = DraftManager::local_storage("application:");
+/// for draft in manager.list()? {
+///     // draft.key, draft.form_name, draft.field_count, draft.updated_at
+/// }
+/// if let Some(values) = manager.restore::<Application>("application:42")?.into_values() {
+///     form.set_values(values);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DraftManager {
+    prefix: String,
+    storage: DraftStorage,
+    codec: DraftCodec,
+}
+
+#[derive(Debug, Clone)]
+enum DraftStorage {
+    Web(StorageArea),
+    Memory(MemoryEntries),
+}
+
+impl DraftStorage {
+    fn raw(&self) -> &dyn RawStorage {
+        match self {
+            DraftStorage::Web(area) => area,
+            DraftStorage::Memory(entries) => entries,
+        }
+    }
+}
+
+impl DraftManager {
+    /// Drafts in `localStorage` whose keys start with `prefix`
+    pub fn local_storage(prefix: impl Into<String>) -> Self {
+        Self::with_storage(prefix, DraftStorage::Web(StorageArea::Local))
+    }
+
+    /// Drafts in `sessionStorage` whose keys start with `prefix`
+    pub fn session_storage(prefix: impl Into<String>) -> Self {
+        Self::with_storage(prefix, DraftStorage::Web(StorageArea::Session))
+    }
+
+    /// Drafts sharing the entries of `persistence` whose keys start with `prefix`
+    pub fn memory(persistence: &MemoryPersistence, prefix: impl Into<String>) -> Self {
+        Self::with_storage(prefix, DraftStorage::Memory(persistence.entries().clone()))
+    }
+
+    fn with_storage(prefix: impl Into<String>, storage: DraftStorage) -> Self {
+        Self {
+            prefix: prefix.into(),
+            storage,
+            codec: DraftCodec::default(),
+        }
+    }
+
+    /// Restore drafts with a schema version, upgrading older ones
+    pub fn with_versioning(mut self, versioning: DraftVersioning) -> Self {
+        self.codec.versioning = Some(versioning);
+        self
+    }
+
+    /// Restore the encrypted sensitive fields of drafts
+    pub fn with_encryptor(mut self, encryptor: impl FieldEncryptor + 'static) -> Self {
+        self.codec.encryptor = Some(Arc::new(encryptor));
+        self
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The drafts under the prefix, most recently edited first
+    ///
+    /// Entries that are not drafts are left out, and expired drafts are
+    /// deleted.
+    pub fn list(&self) -> Result<Vec<DraftInfo>, String> {
+        let now = Utc::now();
+        let mut drafts = Vec::new();
+        for info in self.infos()? {
+            if info.is_expired_at(now) {
+                self.delete(&info.key)?;
+            } else {
+                drafts.push(info);
+            }
+        }
+        drafts.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then(a.key.cmp(&b.key)));
+        Ok(drafts)
+    }
+
+    /// Delete the drafts under the prefix whose time to live has passed
+    ///
+    /// Returns the keys of the deleted drafts.
+    pub fn purge_expired(&self) -> Result<Vec<String>, String> {
+        let now = Utc::now();
+        let mut purged = Vec::new();
+        for info in self.infos()? {
+            if info.is_expired_at(now) {
+                self.delete(&info.key)?;
+                purged.push(info.key);
+            }
+        }
+        purged.sort();
+        Ok(purged)
+    }
+
+    /// Delete the draft saved under `key`
+    pub fn delete(&self, key: &str) -> Result<(), String> {
+        self.storage.raw().remove(key).map_err(into_message)
+    }
+
+    /// Restore the draft saved under `key` as a `T`
+    pub fn restore<T: Form>(&self, key: &str) -> Result<DraftLoad<T>, String> {
+        let slot = DraftSlot {
+            key: key.to_string(),
+            codec: self.codec.clone(),
+            sync: None,
+        };
+        slot.load_draft(self.storage.raw()).map_err(into_message)
+    }
+
+    fn infos(&self) -> Result<Vec<DraftInfo>, String> {
+        let storage = self.storage.raw();
+        let mut infos = Vec::new();
+        for key in storage.keys().map_err(into_message)? {
+            if !key.starts_with(&self.prefix) {
+                continue;
+            }
+            let Some(json) = storage.get(&key).map_err(into_message)? else {
+                continue;
+            };
+            infos.extend(DraftInfo::read(&key, &json));
+        }
+        Ok(infos)
+    }
+}
+
+/// A single entry of one of the storages, for data other than drafts
+#[derive(Debug, Clone)]
+pub(crate) struct StorageEntry {
+    key: String,
+    storage: DraftStorage,
+}
+
+impl StorageEntry {
+    pub(crate) fn local_storage(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            storage: DraftStorage::Web(StorageArea::Local),
+        }
+    }
+
+    pub(crate) fn session_storage(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            storage: DraftStorage::Web(StorageArea::Session),
+        }
+    }
+
+    /// The entry `key` among those of `persistence`
+    pub(crate) fn memory(persistence: &MemoryPersistence, key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            storage: DraftStorage::Memory(persistence.entries().clone()),
+        }
+    }
+
+    pub(crate) fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn read(&self) -> Result<Option<String>, FormError> {
+        self.storage.raw().get(&self.key)
+    }
+
+    pub(crate) fn write(&self, value: &str) -> Result<(), FormError> {
+        self.storage.raw().set(&self.key, value)
+    }
+
+    pub(crate) fn remove(&self) -> Result<(), FormError> {
+        self.storage.raw().remove(&self.key)
+    }
+}
+
+/// The message of a storage error, as the synchronous persistence API reports it
+fn into_message(error: FormError) -> String {
+    error.message().to_string()
+}
+
+/// The draft stored under one key, shared by all persistence backends
+#[derive(Clone, Default)]
+struct DraftSlot {
+    key: String,
+    codec: DraftCodec,
+    sync: Option<Arc<dyn DraftSyncChannel>>,
+}
+
+impl fmt::Debug for DraftSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DraftSlot")
+            .field("key", &self.key)
+            .field("codec", &self.codec)
+            .field("synced", &self.sync.is_some())
+            .finish()
+    }
+}
+
+impl DraftSlot {
+    fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            ..Default::default()
+        }
+    }
+
+    fn save<T: Form>(&self, storage: &dyn RawStorage, form: &T) -> Result<(), FormError> {
+        // A draft that cannot be read is replaced like a missing one
+        let previous = storage.get(&self.key).ok().flatten();
+        let json = self
+            .codec
+            .encode(form, previous.as_deref())
+            .map_err(|e| FormError::serialization_error(e, None))?;
+        storage.set(&self.key, &json)?;
+        if let Some(sync) = &self.sync {
+            sync.publish(&self.key, Some(&json));
+        }
+        Ok(())
+    }
+
+    fn load_draft<T: Form>(&self, storage: &dyn RawStorage) -> Result<DraftLoad<T>, FormError> {
+        let Some(json) = storage.get(&self.key)? else {
+            return Ok(DraftLoad::Empty);
+        };
+        let draft = self
+            .codec
+            .decode(&json)
+            .map_err(|e| FormError::serialization_error(e, None))?;
+        if matches!(draft, DraftLoad::Discarded { .. }) {
+            storage.remove(&self.key)?;
+        }
+        Ok(draft)
+    }
+
+    fn clear(&self, storage: &dyn RawStorage) -> Result<(), FormError> {
+        storage.remove(&self.key)?;
+        if let Some(sync) = &self.sync {
+            sync.publish(&self.key, None);
+        }
+        Ok(())
+    }
+
+    fn exists(&self, storage: &dyn RawStorage) -> bool {
+        matches!(storage.get(&self.key), Ok(Some(_)))
+    }
+
+    fn watch<T: Form>(&self, listener: Arc<dyn Fn(Option<T>) + Send + Sync>) -> bool {
+        let Some(sync) = &self.sync else {
+            return false;
+        };
+        let codec = self.codec.clone();
+        let on_draft: DraftListener = Arc::new(move |json: Option<String>| {
+            let values = match json.map(|json| codec.decode::<T>(&json)) {
+                Some(Ok(draft)) => draft.into_values(),
+                Some(Err(error)) => {
+                    log::warn!("Ignoring a draft from another tab: {}", error);
+                    return;
+                }
+                None => None,
+            };
+            listener(values);
+        });
+        match sync.subscribe(&self.key, on_draft) {
+            Ok(()) => true,
+            Err(error) => {
+                log::warn!("Cross-tab sync is unavailable: {}", error);
+                false
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct MemoryEntries {
+    entries: Arc<Mutex<HashMap<String, String>>>,
+    quota: Option<usize>,
+}
+
+impl MemoryEntries {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, String>> {
+        // A panic while holding the lock cannot leave a map half written
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl RawStorage for MemoryEntries {
+    fn get(&self, key: &str) -> Result<Option<String>, FormError> {
+        Ok(self.lock().get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), FormError> {
+        let mut entries = self.lock();
+        if let Some(quota) = self.quota {
+            let others: usize = entries
+                .iter()
+                .filter(|(stored_key, _)| stored_key.as_str() != key)
+                .map(|(stored_key, stored)| stored_key.len() + stored.len())
+                .sum();
+            if others + key.len() + value.len() > quota {
+                return Err(FormError::quota_exceeded(
+                    format!("Failed to write {}: quota of {} bytes exceeded", key, quota),
+                    "memory",
+                ));
+            }
+        }
+        entries.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), FormError> {
+        self.lock().remove(key);
+        Ok(())
+    }
+
+    fn keys(&self) -> Result<Vec<String>, FormError> {
+        Ok(self.lock().keys().cloned().collect())
+    }
+}
+
+impl RawStorage for StorageArea {
+    fn get(&self, key: &str) -> Result<Option<String>, FormError> {
+        web_storage::get(*self, key)
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), FormError> {
+        web_storage::set(*self, key, value)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), FormError> {
+        web_storage::remove(*self, key)
+    }
+
+    fn keys(&self) -> Result<Vec<String>, FormError> {
+        web_storage::keys(*self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StorageArea {
+    Local,
+    Session,
+}
+
+impl StorageArea {
+    fn storage_type(self) -> &'static str {
+        match self {
+            StorageArea::Local => "localStorage",
+            StorageArea::Session => "sessionStorage",
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web_storage {
+    use super::StorageArea;
+    use crate::error::FormError;
+    use wasm_bindgen::JsCast;
+
+    fn storage(area: StorageArea) -> Result<web_sys::Storage, FormError> {
+        let unavailable =
+            |message: &str| FormError::persistence_error(message, area.storage_type());
+        let window = web_sys::window().ok_or_else(|| unavailable("No window available"))?;
+        let storage = match area {
+            StorageArea::Local => window.local_storage(),
+            StorageArea::Session => window.session_storage(),
+        };
+        storage
+            .ok()
+            .flatten()
+            .ok_or_else(|| unavailable(&format!("{:?} storage is not available", area)))
+    }
+
+    pub(super) fn get(area: StorageArea, key: &str) -> Result<Option<String>, FormError> {
+        storage(area)?.get_item(key).map_err(|e| {
+            FormError::persistence_error(
+                format!("Failed to read {}: {:?}", key, e),
+                area.storage_type(),
+            )
+        })
+    }
+
+    pub(super) fn set(area: StorageArea, key: &str, value: &str) -> Result<(), FormError> {
+        storage(area)?.set_item(key, value).map_err(|e| {
+            let message = format!("Failed to write {}: {:?}", key, e);
+            let quota_exceeded = e
+                .dyn_ref::<web_sys::DomException>()
+                .is_some_and(|e| e.name() == "QuotaExceededError");
+            if quota_exceeded {
+                FormError::quota_exceeded(message, area.storage_type())
+            } else {
+                FormError::persistence_error(message, area.storage_type())
+            }
+        })
+    }
+
+    pub(super) fn keys(area: StorageArea) -> Result<Vec<String>, FormError> {
+        let storage = storage(area)?;
+        let length = storage.length().map_err(|e| {
+            FormError::persistence_error(
+                format!("Failed to list keys: {:?}", e),
+                area.storage_type(),
+            )
+        })?;
+        Ok((0..length)
+            .filter_map(|index| storage.key(index).ok().flatten())
+            .collect())
+    }
+
+    pub(super) fn remove(area: StorageArea, key: &str) -> Result<(), FormError> {
+        storage(area)?.remove_item(key).map_err(|e| {
+            FormError::persistence_error(
+                format!("Failed to remove {}: {:?}", key, e),
+                area.storage_type(),
+            )
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod web_storage {
+    use super::StorageArea;
+    use crate::error::FormError;
+
+    fn unavailable(area: StorageArea) -> FormError {
+        FormError::persistence_error(
+            format!("{:?} storage is only available in the browser", area),
+            area.storage_type(),
+        )
+    }
+
+    pub(super) fn get(area: StorageArea, _key: &str) -> Result<Option<String>, FormError> {
+        Err(unavailable(area))
+    }
+
+    pub(super) fn set(area: StorageArea, _key: &str, _value: &str) -> Result<(), FormError> {
+        Err(unavailable(area))
+    }
+
+    pub(super) fn remove(area: StorageArea, _key: &str) -> Result<(), FormError> {
+        Err(unavailable(area))
+    }
+
+    pub(super) fn keys(area: StorageArea) -> Result<Vec<String>, FormError> {
+        Err(unavailable(area))
+    }
+}
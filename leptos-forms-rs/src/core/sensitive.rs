@@ -0,0 +1,140 @@
+use crate::core::convert::{field_path, parse_field_path, FieldPathSegment};
+use crate::core::traits::{FieldMetadata, Form};
+use crate::core::types::FieldValue;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Placeholder shown instead of the value of a sensitive field
+pub const REDACTED: &str = "[redacted]";
+
+/// Encryption for sensitive values kept in persisted drafts
+///
+/// Without an encryptor, persistence leaves sensitive fields out of drafts
+/// altogether. With one, their values are stored encrypted alongside the
+/// draft and decrypted when it is restored.
+///
+/// ```rust,ignore
+/// struct WebCrypto { key: CryptoKey }
+///
+/// impl FieldEncryptor for WebCrypto {
+///     fn encrypt(&self, plaintext: &str) -> Result<String, String> { /* ... */ }
+///     fn decrypt(&self, ciphertext: &str) -> Result<String, String> { /* ... */ }
+/// }
+///
+/// let storage = LocalStoragePersistence::new("signup").with_encryptor(WebCrypto { key });
+/// ```
+pub trait FieldEncryptor: Send + Sync {
+    fn encrypt(&self, plaintext: &str) -> Result<String, String>;
+
+    fn decrypt(&self, ciphertext: &str) -> Result<String, String>;
+}
+
+/// Paths of the sensitive fields in `form`, e.g. `password` or `cards[0].number`
+pub fn sensitive_field_paths<T: Form>(form: &T) -> Vec<String> {
+    let Ok(values) = serde_json::to_value(form) else {
+        return Vec::new();
+    };
+    json_sensitive_paths(&T::field_metadata(), &values)
+}
+
+/// Form data with the values of sensitive fields replaced by [`REDACTED`]
+///
+/// This is what analytics and debugging tools should be given.
+pub fn redacted_form_data<T: Form>(form: &T) -> HashMap<String, FieldValue> {
+    let mut values = FieldValue::Object(form.get_form_data());
+    for path in sensitive_field_paths(form) {
+        values.set_path(
+            &parse_field_path(&path),
+            FieldValue::String(REDACTED.to_string()),
+        );
+    }
+    match values {
+        FieldValue::Object(values) => values,
+        _ => HashMap::new(),
+    }
+}
+
+/// A copy of `form` with its sensitive fields blanked
+///
+/// Use it for values written where anyone can read them, such as the
+/// server-rendered page. Forms that cannot be blanked are replaced by their
+/// default values.
+pub fn without_sensitive_values<T: Form>(form: &T) -> T {
+    let Ok(mut values) = serde_json::to_value(form) else {
+        return T::default_values();
+    };
+    for path in json_sensitive_paths(&T::field_metadata(), &values) {
+        if let Some(value) = json_path_mut(&mut values, &path) {
+            *value = blank_json(value);
+        }
+    }
+    serde_json::from_value(values).unwrap_or_else(|_| T::default_values())
+}
+
+/// Sensitive paths within the serialized values of a form
+pub(crate) fn json_sensitive_paths(fields: &[FieldMetadata], values: &Value) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_sensitive(fields, values, "", &mut paths);
+    paths
+}
+
+fn collect_sensitive(
+    fields: &[FieldMetadata],
+    values: &Value,
+    path: &str,
+    paths: &mut Vec<String>,
+) {
+    let Value::Object(object) = values else {
+        return;
+    };
+    for field in fields {
+        let Some(value) = object.get(&field.name) else {
+            continue;
+        };
+        let field_path = field_path(path, &field.name);
+        if field.is_sensitive() {
+            paths.push(field_path);
+            continue;
+        }
+        if field.nested_fields.is_empty() {
+            continue;
+        }
+        match value {
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = format!("{}[{}]", field_path, index);
+                    collect_sensitive(&field.nested_fields, item, &item_path, paths);
+                }
+            }
+            nested => collect_sensitive(&field.nested_fields, nested, &field_path, paths),
+        }
+    }
+}
+
+/// The serialized value at `path`
+pub(crate) fn json_path_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    parse_field_path(path)
+        .iter()
+        .try_fold(value, |value, segment| match (value, segment) {
+            (Value::Object(object), FieldPathSegment::Key(key)) => object.get_mut(key),
+            (Value::Array(items), FieldPathSegment::Index(index)) => items.get_mut(*index),
+            _ => None,
+        })
+}
+
+/// An empty value of the same JSON kind, so the form still deserializes
+pub(crate) fn blank_json(value: &Value) -> Value {
+    match value {
+        Value::String(_) => Value::String(String::new()),
+        Value::Number(_) => Value::from(0),
+        Value::Bool(_) => Value::Bool(false),
+        Value::Array(_) => Value::Array(Vec::new()),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), blank_json(value)))
+                .collect(),
+        ),
+        Value::Null => Value::Null,
+    }
+}
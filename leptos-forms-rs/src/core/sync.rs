@@ -0,0 +1,328 @@
+use crate::core::traits::Form;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Called with the draft another tab saved under a key, or `None` once cleared
+pub type DraftListener = Arc<dyn Fn(Option<String>) + Send + Sync>;
+
+/// How tabs tell each other about the drafts they save
+///
+/// Persistence publishes every draft it saves or clears, and subscribes to
+/// those of other tabs when a form watches for them. A tab never receives
+/// its own drafts.
+pub trait DraftSyncChannel: Send + Sync {
+    /// Announce that the draft under `key` was saved, or cleared with `None`
+    fn publish(&self, key: &str, draft: Option<&str>);
+
+    /// Call `listener` whenever another tab saves or clears the draft under `key`
+    fn subscribe(&self, key: &str, listener: DraftListener) -> Result<(), String>;
+}
+
+/// In-memory channel between simulated tabs, for tests and server rendering
+///
+/// Each [`MemorySyncChannel::tab`] is another endpoint on the same channel,
+/// and drafts published by one are delivered right away to the others.
+#[derive(Clone, Default)]
+pub struct MemorySyncChannel {
+    tab: u64,
+    hub: Arc<MemoryHub>,
+}
+
+#[derive(Default)]
+struct MemoryHub {
+    next_tab: AtomicU64,
+    listeners: Mutex<Vec<(u64, String, DraftListener)>>,
+}
+
+impl MemorySyncChannel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Another tab on the same channel
+    pub fn tab(&self) -> Self {
+        Self {
+            tab: self.hub.next_tab.fetch_add(1, Ordering::Relaxed) + 1,
+            hub: self.hub.clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for MemorySyncChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemorySyncChannel")
+            .field("tab", &self.tab)
+            .finish()
+    }
+}
+
+impl DraftSyncChannel for MemorySyncChannel {
+    fn publish(&self, key: &str, draft: Option<&str>) {
+        // Listeners may save in turn, so they run without the lock held
+        let listeners: Vec<DraftListener> = self
+            .hub
+            .listeners
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .filter(|(tab, listening_key, _)| *tab != self.tab && listening_key == key)
+            .map(|(_, _, listener)| listener.clone())
+            .collect();
+        for listener in listeners {
+            listener(draft.map(str::to_string));
+        }
+    }
+
+    fn subscribe(&self, key: &str, listener: DraftListener) -> Result<(), String> {
+        self.hub
+            .listeners
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push((self.tab, key.to_string(), listener));
+        Ok(())
+    }
+}
+
+/// Channel built on the `storage` events `localStorage` raises in other tabs
+///
+/// The browser announces every write by itself, so publishing does nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StorageEventChannel;
+
+impl DraftSyncChannel for StorageEventChannel {
+    fn publish(&self, _key: &str, _draft: Option<&str>) {}
+
+    fn subscribe(&self, key: &str, listener: DraftListener) -> Result<(), String> {
+        browser::listen_to_storage(key.to_string(), listener)
+    }
+}
+
+/// Channel built on a `BroadcastChannel` of the given name
+///
+/// Works with any storage, including `sessionStorage`, which is not shared
+/// between tabs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastDraftChannel {
+    name: String,
+}
+
+impl BroadcastDraftChannel {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// What a [`BroadcastDraftChannel`] sends to other tabs
+#[derive(Debug, Serialize, Deserialize)]
+struct DraftMessage {
+    key: String,
+    draft: Option<String>,
+}
+
+impl DraftSyncChannel for BroadcastDraftChannel {
+    fn publish(&self, key: &str, draft: Option<&str>) {
+        let message = DraftMessage {
+            key: key.to_string(),
+            draft: draft.map(str::to_string),
+        };
+        match serde_json::to_string(&message) {
+            Ok(message) => {
+                if let Err(error) = browser::broadcast(&self.name, &message) {
+                    log::warn!("Failed to announce draft {}: {}", key, error);
+                }
+            }
+            Err(error) => log::warn!("Failed to announce draft {}: {}", key, error),
+        }
+    }
+
+    fn subscribe(&self, key: &str, listener: DraftListener) -> Result<(), String> {
+        let key = key.to_string();
+        browser::listen_to_broadcast(
+            &self.name,
+            Arc::new(
+                move |message: String| match serde_json::from_str::<DraftMessage>(&message) {
+                    Ok(message) if message.key == key => listener(message.draft),
+                    Ok(_) => {}
+                    Err(error) => log::warn!("Ignoring malformed draft message: {}", error),
+                },
+            ),
+        )
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::DraftListener;
+    use std::sync::Arc;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    pub(super) fn listen_to_storage(key: String, listener: DraftListener) -> Result<(), String> {
+        let window = web_sys::window().ok_or("No window available")?;
+        let on_storage =
+            Closure::<dyn Fn(web_sys::StorageEvent)>::new(move |event: web_sys::StorageEvent| {
+                // A `None` key means all of storage was cleared
+                match event.key() {
+                    Some(changed) if changed == key => listener(event.new_value()),
+                    None => listener(None),
+                    Some(_) => {}
+                }
+            });
+        window
+            .add_event_listener_with_callback("storage", on_storage.as_ref().unchecked_ref())
+            .map_err(|e| format!("Failed to listen to storage events: {:?}", e))?;
+        // The listener lives as long as the page
+        on_storage.forget();
+        Ok(())
+    }
+
+    pub(super) fn broadcast(name: &str, message: &str) -> Result<(), String> {
+        let channel = web_sys::BroadcastChannel::new(name)
+            .map_err(|e| format!("Failed to open channel {}: {:?}", name, e))?;
+        let posted = channel
+            .post_message(&JsValue::from_str(message))
+            .map_err(|e| format!("Failed to post to channel {}: {:?}", name, e));
+        channel.close();
+        posted
+    }
+
+    pub(super) fn listen_to_broadcast(
+        name: &str,
+        listener: Arc<dyn Fn(String) + Send + Sync>,
+    ) -> Result<(), String> {
+        let channel = web_sys::BroadcastChannel::new(name)
+            .map_err(|e| format!("Failed to open channel {}: {:?}", name, e))?;
+        let on_message =
+            Closure::<dyn Fn(web_sys::MessageEvent)>::new(move |event: web_sys::MessageEvent| {
+                if let Some(message) = event.data().as_string() {
+                    listener(message);
+                }
+            });
+        channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        // Both stay open as long as the page
+        on_message.forget();
+        std::mem::forget(channel);
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod browser {
+    use super::DraftListener;
+    use std::sync::Arc;
+
+    pub(super) fn listen_to_storage(_key: String, _listener: DraftListener) -> Result<(), String> {
+        Err("Storage events are only available in the browser".to_string())
+    }
+
+    pub(super) fn broadcast(_name: &str, _message: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub(super) fn listen_to_broadcast(
+        _name: &str,
+        _listener: Arc<dyn Fn(String) + Send + Sync>,
+    ) -> Result<(), String> {
+        Err("BroadcastChannel is only available in the browser".to_string())
+    }
+}
+
+/// Fields that differ between the form and a draft saved in another tab
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DraftConflict {
+    pub fields: Vec<String>,
+}
+
+/// Outcome of merging a draft saved in another tab into the form
+#[derive(Debug, Clone, PartialEq)]
+pub struct DraftMerge<T> {
+    /// The local values with the remote changes applied
+    pub values: T,
+    /// Fields taken from the remote draft
+    pub applied: Vec<String>,
+    /// Fields changed both here and in the other tab, left as they are here
+    pub conflicts: Vec<String>,
+}
+
+/// Merge the `remote` draft into the `local` values, field by field
+///
+/// `base` holds the values both tabs last agreed on, i.e. the last draft
+/// saved, restored or merged here. A field that was not edited locally since
+/// takes the remote value; a field edited on both sides is a conflict.
+/// Sensitive fields never leave a tab's memory, so they are kept as they are.
+pub fn merge_remote_draft<T: Form>(base: &T, local: &T, remote: &T) -> DraftMerge<T> {
+    let (Ok(Value::Object(base)), Ok(Value::Object(mut merged)), Ok(Value::Object(remote))) = (
+        serde_json::to_value(base),
+        serde_json::to_value(local),
+        serde_json::to_value(remote),
+    ) else {
+        return DraftMerge {
+            values: local.clone(),
+            applied: Vec::new(),
+            conflicts: Vec::new(),
+        };
+    };
+
+    let sensitive = sensitive_fields::<T>();
+    let mut applied = Vec::new();
+    let mut conflicts = Vec::new();
+    for (name, remote_value) in remote {
+        if sensitive.contains(&name) || merged.get(&name) == Some(&remote_value) {
+            continue;
+        }
+        if merged.get(&name) == base.get(&name) {
+            merged.insert(name.clone(), remote_value);
+            applied.push(name);
+        } else {
+            conflicts.push(name);
+        }
+    }
+    applied.sort();
+    conflicts.sort();
+
+    match serde_json::from_value(Value::Object(merged)) {
+        Ok(values) => DraftMerge {
+            values,
+            applied,
+            conflicts,
+        },
+        Err(_) => DraftMerge {
+            values: local.clone(),
+            applied: Vec::new(),
+            conflicts: [applied, conflicts].concat(),
+        },
+    }
+}
+
+/// Fields whose values differ between `local` and `remote`
+pub(crate) fn differing_fields<T: Form>(local: &T, remote: &T) -> Vec<String> {
+    let (Ok(Value::Object(local)), Ok(Value::Object(remote))) =
+        (serde_json::to_value(local), serde_json::to_value(remote))
+    else {
+        return Vec::new();
+    };
+    let sensitive = sensitive_fields::<T>();
+    let mut fields: Vec<String> = remote
+        .into_iter()
+        .filter(|(name, value)| !sensitive.contains(name) && local.get(name) != Some(value))
+        .map(|(name, _)| name)
+        .collect();
+    fields.sort();
+    fields
+}
+
+/// Top-level fields that are never shared between tabs
+fn sensitive_fields<T: Form>() -> BTreeSet<String> {
+    T::field_metadata()
+        .into_iter()
+        .filter(|field| field.is_sensitive())
+        .map(|field| field.name)
+        .collect()
+}
@@ -1,7 +1,7 @@
 use crate::core::types::{FieldType, FieldValue};
-use crate::validation::Validator;
+use crate::validation::{FieldCondition, Validator};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// Metadata for a form field
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +13,18 @@ pub struct FieldMetadata {
     pub dependencies: Vec<String>,
     pub attributes: HashMap<String, String>,
     pub validators: Vec<Validator>,
+    /// Metadata of the fields of a nested form or of an array of nested forms
+    #[serde(default)]
+    pub nested_fields: Vec<FieldMetadata>,
+    /// Show the field only while this condition holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visible_when: Option<FieldCondition>,
+    /// Allow editing the field only while this condition holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled_when: Option<FieldCondition>,
+    /// Keep the value out of persisted drafts, analytics and debug output
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sensitive: bool,
 }
 
 impl Default for FieldMetadata {
@@ -25,10 +37,160 @@ impl Default for FieldMetadata {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: Vec::new(),
+            nested_fields: Vec::new(),
+            visible_when: None,
+            enabled_when: None,
+            sensitive: false,
         }
     }
 }
 
+impl FieldMetadata {
+    /// Metadata for an optional field without validators
+    ///
+    /// The other settings are added with the builder methods, so metadata
+    /// written by hand keeps compiling when new settings are added:
+    ///
+    /// ```rust
+    /// # use leptos_forms_rs::core::{FieldMetadata, FieldType};
+    /// # use leptos_forms_rs::validation::Validator;
+    /// let email = FieldMetadata::new("email", FieldType::Email)
+    ///     .required()
+    ///     .validator(Validator::Email)
+    ///     .attribute("label", "Email address");
+    /// ```
+    pub fn new(name: impl Into<String>, field_type: FieldType) -> Self {
+        Self {
+            name: name.into(),
+            field_type,
+            ..Default::default()
+        }
+    }
+
+    /// Require a value, checked before the other validators
+    pub fn required(mut self) -> Self {
+        self.is_required = true;
+        if !self.validators.contains(&Validator::Required) {
+            self.validators.insert(0, Validator::Required);
+        }
+        self
+    }
+
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    pub fn default_value(mut self, value: FieldValue) -> Self {
+        self.default_value = Some(value);
+        self
+    }
+
+    /// A field whose value this one is computed from
+    pub fn depends_on(mut self, name: impl Into<String>) -> Self {
+        self.dependencies.push(name.into());
+        self
+    }
+
+    pub fn attribute(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+
+    /// The fields of a nested form or of each item of an array of them
+    pub fn nested_fields(mut self, fields: Vec<FieldMetadata>) -> Self {
+        self.nested_fields = fields;
+        self
+    }
+
+    pub fn visible_when(mut self, condition: FieldCondition) -> Self {
+        self.visible_when = Some(condition);
+        self
+    }
+
+    pub fn enabled_when(mut self, condition: FieldCondition) -> Self {
+        self.enabled_when = Some(condition);
+        self
+    }
+
+    /// Keep the value out of persisted drafts, analytics and debug output
+    pub fn sensitive(mut self) -> Self {
+        self.sensitive = true;
+        self
+    }
+
+    /// Display label from the `label` attribute, or a humanized field name
+    pub fn label(&self) -> String {
+        if let Some(label) = self.attributes.get("label") {
+            return label.clone();
+        }
+        let words: Vec<&str> = self
+            .name
+            .split(['_', '-', '.'])
+            .filter(|word| !word.is_empty())
+            .collect();
+        let mut label = words.join(" ");
+        if let Some(first) = label.get(..1) {
+            label = first.to_uppercase() + &label[1..];
+        }
+        label
+    }
+
+    /// Position from the `order` attribute; unordered fields keep declaration order
+    pub fn order(&self) -> Option<i32> {
+        self.attributes
+            .get("order")
+            .and_then(|o| o.trim().parse().ok())
+    }
+
+    /// Group from the `group` attribute, rendered as a shared fieldset
+    pub fn group(&self) -> Option<&str> {
+        self.attributes.get("group").map(String::as_str)
+    }
+
+    /// Whether the `hidden` attribute keeps the field out of rendered forms
+    pub fn is_hidden(&self) -> bool {
+        self.attributes
+            .get("hidden")
+            .is_some_and(|hidden| hidden.is_empty() || hidden == "true")
+    }
+
+    /// Whether the `readonly` attribute, set on computed fields, prevents editing
+    pub fn is_readonly(&self) -> bool {
+        self.attributes
+            .get("readonly")
+            .is_some_and(|readonly| readonly.is_empty() || readonly == "true")
+    }
+
+    /// Whether the value must not leave the form in the clear
+    ///
+    /// Password fields are always sensitive.
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive || matches!(self.field_type, FieldType::Password)
+    }
+
+    /// Whether `visible_when` holds for the values of the object holding the field
+    pub fn is_visible_in(&self, values: &HashMap<String, FieldValue>) -> bool {
+        condition_holds(self.visible_when.as_ref(), values)
+    }
+
+    /// Whether `enabled_when` holds for the values of the object holding the field
+    pub fn is_enabled_in(&self, values: &HashMap<String, FieldValue>) -> bool {
+        condition_holds(self.enabled_when.as_ref(), values)
+    }
+}
+
+fn condition_holds(
+    condition: Option<&FieldCondition>,
+    values: &HashMap<String, FieldValue>,
+) -> bool {
+    condition.is_none_or(|condition| {
+        condition.evaluate_with(&|path| {
+            crate::core::get_object_path_value(values, path).unwrap_or_default()
+        })
+    })
+}
+
 /// Schema for a form
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FormSchema {
@@ -53,6 +215,55 @@ impl FormSchema {
     pub fn get_field(&self, name: &str) -> Option<&FieldMetadata> {
         self.field_metadata.iter().find(|f| f.name == name)
     }
+
+    /// Stable hash of the field names and value shapes, as 16 hex digits
+    ///
+    /// Labels, validators and field order do not count, so the fingerprint
+    /// only changes when saved values may no longer fit the form.
+    pub fn fingerprint(&self) -> String {
+        // FNV-1a, which unlike `DefaultHasher` is the same across releases
+        let hash = fields_shape(&self.field_metadata)
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+            });
+        format!("{:016x}", hash)
+    }
+}
+
+fn fields_shape(fields: &[FieldMetadata]) -> String {
+    let mut shapes: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            let mut shape = format!("{}:{}", field.name, type_shape(&field.field_type));
+            if !field.nested_fields.is_empty() {
+                shape.push_str(&format!("{{{}}}", fields_shape(&field.nested_fields)));
+            }
+            shape
+        })
+        .collect();
+    shapes.sort();
+    shapes.join(",")
+}
+
+fn type_shape(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Number(_) => "number".to_string(),
+        FieldType::Boolean => "boolean".to_string(),
+        FieldType::Date => "date".to_string(),
+        FieldType::DateTime => "datetime".to_string(),
+        FieldType::File(_) => "file".to_string(),
+        FieldType::Nested(_) => "object".to_string(),
+        FieldType::MultiSelect(_) => "[string]".to_string(),
+        FieldType::Array(item) => format!("[{}]", type_shape(item)),
+        FieldType::Text
+        | FieldType::Email
+        | FieldType::Password
+        | FieldType::Select(_)
+        | FieldType::RichText
+        | FieldType::Markdown
+        | FieldType::Code => "string".to_string(),
+    }
 }
 
 /// Form state for internal management
@@ -62,6 +273,8 @@ pub struct FormState<T: Form> {
     pub errors: crate::validation::ValidationErrors,
     pub is_dirty: bool,
     pub is_submitting: bool,
+    /// Paths of the fields the user has left after focusing them
+    pub touched: BTreeSet<String>,
 }
 
 impl<T: Form> FormState<T> {
@@ -71,6 +284,7 @@ impl<T: Form> FormState<T> {
             errors: crate::validation::ValidationErrors::new(),
             is_dirty: false,
             is_submitting: false,
+            touched: BTreeSet::new(),
         }
     }
 
@@ -101,6 +315,15 @@ impl<T: Form> FormState<T> {
         self
     }
 
+    pub fn mark_touched(mut self, path: &str) -> Self {
+        self.touched.insert(path.to_string());
+        self
+    }
+
+    pub fn is_touched(&self, path: &str) -> bool {
+        self.touched.contains(path)
+    }
+
     pub fn is_valid(&self) -> bool {
         self.errors.is_empty()
     }
@@ -110,10 +333,32 @@ impl<T: Form> FormState<T> {
         self.errors = crate::validation::ValidationErrors::new();
         self.is_dirty = false;
         self.is_submitting = false;
+        self.touched.clear();
         self
     }
 }
 
+/// The parts of a [`FormState`] the server hands over to the client
+///
+/// Serialized into the server-rendered page so that a hydrating client
+/// starts from the values, errors and touched fields the server rendered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormSnapshot<T> {
+    pub values: T,
+    pub errors: crate::validation::ValidationErrors,
+    pub touched: BTreeSet<String>,
+}
+
+impl<T: Form> From<FormState<T>> for FormSnapshot<T> {
+    fn from(state: FormState<T>) -> Self {
+        Self {
+            values: state.values,
+            errors: state.errors,
+            touched: state.touched,
+        }
+    }
+}
+
 /// Core form trait that all forms must implement
 pub trait Form: for<'de> Deserialize<'de> + Serialize + Clone + 'static {
     /// Get metadata for all fields in the form
@@ -125,10 +370,18 @@ pub trait Form: for<'de> Deserialize<'de> + Serialize + Clone + 'static {
     /// Get default values for the form
     fn default_values() -> Self;
 
+    /// Read-only fields derived from other fields, recomputed by `FormHandle`
+    fn computed_fields() -> Vec<crate::core::ComputedField<Self>> {
+        Vec::new()
+    }
+
     /// Get the form schema
     fn schema() -> FormSchema {
         FormSchema {
             name: std::any::type_name::<Self>()
+                .split('<')
+                .next()
+                .unwrap_or_default()
                 .split("::")
                 .last()
                 .unwrap_or("unknown")
@@ -204,11 +457,31 @@ pub trait FormPersistence<T: Form> {
     /// Load form state from storage
     fn load(&self) -> Result<Option<T>, String>;
 
+    /// Load form state along with how it was restored
+    ///
+    /// Storage that keeps versioned drafts reports migrated, partially
+    /// restored and stale drafts; by default a loaded value counts as restored.
+    fn load_draft(&self) -> Result<crate::core::DraftLoad<T>, String> {
+        self.load().map(|values| match values {
+            Some(values) => crate::core::DraftLoad::Restored(values),
+            None => crate::core::DraftLoad::Empty,
+        })
+    }
+
     /// Clear saved form state
     fn clear(&self) -> Result<(), String>;
 
     /// Check if form state exists in storage
     fn exists(&self) -> bool;
+
+    /// Call `listener` whenever another tab saves or clears the form state
+    ///
+    /// Returns whether changes from other tabs can be watched at all; storage
+    /// without a sync channel reports none.
+    fn watch(&self, listener: std::sync::Arc<dyn Fn(Option<T>) + Send + Sync>) -> bool {
+        let _ = listener;
+        false
+    }
 }
 
 /// Trait for form analytics
@@ -219,6 +492,17 @@ pub trait FormAnalytics<T: Form> {
     /// Track field interaction
     fn track_field_interaction(&self, form_name: &str, field_name: &str, action: &str);
 
+    /// Track the values of a form, e.g. on abandonment
+    ///
+    /// Implementations are given the values from `redacted_form_data`, so
+    /// sensitive fields only ever appear as `REDACTED`.
+    fn track_form_data(&self, _form_name: &str, _data: &HashMap<String, FieldValue>) {}
+
+    /// Track the current values of `form` with sensitive fields redacted
+    fn track_values(&self, form_name: &str, form: &T) {
+        self.track_form_data(form_name, &crate::core::redacted_form_data(form));
+    }
+
     /// Track form submission
     fn track_submission(&self, form_name: &str, success: bool);
 
@@ -33,7 +33,7 @@ pub struct NumberType {
     pub step: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SelectOption {
     pub value: String,
     pub label: String,
@@ -48,7 +48,7 @@ pub struct FileConstraints {
 }
 
 /// Dynamic field value representation
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum FieldValue {
     String(String),
@@ -60,6 +60,7 @@ pub enum FieldValue {
     Array(Vec<FieldValue>),
     Object(HashMap<String, FieldValue>),
     File(FileData),
+    #[default]
     Null,
 }
 
@@ -342,14 +343,42 @@ pub enum SubmissionMode {
     Debounced(std::time::Duration),
 }
 
+/// How drafts saved in other tabs are brought into the form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossTabMode {
+    /// Apply remote changes to the fields not edited here, and report the rest
+    LiveMerge,
+    /// Apply nothing and report every field that differs
+    Conflict,
+}
+
+/// Where `use_form_persistence` keeps drafts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PersistenceStorage {
+    /// `localStorage`, kept across browser restarts
+    #[default]
+    Local,
+    /// `sessionStorage`, dropped when the tab is closed
+    Session,
+    /// Memory, dropped with the form
+    Memory,
+}
+
 /// Form state persistence options
 #[derive(Debug, Clone)]
 pub struct PersistenceOptions {
     pub enabled: bool,
     pub storage_key: Option<String>,
+    pub storage: PersistenceStorage,
     pub auto_save: bool,
     pub auto_save_interval: Option<std::time::Duration>,
+    /// Clear the draft once a submission is reported successful, e.g. by
+    /// `use_server_action_form` or `FormHandle::finish_successful_submission`.
+    /// The submit callback of `use_form` sends nothing, so it keeps the draft
     pub clear_on_submit: bool,
+    /// Keep the form in step with drafts saved in other tabs; needs storage
+    /// with a sync channel
+    pub cross_tab_sync: Option<CrossTabMode>,
 }
 
 impl Default for PersistenceOptions {
@@ -357,9 +386,23 @@ impl Default for PersistenceOptions {
         Self {
             enabled: false,
             storage_key: None,
+            storage: PersistenceStorage::Local,
             auto_save: false,
             auto_save_interval: None,
             clear_on_submit: true,
+            cross_tab_sync: None,
+        }
+    }
+}
+
+/// Restore and auto-save drafts under `storage_key` in `localStorage`
+impl From<&str> for PersistenceOptions {
+    fn from(storage_key: &str) -> Self {
+        Self {
+            enabled: true,
+            storage_key: Some(storage_key.to_string()),
+            auto_save: true,
+            ..Default::default()
         }
     }
 }
@@ -0,0 +1,161 @@
+use crate::core::convert::{
+    field_path, get_form_path_value, parse_field_path, set_form_path_value, FieldPathSegment,
+};
+use crate::core::dynamic::empty_value;
+use crate::core::traits::{FieldMetadata, Form};
+use crate::core::types::FieldValue;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// What happens to the value of a field while its `visible_when` rule hides it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HiddenFieldPolicy {
+    /// Keep the value so it reappears when the field is shown again
+    #[default]
+    Keep,
+    /// Reset the value to the field's default
+    Clear,
+}
+
+/// Paths of the fields currently hidden by their `visible_when` rule
+///
+/// Conditions are evaluated against the object holding the field, so rules on
+/// nested fields name their siblings. Fields inside a hidden nested form are
+/// not listed separately.
+pub fn hidden_field_paths<T: Form>(form: &T) -> Vec<String> {
+    let metadata = T::field_metadata();
+    let mut values = form.get_form_data();
+    let mut hidden = Vec::new();
+    prune_hidden(&metadata, &mut values, "", &mut hidden);
+    hidden.into_iter().map(|(path, _)| path).collect()
+}
+
+/// Form data with hidden fields left out, as it should be submitted
+pub fn visible_form_data<T: Form>(form: &T) -> HashMap<String, FieldValue> {
+    let metadata = T::field_metadata();
+    let mut values = form.get_form_data();
+    prune_hidden(&metadata, &mut values, "", &mut Vec::new());
+    values
+}
+
+/// Whether the field at `path` and every form enclosing it are visible
+pub fn is_field_visible<T: Form>(form: &T, path: &str) -> bool {
+    path_conditions_hold(form, path, FieldMetadata::is_visible_in)
+}
+
+/// Whether the field at `path` and every form enclosing it are enabled
+pub fn is_field_enabled<T: Form>(form: &T, path: &str) -> bool {
+    path_conditions_hold(form, path, FieldMetadata::is_enabled_in)
+}
+
+/// Whether `path` is `prefix` itself or a field inside it
+pub fn is_within_path(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+}
+
+/// Reset hidden fields to their defaults, returning whether anything changed
+pub fn clear_hidden_fields<T: Form>(form: &mut T) -> bool {
+    let metadata = T::field_metadata();
+    let defaults = T::default_values();
+    let mut changed = false;
+
+    // Clearing a field can hide the fields depending on it, so repeat until
+    // nothing changes. Values only ever move to their defaults, which bounds
+    // the number of rounds.
+    loop {
+        let mut values = form.get_form_data();
+        let mut hidden = Vec::new();
+        prune_hidden(&metadata, &mut values, "", &mut hidden);
+
+        let mut cleared = false;
+        for (path, field) in hidden {
+            let default = get_form_path_value(&defaults, &path)
+                .or_else(|| field.default_value.clone())
+                .unwrap_or_else(|| empty_value(field));
+            let current = get_form_path_value(form, &path);
+            if current.as_ref() == Some(&default) {
+                continue;
+            }
+            set_form_path_value(form, &path, default);
+            // Values the field's type rejects are left alone rather than retried
+            cleared |= get_form_path_value(form, &path) != current;
+        }
+
+        if !cleared {
+            return changed;
+        }
+        changed = true;
+    }
+}
+
+/// Remove hidden fields from `values`, recording their paths and metadata
+fn prune_hidden<'a>(
+    fields: &'a [FieldMetadata],
+    values: &mut HashMap<String, FieldValue>,
+    path: &str,
+    hidden: &mut Vec<(String, &'a FieldMetadata)>,
+) {
+    // Every rule sees the values as they were before anything was removed
+    let visible: Vec<bool> = fields
+        .iter()
+        .map(|field| field.is_visible_in(values))
+        .collect();
+
+    for (field, visible) in fields.iter().zip(visible) {
+        let field_path = field_path(path, &field.name);
+        if !visible {
+            values.remove(&field.name);
+            hidden.push((field_path, field));
+            continue;
+        }
+        if field.nested_fields.is_empty() {
+            continue;
+        }
+        match values.get_mut(&field.name) {
+            Some(FieldValue::Object(nested)) => {
+                prune_hidden(&field.nested_fields, nested, &field_path, hidden)
+            }
+            Some(FieldValue::Array(items)) => {
+                for (index, item) in items.iter_mut().enumerate() {
+                    if let FieldValue::Object(nested) = item {
+                        let item_path = format!("{}[{}]", field_path, index);
+                        prune_hidden(&field.nested_fields, nested, &item_path, hidden);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn path_conditions_hold<T: Form>(
+    form: &T,
+    path: &str,
+    holds: fn(&FieldMetadata, &HashMap<String, FieldValue>) -> bool,
+) -> bool {
+    let metadata = T::field_metadata();
+    let mut fields = metadata.as_slice();
+    let mut current = FieldValue::Object(form.get_form_data());
+
+    for segment in parse_field_path(path) {
+        let next = match (segment, &current) {
+            (FieldPathSegment::Key(name), FieldValue::Object(values)) => {
+                let Some(field) = fields.iter().find(|field| field.name == name) else {
+                    break;
+                };
+                if !holds(field, values) {
+                    return false;
+                }
+                fields = &field.nested_fields;
+                values.get(&name).cloned().unwrap_or_default()
+            }
+            (FieldPathSegment::Index(index), FieldValue::Array(items)) => {
+                items.get(index).cloned().unwrap_or_default()
+            }
+            _ => break,
+        };
+        current = next;
+    }
+    true
+}
@@ -1,7 +1,7 @@
-use crate::core::{FieldValue, Form, FormHandle};
+use crate::core::{redacted_form_data, FieldValue, Form, FormHandle};
 use crate::validation::ValidationErrors;
 use leptos::prelude::GetUntracked;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, SystemTime};
 
 /// Type alias for form state change listeners
@@ -111,11 +111,14 @@ impl<T: Form + Send + Sync + PartialEq> FormStateInspector<T> {
 
     pub fn get_field_states(&self) -> HashMap<String, FieldState> {
         let mut field_states = HashMap::new();
-        let form_data = self.form_handle.values().get_untracked();
+        let form_data = redacted_form_data(&self.form_handle.values().get_untracked());
         let state = self.form_handle.state().get_untracked();
 
         for field_metadata in &T::schema().field_metadata {
-            let value = form_data.get_field_value(&field_metadata.name);
+            let value = form_data
+                .get(&field_metadata.name)
+                .cloned()
+                .unwrap_or_default();
             let has_error = state.errors.get_field_error(&field_metadata.name).is_some();
             let error_message = state
                 .errors
@@ -215,12 +218,7 @@ impl DebugUtilities {
     ) -> FormSnapshot {
         let form_data = form_handle.values().get_untracked();
         let state = form_handle.state().get_untracked();
-        let mut field_values = HashMap::new();
-
-        for field_metadata in &T::schema().field_metadata {
-            let value = form_data.get_field_value(&field_metadata.name);
-            field_values.insert(field_metadata.name.clone(), value);
-        }
+        let field_values = redacted_form_data(&form_data);
 
         FormSnapshot {
             form_name: T::schema().name,
@@ -259,6 +257,9 @@ impl DebugUtilities {
     ) -> String {
         let form_data = form_handle.values().get_untracked();
         let state = form_handle.state().get_untracked();
+        // Sensitive values are redacted, and sorted keys keep exports comparable
+        let data: BTreeMap<String, FieldValue> =
+            redacted_form_data(&form_data).into_iter().collect();
 
         format!(
             "Form: {}\nFields: {}\nIs Dirty: {}\nIs Submitting: {}\nHas Errors: {}\nData: {:?}",
@@ -267,7 +268,7 @@ impl DebugUtilities {
             state.is_dirty,
             state.is_submitting,
             !state.errors.is_empty(),
-            form_data
+            data
         )
     }
 
@@ -32,6 +32,9 @@ pub enum FormError {
     PersistenceError {
         message: String,
         storage_type: String,
+        /// The storage ran out of space, so a smaller draft or another
+        /// storage may still succeed
+        quota_exceeded: bool,
     },
     /// Configuration error
     ConfigurationError { message: String, component: String },
@@ -116,6 +119,16 @@ impl FormError {
         Self::PersistenceError {
             message: message.into(),
             storage_type: storage_type.into(),
+            quota_exceeded: false,
+        }
+    }
+
+    /// Create a persistence error for storage that ran out of space
+    pub fn quota_exceeded(message: impl Into<String>, storage_type: impl Into<String>) -> Self {
+        Self::PersistenceError {
+            message: message.into(),
+            storage_type: storage_type.into(),
+            quota_exceeded: true,
         }
     }
 
@@ -164,6 +177,22 @@ impl FormError {
         matches!(self, Self::SubmissionError { .. })
     }
 
+    /// Check if this is a persistence error
+    pub fn is_persistence_error(&self) -> bool {
+        matches!(self, Self::PersistenceError { .. })
+    }
+
+    /// Check if this is a persistence error caused by storage running out of space
+    pub fn is_quota_exceeded(&self) -> bool {
+        matches!(
+            self,
+            Self::PersistenceError {
+                quota_exceeded: true,
+                ..
+            }
+        )
+    }
+
     /// Get the field name if this is a field error
     pub fn field_name(&self) -> Option<&str> {
         match self {
@@ -238,6 +267,7 @@ impl fmt::Display for FormError {
             Self::PersistenceError {
                 message,
                 storage_type,
+                ..
             } => {
                 write!(f, "Persistence error ({}): {}", storage_type, message)
             }
@@ -1,8 +1,15 @@
-use crate::core::traits::Form;
-use crate::core::types::FieldValue;
-use crate::core::FormHandle;
+use crate::core::sync::differing_fields;
+use crate::core::traits::{Form, FormPersistence};
+use crate::core::types::{CrossTabMode, FieldValue, PersistenceOptions, PersistenceStorage};
+use crate::core::{
+    merge_remote_draft, AutoSaveSchedule, DraftConflict, DraftLoad, FieldOptions, FormHandle,
+    LocalStoragePersistence, MemoryPersistence, OptionsProvider, SessionStoragePersistence,
+    StaleDraft,
+};
+use crate::submission::{finish_server_submission, ServerFormError};
 use leptos::prelude::*;
 use leptos::task::spawn_local;
+use std::sync::Arc;
 
 /// Type alias for wizard hook return type
 pub type WizardHookReturn = (
@@ -14,10 +21,18 @@ pub type WizardHookReturn = (
 );
 
 /// Hook for managing form state
+///
+/// When hydrating, the form starts from the state the server rendered
+/// rather than from `initial_values` (see [`FormHandle::hydrated`]).
+///
+/// `submit` validates the form and marks it submitting without sending it
+/// anywhere, so it does not report success: drafts kept with
+/// `clear_on_submit` stay until the submission is finished with
+/// [`FormHandle::finish_successful_submission`].
 pub fn use_form<T: Form + PartialEq + Clone + Send + Sync>(
     initial_values: T,
 ) -> (FormHandle<T>, Callback<()>, Callback<()>) {
-    let form_handle = FormHandle::new(initial_values);
+    let form_handle = FormHandle::hydrated(move || FormHandle::new(initial_values));
 
     let form_clone1 = form_handle.clone();
     let submit = Callback::new(move |_| {
@@ -69,6 +84,30 @@ pub fn use_field_error<T: Form + PartialEq + Clone + Send + Sync>(
     })
 }
 
+/// Hook keeping the options of a select field in sync with its dependencies
+///
+/// Options are reloaded whenever one of the provider's dependencies changes.
+pub fn use_field_options<T: Form + PartialEq + Clone + Send + Sync>(
+    form_handle: &FormHandle<T>,
+    provider: OptionsProvider,
+) -> FieldOptions {
+    let field_options = FieldOptions::new(provider);
+    let values = form_handle.values();
+    let options = field_options.clone();
+    let inputs = Memo::new(move |_| values.with(|values| options.input_values(values)));
+
+    let form_handle = form_handle.clone();
+    let options = field_options.clone();
+    Effect::new(move |_| {
+        inputs.track();
+        let form_handle = form_handle.clone();
+        let options = options.clone();
+        spawn_local(async move { options.refresh(&form_handle).await });
+    });
+
+    field_options
+}
+
 /// Hook for checking if a field is dirty
 pub fn use_field_dirty<T: Form + PartialEq + Clone + Send + Sync>(
     form_handle: &FormHandle<T>,
@@ -77,11 +116,22 @@ pub fn use_field_dirty<T: Form + PartialEq + Clone + Send + Sync>(
 }
 
 /// Hook for checking if a field has been touched
+pub fn use_field_is_touched<T: Form + PartialEq + Clone + Send + Sync>(
+    form_handle: &FormHandle<T>,
+    field_name: &str,
+) -> Memo<bool> {
+    form_handle.field_touched(field_name)
+}
+
+/// Hook for checking if any field has been touched
+#[deprecated(
+    since = "1.2.0",
+    note = "use `use_field_is_touched` to check a single field, or `FormHandle::is_touched`"
+)]
 pub fn use_field_touched<T: Form + PartialEq + Clone + Send + Sync>(
-    _form_handle: &FormHandle<T>,
+    form_handle: &FormHandle<T>,
 ) -> Memo<bool> {
-    // For now, return false - this would need to be tracked in FormState
-    Memo::new(move |_| false)
+    form_handle.is_touched()
 }
 
 /// Hook for form validation
@@ -122,50 +172,356 @@ pub fn use_form_submission<T: Form + PartialEq + Clone + Send + Sync>(
     (is_submitting, submit)
 }
 
-/// Hook for form persistence
+/// Hook for form persistence in Web Storage or memory
+///
+/// Takes a storage key, which restores the values saved under it in
+/// `localStorage` on mount, saves changes as they are made and clears the
+/// saved values once the form is submitted, or [`PersistenceOptions`] to pick
+/// the storage and what is done automatically. Without a `storage_key` the
+/// form's schema name is used. Returns callbacks to save, load and clear the
+/// values by hand.
 pub fn use_form_persistence<T: Form + PartialEq + Clone + Send + Sync>(
-    _form_handle: &FormHandle<T>,
-    storage_key: &str,
+    form_handle: &FormHandle<T>,
+    options: impl Into<PersistenceOptions>,
 ) -> (Callback<()>, Callback<()>, Callback<()>) {
-    let storage_key = storage_key.to_string();
-    let storage_key1 = storage_key.clone();
-    let storage_key2 = storage_key.clone();
-    let storage_key3 = storage_key.clone();
+    let options = options.into();
+    let key = options
+        .storage_key
+        .clone()
+        .unwrap_or_else(|| T::schema().name);
+    match options.storage {
+        PersistenceStorage::Local => {
+            let mut persistence = LocalStoragePersistence::new(key);
+            if options.cross_tab_sync.is_some() {
+                persistence = persistence.with_cross_tab_sync();
+            }
+            use_form_persistence_with(form_handle, persistence, options)
+        }
+        PersistenceStorage::Session => {
+            use_form_persistence_with(form_handle, SessionStoragePersistence::new(key), options)
+        }
+        PersistenceStorage::Memory => {
+            use_form_persistence_with(form_handle, MemoryPersistence::new(key), options)
+        }
+    }
+}
 
+/// Delay before changes are auto-saved when no `auto_save_interval` is set
+pub const DEFAULT_AUTO_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Hook for form persistence in any storage
+///
+/// With `options.enabled` the saved values are restored once the form is
+/// mounted, with `auto_save` changes are saved once no further change was
+/// made for `auto_save_interval`, and with `clear_on_submit` the saved values
+/// are removed once a submission succeeds. Restoring and auto-saving run in
+/// the browser only. Storage failures are logged rather than interrupting the
+/// form.
+pub fn use_form_persistence_with<T, P>(
+    form_handle: &FormHandle<T>,
+    persistence: P,
+    options: PersistenceOptions,
+) -> (Callback<()>, Callback<()>, Callback<()>)
+where
+    T: Form + PartialEq + Clone + Send + Sync,
+    P: FormPersistence<T> + Send + Sync + 'static,
+{
+    let drafts = use_form_drafts(form_handle, persistence, options);
+    (drafts.save, drafts.load, drafts.clear)
+}
+
+/// Hook for form persistence that surfaces drafts needing a decision
+///
+/// Behaves like [`use_form_persistence_with`]. A draft the storage's
+/// `DraftFallback::Prompt` policy kept back is exposed through `stale` until
+/// it is restored in part or discarded.
+///
+/// With `options.cross_tab_sync` and storage that has a sync channel, drafts
+/// saved in other tabs are brought in as they arrive. `CrossTabMode::LiveMerge`
+/// applies them to the fields not edited here since the last save, and
+/// `CrossTabMode::Conflict` applies nothing. Fields that cannot be merged are
+/// exposed through `conflict` until the remote draft is accepted or the local
+/// values are kept.
+pub fn use_form_drafts<T, P>(
+    form_handle: &FormHandle<T>,
+    persistence: P,
+    options: PersistenceOptions,
+) -> FormDrafts
+where
+    T: Form + PartialEq + Clone + Send + Sync,
+    P: FormPersistence<T> + Send + Sync + 'static,
+{
+    let persistence = Arc::new(persistence);
+    let stale = RwSignal::new(None::<StaleDraft>);
+    let conflict = RwSignal::new(None::<DraftConflict>);
+    // The values this tab last shared with the others, to tell local edits
+    // from remote ones, and the remote draft awaiting a decision
+    let base = StoredValue::new(form_handle.state().get_untracked().values);
+    let pending_remote = StoredValue::new(None::<T>);
+
+    let form_clone = form_handle.clone();
+    let storage = persistence.clone();
     let save = Callback::new(move |_| {
-        let storage_key = storage_key1.clone();
-        spawn_local(async move {
-            if let Some(window) = web_sys::window() {
-                if let Ok(Some(_storage)) = window.local_storage() {
-                    log::info!("Saving form data to storage with key: {}", storage_key);
-                }
-            }
-        });
+        let values = form_clone.state().get_untracked().values;
+        match storage.save(&values) {
+            Ok(()) => base.set_value(values),
+            Err(error) => log::warn!("Failed to save form data: {}", error),
+        }
     });
 
-    let load = Callback::new(move |_| {
-        let storage_key = storage_key2.clone();
-        spawn_local(async move {
-            if let Some(window) = web_sys::window() {
-                if let Ok(Some(_storage)) = window.local_storage() {
-                    log::info!("Loading form data from storage with key: {}", storage_key);
-                }
+    let form_clone = form_handle.clone();
+    let storage = persistence.clone();
+    let load = Callback::new(move |_| match storage.load_draft() {
+        Ok(DraftLoad::Stale(draft)) => stale.set(Some(draft)),
+        Ok(DraftLoad::Discarded { reason }) => {
+            log::info!("Discarded saved form data: {}", reason)
+        }
+        Ok(draft) => {
+            if let Some(values) = draft.into_values() {
+                base.set_value(values.clone());
+                form_clone.set_values(values);
             }
-        });
+        }
+        Err(error) => log::warn!("Failed to load form data: {}", error),
     });
 
+    let auto_save = AutoSaveSchedule::new();
+
+    let storage = persistence.clone();
+    let scheduled = auto_save.clone();
     let clear = Callback::new(move |_| {
-        let storage_key = storage_key3.clone();
-        spawn_local(async move {
-            if let Some(window) = web_sys::window() {
-                if let Ok(Some(_storage)) = window.local_storage() {
-                    log::info!("Clearing form data from storage with key: {}", storage_key);
+        // A save scheduled before clearing would write the draft back
+        scheduled.cancel();
+        stale.set(None);
+        if let Err(error) = storage.clear() {
+            log::warn!("Failed to clear form data: {}", error);
+        }
+    });
+
+    let form_clone = form_handle.clone();
+    let restore_partial = Callback::new(move |_| {
+        if let Some(draft) = stale.get_untracked() {
+            let (values, _skipped) = draft.partial::<T>();
+            form_clone.set_values(values);
+            stale.set(None);
+        }
+    });
+
+    let form_clone = form_handle.clone();
+    let accept_remote = Callback::new(move |_| {
+        if let Some(remote) = pending_remote.try_update_value(Option::take).flatten() {
+            base.set_value(remote.clone());
+            form_clone.set_values(remote);
+        }
+        conflict.set(None);
+    });
+
+    let keep_local = Callback::new(move |_| {
+        pending_remote.set_value(None);
+        conflict.set(None);
+        // Saving hands the local values to the other tabs in turn
+        save.run(());
+    });
+
+    let drafts = FormDrafts {
+        save,
+        load,
+        clear,
+        restore_partial,
+        discard: clear,
+        stale: stale.read_only(),
+        accept_remote,
+        keep_local,
+        conflict: conflict.read_only(),
+    };
+
+    if !options.enabled {
+        return drafts;
+    }
+
+    // Restoring once mounted keeps the rendered form in step with the server's,
+    // which has no saved values to restore
+    Effect::new(move |_| untrack(|| load.run(())));
+
+    if let Some(mode) = options.cross_tab_sync {
+        let form_clone = form_handle.clone();
+        let on_remote = Arc::new(move |remote: Option<T>| {
+            // A draft cleared elsewhere, e.g. on submit, leaves this tab's
+            // values alone
+            let Some(remote) = remote else {
+                return;
+            };
+            let local = form_clone.state().get_untracked().values;
+            let fields = match mode {
+                CrossTabMode::LiveMerge => {
+                    let merge = merge_remote_draft(&base.get_value(), &local, &remote);
+                    if !merge.applied.is_empty() {
+                        form_clone.set_values(merge.values);
+                    }
+                    merge.conflicts
                 }
+                CrossTabMode::Conflict => differing_fields(&local, &remote),
+            };
+            base.set_value(remote.clone());
+            if fields.is_empty() {
+                pending_remote.set_value(None);
+                conflict.set(None);
+            } else {
+                pending_remote.set_value(Some(remote));
+                conflict.set(Some(DraftConflict { fields }));
             }
         });
+        if !persistence.watch(on_remote) {
+            log::warn!("Cross-tab sync needs storage with a sync channel");
+        }
+    }
+
+    if options.clear_on_submit {
+        form_handle.on_submit_success(move |_| clear.run(()));
+    }
+
+    // Debounced saves rely on browser timers, so there is nothing to schedule
+    // when rendering on the server
+    if options.auto_save && cfg!(target_arch = "wasm32") {
+        let values = form_handle.values();
+        let interval = options
+            .auto_save_interval
+            .unwrap_or(DEFAULT_AUTO_SAVE_INTERVAL);
+        let delay_ms = u32::try_from(interval.as_millis()).unwrap_or(u32::MAX);
+
+        Effect::new(move |previous: Option<()>| {
+            values.track();
+            // The values on mount are the ones just restored
+            if previous.is_none() {
+                return;
+            }
+            let ticket = auto_save.schedule();
+            let auto_save = auto_save.clone();
+            spawn_local(async move {
+                gloo_timers::future::TimeoutFuture::new(delay_ms).await;
+                // Later changes restart the wait, clearing cancels it
+                if auto_save.is_due(ticket) {
+                    save.run(());
+                }
+            });
+        });
+    }
+
+    drafts
+}
+
+/// Handle for saved drafts of a form
+#[derive(Clone)]
+pub struct FormDrafts {
+    pub save: Callback<()>,
+    pub load: Callback<()>,
+    pub clear: Callback<()>,
+    /// Restore the fields of the stale draft that still fit the form
+    pub restore_partial: Callback<()>,
+    /// Delete the stale draft
+    pub discard: Callback<()>,
+    /// A saved draft that could not be restored automatically
+    pub stale: ReadSignal<Option<StaleDraft>>,
+    /// Replace the local values with the conflicting draft of another tab
+    pub accept_remote: Callback<()>,
+    /// Keep the local values and save them over the other tab's draft
+    pub keep_local: Callback<()>,
+    /// Fields a draft saved in another tab could not be merged into
+    pub conflict: ReadSignal<Option<DraftConflict>>,
+}
+
+/// Hook submitting a form through a server function
+///
+/// `submit` validates the form and dispatches `action` with the input
+/// `to_input` builds from its values. The form is submitting for as long as
+/// the action is pending; errors the server function returns are then shown
+/// on the form, field by field where they carry fields (see
+/// [`ServerFormError`]). The submitted values stay available through
+/// `optimistic` until the server answers.
+///
+/// ```rust,ignore
+/// #[server]
+/// async fn sign_up(values: Signup) -> Result<(), ValidationErrors> {
+///     values.validate()?;
+///     Ok(())
+/// }
+///
+/// let server_form = use_server_action_form(
+///     &form,
+///     ServerAction::<SignUp>::new(),
+///     |values| SignUp { values },
+/// );
+/// ```
+pub fn use_server_action_form<T, I, O, E>(
+    form_handle: &FormHandle<T>,
+    action: impl Into<Action<I, Result<O, E>>>,
+    to_input: impl Fn(T) -> I + Send + Sync + 'static,
+) -> ServerActionForm<T, I, O, E>
+where
+    T: Form + PartialEq + Clone + Send + Sync,
+    I: Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    E: ServerFormError + Send + Sync + 'static,
+{
+    let action = action.into();
+    let pending = action.pending();
+    let version = action.version();
+    let submitted = RwSignal::new(None::<T>);
+    // The version of the action's value before the latest dispatch, to tell
+    // an answer from a call that was aborted
+    let dispatched_at = StoredValue::new(0usize);
+
+    let form_clone = form_handle.clone();
+    let submit = Callback::new(move |_| match form_clone.submit() {
+        Ok(values) => {
+            submitted.set(Some(values.clone()));
+            dispatched_at.set_value(version.get_untracked());
+            action.dispatch(to_input(values));
+        }
+        // Validation errors are already on the form
+        Err(error) => log::debug!("Form not submitted: {}", error),
     });
 
-    (save, load, clear)
+    let form_clone = form_handle.clone();
+    Effect::new(move |was_pending: Option<bool>| {
+        let is_pending = pending.get();
+        if is_pending && was_pending == Some(false) {
+            // Also covers calls dispatched on the action from elsewhere
+            dispatched_at.set_value(version.get_untracked());
+        }
+        if was_pending == Some(true) && !is_pending {
+            if version.get_untracked() > dispatched_at.get_value() {
+                action.value().with_untracked(|result| match result {
+                    Some(result) => finish_server_submission(&form_clone, result),
+                    None => form_clone.finish_submission(),
+                });
+            } else {
+                form_clone.finish_submission();
+            }
+            submitted.set(None);
+        }
+        is_pending
+    });
+
+    ServerActionForm {
+        action,
+        submit,
+        pending,
+        optimistic: submitted.read_only(),
+    }
+}
+
+/// Handle for a form submitted through a server function
+#[derive(Clone)]
+pub struct ServerActionForm<T: 'static, I: 'static, O: 'static, E: 'static> {
+    /// The server action, e.g. to read what the last call returned
+    pub action: Action<I, Result<O, E>>,
+    /// Validate the form and call the server function with its values
+    pub submit: Callback<()>,
+    /// Whether the server function is running
+    pub pending: Memo<bool>,
+    /// The values being submitted, until the server answers
+    pub optimistic: ReadSignal<Option<T>>,
 }
 
 /// Hook for form analytics
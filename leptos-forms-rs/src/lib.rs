@@ -8,6 +8,7 @@ pub mod core;
 pub mod devtools;
 pub mod error;
 pub mod hooks;
+pub mod submission;
 pub mod utils;
 pub mod validation;
 
@@ -17,25 +18,29 @@ pub mod wasm_optimizations;
 
 // Re-export core types and traits
 pub use core::traits::FormState;
-pub use core::{FieldMetadata, Form, FormHandle, FormSchema};
+pub use core::{DynamicForm, FieldMetadata, Form, FormHandle, FormSchema};
 
 // Re-export validation types
-pub use validation::{validate_form, ValidationErrors, Validators};
+pub use validation::{validate_form, validate_values, ValidationErrors, Validators};
 
 // Re-export error types
 pub use error::{FieldError, FormError};
 
 // Re-export hooks
+#[allow(deprecated)]
+pub use hooks::use_field_touched;
 pub use hooks::{
     use_conditional_validation, use_field_array, use_field_dirty, use_field_error,
-    use_field_touched, use_field_value, use_form, use_form_analytics, use_form_performance,
-    use_form_persistence, use_form_submission, use_form_validation, use_form_wizard,
-    use_real_time_validation, FieldArrayHandle,
+    use_field_is_touched, use_field_options, use_field_value, use_form, use_form_analytics,
+    use_form_drafts, use_form_performance, use_form_persistence, use_form_persistence_with,
+    use_form_submission, use_form_validation, use_form_wizard, use_real_time_validation,
+    use_server_action_form, FieldArrayHandle, FormDrafts, ServerActionForm,
 };
 
 // Re-export components
 pub use components::{
-    form_wizard as FormWizard, FieldArray, Form as FormComponent, FormDebug, FormField, FormReset,
+    form_wizard as FormWizard, AutoForm, FieldArray, FieldOverrides, Form as FormComponent,
+    FormDebug, FormField, FormReset,
 };
 
 // Re-export utility functions
@@ -51,3 +56,9 @@ pub use devtools::{
     DebugUtilities, FieldChange, FieldState, FormSnapshot, FormStateInspector, FormStateSnapshot,
     IntegrityCheck, PerformanceMetrics, PerformanceMonitor, SnapshotDiff,
 };
+
+// Paths used by the `Form` derive, not part of the public API
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+}
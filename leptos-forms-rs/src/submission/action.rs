@@ -0,0 +1,105 @@
+use crate::core::traits::Form;
+use crate::core::FormHandle;
+use crate::submission::http::parse_error_body;
+use crate::validation::ValidationErrors;
+use leptos::prelude::{FromServerFnError, ServerFnError, ServerFnErrorErr};
+use leptos::server_fn::codec::JsonEncoding;
+use std::fmt::Display;
+
+/// Errors of a server function that can be shown on a form
+///
+/// Implemented for [`ValidationErrors`] and [`ServerFnError`]; implement it
+/// for a custom error type to show it field by field.
+pub trait ServerFormError {
+    fn validation_errors(&self) -> ValidationErrors;
+}
+
+impl ServerFormError for ValidationErrors {
+    fn validation_errors(&self) -> ValidationErrors {
+        self.clone()
+    }
+}
+
+impl<E: Display> ServerFormError for ServerFnError<E> {
+    fn validation_errors(&self) -> ValidationErrors {
+        match self {
+            ServerFnError::ServerError(payload) => validation_payload(payload),
+            other => form_error(other.to_string()),
+        }
+    }
+}
+
+/// Server functions may return `Result<_, ValidationErrors>` directly
+///
+/// Failures of the call itself, e.g. a network error, become form errors.
+impl FromServerFnError for ValidationErrors {
+    type Encoder = JsonEncoding;
+
+    fn from_server_fn_error(value: ServerFnErrorErr) -> Self {
+        form_error(value.to_string())
+    }
+}
+
+impl ValidationErrors {
+    /// Errors to return from a server function whose error is [`ServerFnError`]
+    ///
+    /// The errors travel as JSON in [`ServerFnError::ServerError`] and are
+    /// put back on their fields by [`ServerFormError`]. Converting with `?`
+    /// instead keeps only their text.
+    ///
+    /// ```rust,ignore
+    /// #[server]
+    /// async fn sign_up(values: Signup) -> Result<(), ServerFnError> {
+    ///     values.validate().map_err(ValidationErrors::into_server_fn_error)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn into_server_fn_error(self) -> ServerFnError {
+        match serde_json::to_string(&self) {
+            Ok(payload) => ServerFnError::ServerError(payload),
+            Err(error) => ServerFnError::Serialization(error.to_string()),
+        }
+    }
+}
+
+/// Mark a submission finished with what the server function returned
+///
+/// Errors replace those on the form; a success leaves the form as submitted
+/// and runs its `on_submit_success` listeners.
+pub fn finish_server_submission<T, O, E>(form: &FormHandle<T>, result: &Result<O, E>)
+where
+    T: Form + PartialEq + Send + Sync,
+    E: ServerFormError,
+{
+    match result {
+        Ok(_) => form.finish_successful_submission(),
+        Err(error) => {
+            form.set_errors(error.validation_errors());
+            form.finish_submission();
+        }
+    }
+}
+
+/// Read the errors a server sent as text
+///
+/// Understands serialized [`ValidationErrors`] and the problem bodies read
+/// by [`parse_error_body`]; anything else is shown as a form error.
+fn validation_payload(payload: &str) -> ValidationErrors {
+    if let Ok(errors) = serde_json::from_str::<ValidationErrors>(payload) {
+        if !errors.is_empty() {
+            return errors;
+        }
+    }
+    let errors = parse_error_body(payload);
+    if errors.is_empty() {
+        form_error(payload.to_string())
+    } else {
+        errors
+    }
+}
+
+fn form_error(message: String) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    errors.add_form_error(message);
+    errors
+}
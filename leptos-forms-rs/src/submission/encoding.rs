@@ -0,0 +1,531 @@
+use crate::core::convert::FormValue;
+use crate::core::traits::{FieldMetadata, Form};
+use crate::core::types::{FieldType, FieldValue, FileData};
+use crate::error::FormError;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+
+/// Most parts a decoded body may have, to bound the work untrusted bodies cause
+pub const MAX_FORM_PARTS: usize = 10_000;
+
+/// Deepest nesting of bracket names like `a[b][c]` a decoded body may use
+pub const MAX_NESTING_DEPTH: usize = 32;
+
+/// One value of a form body, as the browser would send it
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormPart {
+    Text(String),
+    File(FileData),
+}
+
+/// A `multipart/form-data` body along with its boundary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipartBody {
+    pub boundary: String,
+    pub body: Vec<u8>,
+}
+
+impl MultipartBody {
+    /// Value for the `Content-Type` header of the request
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+}
+
+/// The values of `form` as named parts, in field order
+///
+/// Nested objects and arrays use bracket notation, e.g. `address[city]` and
+/// `items[0][sku]`. Empty values (`None`, empty arrays) are left out.
+pub fn form_parts<T: Form>(form: &T) -> Vec<(String, FormPart)> {
+    let data = form.get_form_data();
+    let mut parts = Vec::new();
+    for field in T::field_metadata() {
+        if let Some(value) = data.get(&field.name) {
+            flatten(&field.name, value, &mut parts);
+        }
+    }
+    parts
+}
+
+/// Encode `form` as an `application/x-www-form-urlencoded` body
+///
+/// Files cannot be sent this way and are left out; use
+/// [`encode_multipart`] for forms with uploads.
+pub fn encode_urlencoded<T: Form>(form: &T) -> String {
+    form_parts(form)
+        .into_iter()
+        .filter_map(|(name, part)| match part {
+            FormPart::Text(value) => Some(format!(
+                "{}={}",
+                percent_encode(&name),
+                percent_encode(&value)
+            )),
+            FormPart::File(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Decode an `application/x-www-form-urlencoded` body into a form
+///
+/// Accepts the bracket notation of [`encode_urlencoded`], `tags[]` and
+/// repeated names for arrays. Fields missing from the body keep their
+/// default values, as unchecked checkboxes do.
+pub fn decode_urlencoded<T: Form + FormValue>(body: &str) -> Result<T, FormError> {
+    from_parts(urlencoded_parts(body)?)
+}
+
+pub(crate) fn urlencoded_parts(body: &str) -> Result<Vec<(String, FormPart)>, FormError> {
+    let mut parts = Vec::new();
+    for pair in body.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let name = percent_decode(name)?;
+        check_part(&name, parts.len())?;
+        parts.push((name, FormPart::Text(percent_decode(value)?)));
+    }
+    Ok(parts)
+}
+
+/// Encode `form` as a `multipart/form-data` body, sending files as file parts
+pub fn encode_multipart<T: Form>(form: &T) -> MultipartBody {
+    let parts = form_parts(form);
+    let boundary = boundary_for(&parts);
+    let mut body = Vec::new();
+    for (name, part) in &parts {
+        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        match part {
+            FormPart::Text(value) => {
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"\r\n\r\n",
+                        quote(name)
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(value.as_bytes());
+            }
+            FormPart::File(file) => {
+                let mime_type = if file.mime_type.is_empty() {
+                    "application/octet-stream"
+                } else {
+                    &file.mime_type
+                };
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+                        quote(name),
+                        quote(&file.name),
+                        mime_type
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(&file.data);
+            }
+        }
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    MultipartBody { boundary, body }
+}
+
+/// Decode a `multipart/form-data` body into a form
+///
+/// `content_type` is the request's `Content-Type` header, which carries the
+/// boundary. File parts become `FileData`; file inputs left empty are
+/// treated as missing.
+pub fn decode_multipart<T: Form + FormValue>(
+    content_type: &str,
+    body: &[u8],
+) -> Result<T, FormError> {
+    from_parts(multipart_parts(content_type, body)?)
+}
+
+pub(crate) fn multipart_parts(
+    content_type: &str,
+    body: &[u8],
+) -> Result<Vec<(String, FormPart)>, FormError> {
+    let boundary = content_type
+        .split(';')
+        .filter_map(|param| param.trim().split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .filter(|boundary| !boundary.is_empty())
+        .ok_or_else(|| malformed("Missing multipart boundary"))?;
+    let parts = parse_multipart(&boundary, body)?;
+    for (count, (name, _)) in parts.iter().enumerate() {
+        check_part(name, count)?;
+    }
+    Ok(parts)
+}
+
+/// Refuse the part named `name` after `count` others when it exceeds the limits
+fn check_part(name: &str, count: usize) -> Result<(), FormError> {
+    if count >= MAX_FORM_PARTS {
+        return Err(malformed(&format!(
+            "The body has more than {} fields",
+            MAX_FORM_PARTS
+        )));
+    }
+    if name_segments(name).len() > MAX_NESTING_DEPTH {
+        return Err(malformed(&format!(
+            "{} is nested more than {} levels deep",
+            name, MAX_NESTING_DEPTH
+        )));
+    }
+    Ok(())
+}
+
+/// Build a form from named parts using bracket notation
+pub fn from_parts<T: Form + FormValue>(
+    parts: impl IntoIterator<Item = (String, FormPart)>,
+) -> Result<T, FormError> {
+    form_from_values(parts_to_values(parts))
+}
+
+/// Build a form from decoded values, shaped after the form's metadata
+///
+/// Errors name the top-level field whose value could not be read.
+pub(crate) fn form_from_values<T: Form + FormValue>(
+    mut object: HashMap<String, FieldValue>,
+) -> Result<T, FormError> {
+    for field in T::field_metadata() {
+        if let Some(value) = object.remove(&field.name) {
+            object.insert(field.name.clone(), coerce(value, &field));
+        }
+    }
+    T::from_field_value(FieldValue::Object(object)).map_err(|message| {
+        let field = message.split_once(": ").map(|(field, _)| field.to_string());
+        FormError::serialization_error(message, field)
+    })
+}
+
+/// Collect named parts into objects and arrays, for bodies that are not a `Form`
+///
+/// Values stay text, since without a form there is no type to parse them as.
+pub fn parts_to_values(
+    parts: impl IntoIterator<Item = (String, FormPart)>,
+) -> HashMap<String, FieldValue> {
+    let mut root = Node::branch();
+    for (name, part) in parts {
+        let segments = name_segments(&name);
+        // Decoded bodies are refused before this; others lose such parts
+        if segments.len() > MAX_NESTING_DEPTH {
+            continue;
+        }
+        let value = match part {
+            FormPart::Text(text) => FieldValue::String(text),
+            FormPart::File(file) => FieldValue::File(file),
+        };
+        root.insert(&segments, value);
+    }
+    match root.into_value() {
+        FieldValue::Object(object) => object,
+        _ => HashMap::new(),
+    }
+}
+
+fn flatten(name: &str, value: &FieldValue, parts: &mut Vec<(String, FormPart)>) {
+    let text = match value {
+        FieldValue::Null => return,
+        FieldValue::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten(&format!("{}[{}]", name, index), item, parts);
+            }
+            return;
+        }
+        FieldValue::Object(object) => {
+            let object: BTreeMap<_, _> = object.iter().collect();
+            for (key, nested) in object {
+                flatten(&format!("{}[{}]", name, key), nested, parts);
+            }
+            return;
+        }
+        FieldValue::File(file) => {
+            parts.push((name.to_string(), FormPart::File(file.clone())));
+            return;
+        }
+        FieldValue::DateTime(date_time) => date_time.to_rfc3339(),
+        FieldValue::String(_)
+        | FieldValue::Number(_)
+        | FieldValue::Integer(_)
+        | FieldValue::Boolean(_)
+        | FieldValue::Date(_) => value.to_string(),
+    };
+    parts.push((name.to_string(), FormPart::Text(text)));
+}
+
+/// `items[0][sku]` as `["items", "0", "sku"]`; `tags[]` ends in an empty segment
+fn name_segments(name: &str) -> Vec<&str> {
+    let Some(start) = name.find('[').filter(|_| name.ends_with(']')) else {
+        return vec![name];
+    };
+    let mut segments = vec![&name[..start]];
+    segments.extend(name[start + 1..name.len() - 1].split("]["));
+    segments
+}
+
+/// Values gathered by name before they are turned into objects and arrays
+enum Node {
+    Leaf(Vec<FieldValue>),
+    /// Children in the order they were first named, and where each key is
+    Branch(Vec<(String, Node)>, HashMap<String, usize>),
+}
+
+impl Node {
+    fn branch() -> Self {
+        Node::Branch(Vec::new(), HashMap::new())
+    }
+
+    fn insert(&mut self, segments: &[&str], value: FieldValue) {
+        let Some((segment, rest)) = segments.split_first() else {
+            match self {
+                Node::Leaf(values) => values.push(value),
+                Node::Branch(..) => *self = Node::Leaf(vec![value]),
+            }
+            return;
+        };
+        if let Node::Leaf(_) = self {
+            *self = Node::branch();
+        }
+        let Node::Branch(children, positions) = self else {
+            return;
+        };
+        // `tags[]` appends, so every value gets a child of its own
+        let key = if segment.is_empty() {
+            children.len().to_string()
+        } else {
+            segment.to_string()
+        };
+        let index = *positions.entry(key.clone()).or_insert_with(|| {
+            children.push((key, Node::Leaf(Vec::new())));
+            children.len() - 1
+        });
+        children[index].1.insert(rest, value);
+    }
+
+    fn into_value(self) -> FieldValue {
+        match self {
+            Node::Leaf(mut values) if values.len() == 1 => values.remove(0),
+            Node::Leaf(values) => FieldValue::Array(values),
+            Node::Branch(children, _) => {
+                let indices: Option<Vec<usize>> =
+                    children.iter().map(|(key, _)| key.parse().ok()).collect();
+                match indices {
+                    Some(indices) if !children.is_empty() => {
+                        let mut items: Vec<_> = indices.into_iter().zip(children).collect();
+                        items.sort_by_key(|(index, _)| *index);
+                        FieldValue::Array(
+                            items
+                                .into_iter()
+                                .map(|(_, (_, node))| node.into_value())
+                                .collect(),
+                        )
+                    }
+                    _ => FieldValue::Object(
+                        children
+                            .into_iter()
+                            .map(|(key, node)| (key, node.into_value()))
+                            .collect(),
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Shape `value` after the field's metadata, e.g. a single `tags=a` is an array
+fn coerce(value: FieldValue, field: &FieldMetadata) -> FieldValue {
+    let is_array = matches!(
+        field.field_type,
+        FieldType::Array(_) | FieldType::MultiSelect(_)
+    );
+    match value {
+        FieldValue::Array(items) if is_array => FieldValue::Array(
+            items
+                .into_iter()
+                .map(|item| coerce_nested(item, &field.nested_fields))
+                .collect(),
+        ),
+        FieldValue::Array(_) => value,
+        value if is_array => FieldValue::Array(vec![coerce_nested(value, &field.nested_fields)]),
+        value => coerce_nested(value, &field.nested_fields),
+    }
+}
+
+fn coerce_nested(value: FieldValue, fields: &[FieldMetadata]) -> FieldValue {
+    let FieldValue::Object(mut object) = value else {
+        return value;
+    };
+    for field in fields {
+        if let Some(nested) = object.remove(&field.name) {
+            object.insert(field.name.clone(), coerce(nested, field));
+        }
+    }
+    FieldValue::Object(object)
+}
+
+fn parse_multipart(boundary: &str, body: &[u8]) -> Result<Vec<(String, FormPart)>, FormError> {
+    let delimiter = format!("--{}", boundary);
+    let mut sections = split_bytes(body, delimiter.as_bytes()).into_iter();
+    // Anything before the first delimiter is preamble
+    sections.next();
+
+    let mut parts = Vec::new();
+    for section in sections {
+        if section.starts_with(b"--") {
+            break;
+        }
+        let section = section.strip_prefix(b"\r\n").unwrap_or(section);
+        let section = section.strip_suffix(b"\r\n").unwrap_or(section);
+        let Some(header_end) = find_bytes(section, b"\r\n\r\n") else {
+            return Err(malformed("Multipart part without headers"));
+        };
+        let headers = std::str::from_utf8(&section[..header_end])
+            .map_err(|_| malformed("Multipart headers are not UTF-8"))?;
+        let content = &section[header_end + 4..];
+
+        let mut name = None;
+        let mut filename = None;
+        let mut mime_type = None;
+        for header in headers.split("\r\n") {
+            let Some((key, value)) = header.split_once(':') else {
+                continue;
+            };
+            if key.trim().eq_ignore_ascii_case("content-disposition") {
+                name = disposition_param(value, "name");
+                filename = disposition_param(value, "filename");
+            } else if key.trim().eq_ignore_ascii_case("content-type") {
+                mime_type = Some(value.trim().to_string());
+            }
+        }
+        let Some(name) = name else {
+            return Err(malformed("Multipart part without a name"));
+        };
+
+        let part = match filename {
+            // An empty file input
+            Some(filename) if filename.is_empty() && content.is_empty() => continue,
+            Some(filename) => FormPart::File(FileData {
+                name: filename,
+                size: content.len(),
+                mime_type: mime_type.unwrap_or_else(|| "application/octet-stream".to_string()),
+                data: content.to_vec(),
+            }),
+            None => FormPart::Text(
+                String::from_utf8(content.to_vec())
+                    .map_err(|_| malformed(&format!("Value of {} is not UTF-8", name)))?,
+            ),
+        };
+        parts.push((name, part));
+    }
+    Ok(parts)
+}
+
+/// Parameter of a `Content-Disposition` header, e.g. `name="email"`
+fn disposition_param(header: &str, param: &str) -> Option<String> {
+    header.split(';').skip(1).find_map(|item| {
+        let (key, value) = item.trim().split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case(param) {
+            return None;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        Some(
+            value
+                .replace("%22", "\"")
+                .replace("%0D", "\r")
+                .replace("%0A", "\n"),
+        )
+    })
+}
+
+/// Escape a name for a quoted `Content-Disposition` parameter
+fn quote(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// A boundary that occurs in none of the parts
+fn boundary_for(parts: &[(String, FormPart)]) -> String {
+    let seed = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    (0u64..)
+        .map(|attempt| format!("leptos-forms-{:x}{:x}", seed, attempt))
+        .find(|boundary| {
+            parts.iter().all(|(name, part)| {
+                let content = match part {
+                    FormPart::Text(value) => value.as_bytes(),
+                    FormPart::File(file) => &file.data,
+                };
+                !name.contains(boundary.as_str())
+                    && find_bytes(content, boundary.as_bytes()).is_none()
+            })
+        })
+        .unwrap_or_default()
+}
+
+fn split_bytes<'a>(bytes: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut sections = Vec::new();
+    let mut rest = bytes;
+    while let Some(index) = find_bytes(rest, delimiter) {
+        sections.push(&rest[..index]);
+        rest = &rest[index + delimiter.len()..];
+    }
+    sections.push(rest);
+    sections
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => {
+                let _ = write!(encoded, "%{:02X}", byte);
+            }
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> Result<String, FormError> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let byte = value
+                    .get(index + 1..index + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| malformed(&format!("Invalid escape in {}", value)))?;
+                decoded.push(byte);
+                index += 2;
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8(decoded).map_err(|_| malformed(&format!("{} is not UTF-8", value)))
+}
+
+fn malformed(message: &str) -> FormError {
+    FormError::serialization_error(message, None)
+}
@@ -0,0 +1,496 @@
+use crate::core::traits::Form;
+use crate::core::types::FieldValue;
+use crate::core::FormHandle;
+use crate::error::FormError;
+use crate::submission::protection::{BotProtection, CsrfProtection};
+use crate::validation::ValidationErrors;
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Response of an HTTP request, or the error it failed with
+pub type HttpFuture = Pin<Box<dyn Future<Output = Result<HttpResponse, FormError>>>>;
+
+type TokenProvider = Arc<dyn Fn() -> Option<String>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Post,
+    Put,
+}
+
+impl HttpMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+        }
+    }
+}
+
+/// A request as handed to the [`HttpClient`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// How long to wait for the response before giving up
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// Value of the header `name`, ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: None,
+            body: body.into(),
+        }
+    }
+
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..400).contains(&self.status)
+    }
+
+    /// The body parsed as JSON
+    pub fn json<R: DeserializeOwned>(&self) -> Result<R, FormError> {
+        serde_json::from_str(&self.body)
+            .map_err(|e| FormError::serialization_error(e.to_string(), None))
+    }
+}
+
+/// Transport that sends submissions, so tests can stand in for the network
+///
+/// Implementations report failures to reach the server, including timeouts,
+/// as `FormError::SubmissionError` without a status code; responses of any
+/// status are returned as they are.
+pub trait HttpClient {
+    fn send(&self, request: HttpRequest) -> HttpFuture;
+}
+
+/// Sends requests with `gloo-net`, i.e. the browser's `fetch`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlooHttpClient;
+
+impl HttpClient for GlooHttpClient {
+    fn send(&self, request: HttpRequest) -> HttpFuture {
+        Box::pin(fetch::send(request))
+    }
+}
+
+/// Submits forms to an HTTP endpoint as JSON
+///
+/// Responses with a 4xx or 5xx status fail with `FormError::SubmissionError`
+/// carrying the status and body. Field errors in the body, either as an RFC
+/// 7807 problem or as `{"errors": {"field": ["message"]}}`, are shown on the
+/// form by [`submit`](Self::submit).
+///
+/// ```rust,ignore
+/// let submitter = HttpSubmitter::post("/api/applications")
+///     .header("X-Client", "web")
+///     .auth_token(|| session_token())
+///     .timeout(Duration::from_secs(10));
+///
+/// spawn_local(async move {
+///     match submitter.submit(&form).await {
+///         Ok(response) => navigate(&response.json::<Created>()?.url),
+///         Err(error) => log::warn!("{}", error),
+///     }
+/// });
+/// ```
+#[derive(Clone)]
+pub struct HttpSubmitter {
+    method: HttpMethod,
+    url: String,
+    headers: Vec<(String, String)>,
+    auth_token: Option<TokenProvider>,
+    timeout: Option<Duration>,
+    client: Arc<dyn HttpClient>,
+    csrf: Option<CsrfProtection>,
+    bots: Option<BotProtection>,
+}
+
+impl HttpSubmitter {
+    pub fn new(method: HttpMethod, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            auth_token: None,
+            timeout: None,
+            client: Arc::new(GlooHttpClient),
+            csrf: None,
+            bots: None,
+        }
+    }
+
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new(HttpMethod::Post, url)
+    }
+
+    pub fn put(url: impl Into<String>) -> Self {
+        Self::new(HttpMethod::Put, url)
+    }
+
+    /// Send the header `name` with every request
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Send `Authorization: Bearer <token>` with the token current at each
+    /// request; no header is sent while `token` returns `None`
+    pub fn auth_token(mut self, token: impl Fn() -> Option<String> + 'static) -> Self {
+        self.auth_token = Some(Arc::new(token));
+        self
+    }
+
+    /// Send `Authorization: Bearer <token>` with every request
+    pub fn bearer_token(self, token: impl Into<String>) -> Self {
+        let token = token.into();
+        self.auth_token(move || Some(token.clone()))
+    }
+
+    /// Give up on requests that take longer than `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send the CSRF token current at each request in the protection's header
+    pub fn csrf(mut self, csrf: CsrfProtection) -> Self {
+        self.csrf = Some(csrf);
+        self
+    }
+
+    /// Refuse forms submitted too soon after they were rendered, and send
+    /// the empty honeypot and the render time for the server to check again
+    ///
+    /// The render time sent is the one the server stamped for the form,
+    /// see [`FormHandle::share_render_stamp`].
+    pub fn bot_protection(mut self, bots: BotProtection) -> Self {
+        self.bots = Some(bots);
+        self
+    }
+
+    /// Send requests with `client` instead of `fetch`
+    pub fn with_client(mut self, client: impl HttpClient + 'static) -> Self {
+        self.client = Arc::new(client);
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn method(&self) -> HttpMethod {
+        self.method
+    }
+
+    /// Validate and submit the form, showing field errors the server reports
+    ///
+    /// The submitted values are those of `FormHandle::submission_data`, so
+    /// hidden fields are left out.
+    pub async fn submit<T>(&self, form: &FormHandle<T>) -> Result<HttpResponse, FormError>
+    where
+        T: Form + PartialEq + Send + Sync,
+    {
+        let data = self.submission_body(form)?;
+        let result = self.send(&data).await;
+        match &result {
+            Ok(_) => form.finish_successful_submission(),
+            Err(error) => {
+                form.finish_submission();
+                show_response_errors(form, error);
+            }
+        }
+        result
+    }
+
+    /// Send `body` as JSON
+    pub async fn send<B: Serialize + ?Sized>(&self, body: &B) -> Result<HttpResponse, FormError> {
+        let body = serde_json::to_string(body)
+            .map_err(|e| FormError::serialization_error(e.to_string(), None))?;
+        let response = self.client.send(self.request(body)).await?;
+        if response.status < 400 {
+            return Ok(response);
+        }
+        let message = problem_title(&response.body)
+            .unwrap_or_else(|| format!("Server responded with status {}", response.status));
+        Err(FormError::submission_error(
+            message,
+            Some(response.status),
+            Some(response.body),
+        ))
+    }
+
+    /// Validate the form and collect what to send for it, refusing bots
+    pub(crate) fn submission_body<T>(
+        &self,
+        form: &FormHandle<T>,
+    ) -> Result<BTreeMap<String, FieldValue>, FormError>
+    where
+        T: Form + PartialEq + Send + Sync,
+    {
+        if let Some(bots) = &self.bots {
+            // The honeypot lives in the page, so only the timing is known
+            // here; the stamp holds the time the server rendered the form
+            bots.verify_render_stamp(&form.render_stamp(bots))
+                .and_then(|rendered_at| bots.check(None, Some(rendered_at), Utc::now()))
+                .map_err(|rejected| {
+                    FormError::submission_error(rejected.to_string(), None, None)
+                })?;
+        }
+        form.submit()?;
+        let mut data: BTreeMap<_, _> = form.submission_data().into_iter().collect();
+        if let Some(bots) = &self.bots {
+            for (name, value) in bots.hidden_fields(&form.render_stamp(bots)) {
+                data.insert(name, FieldValue::String(value));
+            }
+        }
+        Ok(data)
+    }
+
+    fn request(&self, body: String) -> HttpRequest {
+        let mut headers = vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            (
+                "Accept".to_string(),
+                "application/json, application/problem+json".to_string(),
+            ),
+        ];
+        if let Some(token) = self.auth_token.as_ref().and_then(|token| token()) {
+            headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+        }
+        if let Some(csrf) = &self.csrf {
+            if let Some(token) = csrf.token() {
+                headers.push((csrf.header_name().to_string(), token));
+            }
+        }
+        headers.extend(self.headers.iter().cloned());
+        HttpRequest {
+            method: self.method,
+            url: self.url.clone(),
+            headers,
+            body,
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl fmt::Debug for HttpSubmitter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpSubmitter")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &self.headers)
+            .field("authenticated", &self.auth_token.is_some())
+            .field("timeout", &self.timeout)
+            .field("csrf", &self.csrf)
+            .field("bots", &self.bots)
+            .finish()
+    }
+}
+
+/// Show the errors in the body of an error response on the form
+pub(crate) fn show_response_errors<T>(form: &FormHandle<T>, error: &FormError)
+where
+    T: Form + PartialEq + Send + Sync,
+{
+    if let FormError::SubmissionError {
+        response: Some(body),
+        ..
+    } = error
+    {
+        let errors = parse_error_body(body);
+        if errors.has_errors() {
+            form.set_errors(errors);
+        }
+    }
+}
+
+/// Field and form errors in the body of an error response
+///
+/// Understands RFC 7807 problems, whose `detail` (or `title`) becomes a form
+/// error and whose `errors` or `invalid-params` become field errors, and the
+/// `{"errors": {"field": ["message"]}}` shape used by many frameworks. Field
+/// names like `items.0.name` are read as the path `items[0].name`. Anything
+/// else yields no errors.
+pub fn parse_error_body(body: &str) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    let Ok(Value::Object(body)) = serde_json::from_str::<Value>(body) else {
+        return errors;
+    };
+
+    match body.get("errors") {
+        Some(Value::Object(fields)) => {
+            for (field, messages) in fields {
+                for message in messages_of(messages) {
+                    errors.add_field_error(&field_path_of(field), message);
+                }
+            }
+        }
+        Some(Value::Array(messages)) => {
+            for message in messages.iter().filter_map(Value::as_str) {
+                errors.add_form_error(message.to_string());
+            }
+        }
+        _ => {}
+    }
+    if let Some(Value::Array(params)) = body.get("invalid-params") {
+        for param in params {
+            let (Some(name), Some(reason)) = (
+                param.get("name").and_then(Value::as_str),
+                param.get("reason").and_then(Value::as_str),
+            ) else {
+                continue;
+            };
+            errors.add_field_error(&field_path_of(name), reason.to_string());
+        }
+    }
+    if errors.field_errors.is_empty() {
+        let summary = ["detail", "title"]
+            .iter()
+            .find_map(|key| body.get(*key).and_then(Value::as_str));
+        if let Some(summary) = summary {
+            errors.add_form_error(summary.to_string());
+        }
+    }
+    errors
+}
+
+fn messages_of(messages: &Value) -> Vec<String> {
+    match messages {
+        Value::String(message) => vec![message.clone()],
+        Value::Array(messages) => messages
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `items.0.name` as `items[0].name`; bracketed paths are kept as they are
+fn field_path_of(name: &str) -> String {
+    let mut path = String::new();
+    for segment in name.split('.') {
+        if !path.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+            path.push_str(&format!("[{}]", segment));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(segment);
+        }
+    }
+    path
+}
+
+/// Short description of an RFC 7807 problem body
+fn problem_title(body: &str) -> Option<String> {
+    let body: Value = serde_json::from_str(body).ok()?;
+    ["title", "detail", "message"]
+        .iter()
+        .find_map(|key| body.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+#[cfg(target_arch = "wasm32")]
+mod fetch {
+    use super::{HttpMethod, HttpRequest, HttpResponse};
+    use crate::error::FormError;
+    use std::future::Future;
+    use std::task::Poll;
+
+    pub(super) async fn send(request: HttpRequest) -> Result<HttpResponse, FormError> {
+        let mut builder = match request.method {
+            HttpMethod::Post => gloo_net::http::Request::post(&request.url),
+            HttpMethod::Put => gloo_net::http::Request::put(&request.url),
+        };
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let sending = builder
+            .body(request.body)
+            .map_err(|e| FormError::submission_error(e.to_string(), None, None))?
+            .send();
+
+        let response = match request.timeout {
+            Some(timeout) => {
+                let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+                within(sending, millis).await.ok_or_else(|| {
+                    FormError::submission_error(
+                        format!("No response within {} ms", millis),
+                        None,
+                        None,
+                    )
+                })?
+            }
+            None => sending.await,
+        }
+        .map_err(|e| FormError::submission_error(e.to_string(), None, None))?;
+
+        Ok(HttpResponse {
+            status: response.status(),
+            content_type: response.headers().get("content-type"),
+            body: response.text().await.unwrap_or_default(),
+        })
+    }
+
+    /// The output of `future`, unless `millis` pass first
+    async fn within<F: Future>(future: F, millis: u32) -> Option<F::Output> {
+        let mut future = std::pin::pin!(future);
+        let mut timer = std::pin::pin!(gloo_timers::future::TimeoutFuture::new(millis));
+        std::future::poll_fn(|cx| {
+            if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                return Poll::Ready(Some(output));
+            }
+            timer.as_mut().poll(cx).map(|()| None)
+        })
+        .await
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod fetch {
+    use super::{HttpRequest, HttpResponse};
+    use crate::error::FormError;
+
+    pub(super) async fn send(_request: HttpRequest) -> Result<HttpResponse, FormError> {
+        Err(FormError::submission_error(
+            "HTTP requests are only available in the browser",
+            None,
+            None,
+        ))
+    }
+}
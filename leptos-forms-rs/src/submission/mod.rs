@@ -0,0 +1,28 @@
+pub mod action;
+pub mod encoding;
+pub mod http;
+pub mod protection;
+pub mod queue;
+pub mod server;
+
+pub use action::{finish_server_submission, ServerFormError};
+pub use encoding::{
+    decode_multipart, decode_urlencoded, encode_multipart, encode_urlencoded, form_parts,
+    from_parts, parts_to_values, FormPart, MultipartBody, MAX_FORM_PARTS, MAX_NESTING_DEPTH,
+};
+pub use http::{
+    parse_error_body, GlooHttpClient, HttpClient, HttpFuture, HttpMethod, HttpRequest,
+    HttpResponse, HttpSubmitter,
+};
+pub use protection::{
+    csrf_tokens_match, parse_cookie, BotProtection, CsrfProtection, CsrfSource, SubmissionRejected,
+    CSRF_FIELD, CSRF_HEADER, RENDERED_AT_FIELD,
+};
+pub use queue::{
+    BrowserNetworkStatus, MemoryNetworkStatus, NetworkListener, NetworkStatus, QueueOutcome,
+    QueuedStatus, QueuedSubmission, RetryPolicy, SubmissionQueue,
+};
+pub use server::{
+    read_protected_submission, read_submission, validate_json, validate_submission,
+    validate_urlencoded, Submission, SubmissionProtection,
+};
@@ -0,0 +1,458 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Form field carrying the CSRF token of forms posted by the browser
+pub const CSRF_FIELD: &str = "csrf_token";
+
+/// Header carrying the CSRF token of submissions sent with JavaScript
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Form field carrying the time the form was rendered, signed by the server
+pub const RENDERED_AT_FIELD: &str = "_rendered_at";
+
+type TokenCallback = Arc<dyn Fn() -> Option<String> + Send + Sync>;
+
+/// Where the page keeps its CSRF token
+#[derive(Clone)]
+pub enum CsrfSource {
+    /// The `content` of `<meta name="...">`
+    Meta(String),
+    /// The cookie of that name, for the double-submit cookie pattern
+    Cookie(String),
+    Callback(TokenCallback),
+}
+
+impl CsrfSource {
+    /// The current token; meta tags and cookies are only read in the browser
+    pub fn token(&self) -> Option<String> {
+        match self {
+            CsrfSource::Meta(name) => browser::meta_content(name),
+            CsrfSource::Cookie(name) => browser::cookies().and_then(|c| parse_cookie(&c, name)),
+            CsrfSource::Callback(token) => token(),
+        }
+        .filter(|token| !token.is_empty())
+    }
+}
+
+impl fmt::Debug for CsrfSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsrfSource::Meta(name) => f.debug_tuple("Meta").field(name).finish(),
+            CsrfSource::Cookie(name) => f.debug_tuple("Cookie").field(name).finish(),
+            CsrfSource::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+/// A CSRF token sent with every submission
+///
+/// Forms posted by the browser carry it in a hidden field, submissions
+/// sent with JavaScript in a header. The token is read when it is sent, so
+/// a rotated token is picked up.
+///
+/// Meta tags and cookies can only be read in the browser. Forms rendered on
+/// the server need the token handed over with
+/// [`server_token`](Self::server_token); the hidden field rendered there
+/// reaches the hydrating client with the rest of the page.
+///
+/// ```rust,ignore
+/// let csrf = CsrfProtection::meta("csrf-token")
+///     .server_token(|| use_context::<Session>().map(|session| session.csrf_token()));
+/// view! { <Form form=form action="/signup" csrf=csrf.clone()>...</Form> }
+/// let submitter = HttpSubmitter::post("/api/signup").csrf(csrf);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsrfProtection {
+    source: CsrfSource,
+    server_token: Option<CsrfSource>,
+    field: String,
+    header: String,
+}
+
+impl CsrfProtection {
+    pub fn new(source: CsrfSource) -> Self {
+        Self {
+            source,
+            server_token: None,
+            field: CSRF_FIELD.to_string(),
+            header: CSRF_HEADER.to_string(),
+        }
+    }
+
+    /// Token in the `content` of `<meta name="{name}">`
+    pub fn meta(name: impl Into<String>) -> Self {
+        Self::new(CsrfSource::Meta(name.into()))
+    }
+
+    /// Token in the cookie `name`
+    pub fn cookie(name: impl Into<String>) -> Self {
+        Self::new(CsrfSource::Cookie(name.into()))
+    }
+
+    /// Token returned by `token`; no token is sent while it returns `None`
+    pub fn callback(token: impl Fn() -> Option<String> + Send + Sync + 'static) -> Self {
+        Self::new(CsrfSource::Callback(Arc::new(token)))
+    }
+
+    /// The token to use outside the browser, e.g. when rendering on the
+    /// server, where meta tags and cookies cannot be read
+    pub fn server_token(
+        mut self,
+        token: impl Fn() -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.server_token = Some(CsrfSource::Callback(Arc::new(token)));
+        self
+    }
+
+    /// Send the token in the form field `name` instead of `csrf_token`
+    pub fn field(mut self, name: impl Into<String>) -> Self {
+        self.field = name.into();
+        self
+    }
+
+    /// Send the token in the header `name` instead of `X-CSRF-Token`
+    pub fn header(mut self, name: impl Into<String>) -> Self {
+        self.header = name.into();
+        self
+    }
+
+    pub fn field_name(&self) -> &str {
+        &self.field
+    }
+
+    pub fn header_name(&self) -> &str {
+        &self.header
+    }
+
+    pub fn source(&self) -> &CsrfSource {
+        &self.source
+    }
+
+    /// The current token: the server token outside the browser when there
+    /// is one, otherwise the token of the source
+    pub fn token(&self) -> Option<String> {
+        match &self.server_token {
+            Some(server_token) if !cfg!(target_arch = "wasm32") => server_token.token(),
+            _ => self.source.token(),
+        }
+    }
+
+    /// Whether a token can be read here, outside the browser only from a
+    /// callback
+    pub fn is_readable(&self) -> bool {
+        cfg!(target_arch = "wasm32")
+            || self.server_token.is_some()
+            || matches!(self.source, CsrfSource::Callback(_))
+    }
+}
+
+/// Checks that tell bots from people filling in a form
+///
+/// A honeypot is a field hidden from people that bots fill in like any
+/// other. Bots also submit much faster than anyone can type, so forms
+/// submitted sooner than `min_time_to_submit` after they were rendered are
+/// rejected too, as are forms rendered longer than `max_render_age` ago,
+/// whose render time could otherwise be replayed indefinitely. By default
+/// the honeypot is called `website`, the minimum time is three seconds and
+/// the maximum age one day.
+///
+/// The server only trusts a render time it signed itself, so give the
+/// protection a [`signing_key`](Self::signing_key) in the server build;
+/// without one the server checks the honeypot only. The signed time
+/// rendered on the server reaches the hydrating client with the rest of the
+/// page; the key never does.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BotProtection {
+    honeypot: Option<String>,
+    min_time_to_submit: Option<Duration>,
+    max_render_age: Option<Duration>,
+    signing_key: Option<Vec<u8>>,
+}
+
+impl Default for BotProtection {
+    fn default() -> Self {
+        Self {
+            honeypot: Some("website".to_string()),
+            min_time_to_submit: Some(Duration::from_secs(3)),
+            max_render_age: Some(Duration::from_secs(24 * 60 * 60)),
+            signing_key: None,
+        }
+    }
+}
+
+impl fmt::Debug for BotProtection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BotProtection")
+            .field("honeypot", &self.honeypot)
+            .field("min_time_to_submit", &self.min_time_to_submit)
+            .field("max_render_age", &self.max_render_age)
+            .field("signed", &self.signing_key.is_some())
+            .finish()
+    }
+}
+
+impl BotProtection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name the honeypot field `name`, which the form must not otherwise use
+    pub fn honeypot(mut self, name: impl Into<String>) -> Self {
+        self.honeypot = Some(name.into());
+        self
+    }
+
+    pub fn without_honeypot(mut self) -> Self {
+        self.honeypot = None;
+        self
+    }
+
+    pub fn min_time_to_submit(mut self, min_time: Duration) -> Self {
+        self.min_time_to_submit = Some(min_time);
+        self
+    }
+
+    pub fn without_min_time_to_submit(mut self) -> Self {
+        self.min_time_to_submit = None;
+        self
+    }
+
+    /// Reject forms rendered longer than `max_age` before they are submitted
+    pub fn max_render_age(mut self, max_age: Duration) -> Self {
+        self.max_render_age = Some(max_age);
+        self
+    }
+
+    pub fn without_max_render_age(mut self) -> Self {
+        self.max_render_age = None;
+        self
+    }
+
+    /// Sign render times with `key`, a server secret, and accept only render
+    /// times signed with it
+    pub fn signing_key(mut self, key: impl AsRef<[u8]>) -> Self {
+        self.signing_key = Some(key.as_ref().to_vec());
+        self
+    }
+
+    pub fn honeypot_field(&self) -> Option<&str> {
+        self.honeypot.as_deref()
+    }
+
+    pub fn min_time(&self) -> Option<Duration> {
+        self.min_time_to_submit
+    }
+
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_render_age
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signing_key.is_some()
+    }
+
+    /// The hidden fields to submit with a form carrying `render_stamp`: the
+    /// empty honeypot and the render time
+    pub fn hidden_fields(&self, render_stamp: &str) -> Vec<(String, String)> {
+        let mut fields = Vec::new();
+        if let Some(honeypot) = &self.honeypot {
+            fields.push((honeypot.clone(), String::new()));
+        }
+        if self.min_time_to_submit.is_some() {
+            fields.push((RENDERED_AT_FIELD.to_string(), render_stamp.to_string()));
+        }
+        fields
+    }
+
+    /// The value of the render time field for a form rendered at
+    /// `rendered_at`, signed when there is a signing key
+    pub fn render_stamp(&self, rendered_at: DateTime<Utc>) -> String {
+        let time = rendered_at.to_rfc3339_opts(SecondsFormat::Millis, true);
+        match &self.signing_key {
+            Some(key) => {
+                let signature = render_time_mac(key, &time).finalize().into_bytes();
+                format!("{}~{}", time, hex(&signature))
+            }
+            None => time,
+        }
+    }
+
+    /// The render time in a submitted render stamp
+    ///
+    /// With a signing key only stamps signed with it are accepted, so a bot
+    /// cannot claim the form was rendered long ago.
+    pub fn verify_render_stamp(&self, stamp: &str) -> Result<DateTime<Utc>, SubmissionRejected> {
+        let (time, signature) = match stamp.split_once('~') {
+            Some((time, signature)) => (time, Some(signature)),
+            None => (stamp, None),
+        };
+        if let Some(key) = &self.signing_key {
+            let signature = signature
+                .and_then(unhex)
+                .ok_or(SubmissionRejected::InvalidRenderTime)?;
+            render_time_mac(key, time)
+                .verify_slice(&signature)
+                .map_err(|_| SubmissionRejected::InvalidRenderTime)?;
+        }
+        DateTime::parse_from_rfc3339(time)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|_| SubmissionRejected::InvalidRenderTime)
+    }
+
+    /// Check a submission made at `now` with the given honeypot content and
+    /// render time
+    ///
+    /// A missing render time counts as a failed check when a minimum time
+    /// is set, since forms rendered with the protection always send it.
+    pub fn check(
+        &self,
+        honeypot: Option<&str>,
+        rendered_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Result<(), SubmissionRejected> {
+        self.check_honeypot(honeypot)?;
+        if let Some(min_time) = self.min_time_to_submit {
+            let rendered_at = rendered_at.ok_or(SubmissionRejected::MissingRenderTime)?;
+            let elapsed = (now - rendered_at).to_std().unwrap_or_default();
+            if elapsed < min_time {
+                return Err(SubmissionRejected::TooFast);
+            }
+            if self.max_render_age.is_some_and(|max_age| elapsed > max_age) {
+                return Err(SubmissionRejected::ExpiredRenderTime);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the honeypot content alone
+    pub fn check_honeypot(&self, honeypot: Option<&str>) -> Result<(), SubmissionRejected> {
+        if self.honeypot.is_some() && honeypot.is_some_and(|value| !value.trim().is_empty()) {
+            return Err(SubmissionRejected::Honeypot);
+        }
+        Ok(())
+    }
+}
+
+/// Why a submission was refused before its values were looked at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionRejected {
+    /// The honeypot field was filled in
+    Honeypot,
+    /// The form was submitted sooner after rendering than a person could
+    TooFast,
+    /// The time the form was rendered is missing
+    MissingRenderTime,
+    /// The render time is unreadable or was not signed by the server
+    InvalidRenderTime,
+    /// The form was rendered longer ago than the protection accepts
+    ExpiredRenderTime,
+    MissingCsrfToken,
+    InvalidCsrfToken,
+}
+
+impl SubmissionRejected {
+    /// Whether the submission looks like it came from a bot rather than
+    /// from a stale or forged page
+    pub fn is_bot(self) -> bool {
+        matches!(
+            self,
+            SubmissionRejected::Honeypot
+                | SubmissionRejected::TooFast
+                | SubmissionRejected::MissingRenderTime
+                | SubmissionRejected::InvalidRenderTime
+        )
+    }
+}
+
+impl fmt::Display for SubmissionRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SubmissionRejected::Honeypot => "The honeypot field was filled in",
+            SubmissionRejected::TooFast => "The form was submitted too quickly",
+            SubmissionRejected::MissingRenderTime => "The form's render time is missing",
+            SubmissionRejected::InvalidRenderTime => "The form's render time is invalid",
+            SubmissionRejected::ExpiredRenderTime => "The form has expired",
+            SubmissionRejected::MissingCsrfToken => "The CSRF token is missing",
+            SubmissionRejected::InvalidCsrfToken => "The CSRF token is invalid",
+        })
+    }
+}
+
+impl std::error::Error for SubmissionRejected {}
+
+/// The value of the cookie `name` in a `Cookie` header or `document.cookie`
+pub fn parse_cookie(cookies: &str, name: &str) -> Option<String> {
+    cookies.split(';').find_map(|cookie| {
+        let (key, value) = cookie.split_once('=')?;
+        (key.trim() == name).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Compare a submitted CSRF token with the expected one in constant time
+pub fn csrf_tokens_match(expected: &str, submitted: &str) -> bool {
+    expected.len() == submitted.len()
+        && expected
+            .bytes()
+            .zip(submitted.bytes())
+            .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn render_time_mac(key: &[u8], time: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(time.as_bytes());
+    mac
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use wasm_bindgen::JsCast;
+
+    pub(super) fn meta_content(name: &str) -> Option<String> {
+        let selector = format!("meta[name=\"{}\"]", name.replace('"', "\\\""));
+        web_sys::window()?
+            .document()?
+            .query_selector(&selector)
+            .ok()
+            .flatten()?
+            .get_attribute("content")
+    }
+
+    pub(super) fn cookies() -> Option<String> {
+        web_sys::window()?
+            .document()?
+            .dyn_into::<web_sys::HtmlDocument>()
+            .ok()?
+            .cookie()
+            .ok()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod browser {
+    pub(super) fn meta_content(_name: &str) -> Option<String> {
+        None
+    }
+
+    pub(super) fn cookies() -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,767 @@
+use crate::core::persistence::StorageEntry;
+use crate::core::sensitive::{blank_json, json_path_mut, json_sensitive_paths, FieldEncryptor};
+use crate::core::traits::Form;
+use crate::core::{FormHandle, MemoryPersistence};
+use crate::error::FormError;
+use crate::submission::http::{show_response_errors, HttpResponse, HttpSubmitter};
+use chrono::{DateTime, Utc};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Called with the new connectivity whenever it changes
+pub type NetworkListener = Rc<dyn Fn(bool)>;
+
+/// Whether the device can reach the network, and when that changes
+pub trait NetworkStatus {
+    fn is_online(&self) -> bool;
+
+    /// Call `listener` whenever the device goes online or offline
+    fn subscribe(&self, listener: NetworkListener) -> Result<(), String>;
+}
+
+/// Connectivity as the browser reports it through `navigator.onLine` and
+/// the `online` and `offline` events
+///
+/// Outside the browser the device counts as online and never changes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrowserNetworkStatus;
+
+impl NetworkStatus for BrowserNetworkStatus {
+    fn is_online(&self) -> bool {
+        browser::is_online()
+    }
+
+    fn subscribe(&self, listener: NetworkListener) -> Result<(), String> {
+        browser::listen_to_connectivity(listener)
+    }
+}
+
+/// Connectivity set by hand, for tests and server rendering
+///
+/// Clones share their status, so a test can keep one to switch the
+/// network of a queue on and off.
+#[derive(Clone)]
+pub struct MemoryNetworkStatus {
+    online: Rc<Cell<bool>>,
+    listeners: Rc<RefCell<Vec<NetworkListener>>>,
+}
+
+impl MemoryNetworkStatus {
+    pub fn new(online: bool) -> Self {
+        Self {
+            online: Rc::new(Cell::new(online)),
+            listeners: Rc::default(),
+        }
+    }
+
+    /// Go online or offline, telling the listeners if that is a change
+    pub fn set_online(&self, online: bool) {
+        if self.online.replace(online) == online {
+            return;
+        }
+        // Listeners may subscribe in turn, so they run without the borrow held
+        let listeners = self.listeners.borrow().clone();
+        for listener in listeners {
+            listener(online);
+        }
+    }
+}
+
+impl Default for MemoryNetworkStatus {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl fmt::Debug for MemoryNetworkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryNetworkStatus")
+            .field("online", &self.online.get())
+            .finish()
+    }
+}
+
+impl NetworkStatus for MemoryNetworkStatus {
+    fn is_online(&self) -> bool {
+        self.online.get()
+    }
+
+    fn subscribe(&self, listener: NetworkListener) -> Result<(), String> {
+        self.listeners.borrow_mut().push(listener);
+        Ok(())
+    }
+}
+
+/// How often and how long apart queued submissions are retried
+///
+/// The wait after the `n`th failed attempt is `base_delay * 2^(n - 1)`, at
+/// most `max_delay`, shortened by up to `jitter` of itself at random so
+/// that clients coming back online together do not retry in step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Attempts after which a submission is given up as failed
+    pub max_attempts: u32,
+    /// Fraction of each wait drawn at random, from 0 to 1
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(300),
+            max_attempts: 8,
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The wait after `attempts` failed attempts, with `random` in `0..1`
+    pub fn delay(&self, attempts: u32, random: f64) -> Duration {
+        let exponent = attempts.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0) * random.clamp(0.0, 1.0);
+        delay.mul_f64(1.0 - jitter)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueuedStatus {
+    /// Waiting to be sent, or for its next attempt
+    Pending,
+    /// Rejected by the server or out of attempts; retried only on request
+    Failed,
+}
+
+/// A submission waiting in a [`SubmissionQueue`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedSubmission {
+    /// Sent as the `Idempotency-Key` header with every attempt, so the
+    /// server can tell a retry from a new submission
+    pub idempotency_key: String,
+    pub body: Value,
+    pub status: QueuedStatus,
+    /// Attempts made so far
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub queued_at: DateTime<Utc>,
+    pub next_attempt_at: DateTime<Utc>,
+    /// Paths of the sensitive values in `body`, which are only stored
+    /// encrypted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sensitive: Vec<String>,
+    /// Whether the sensitive values were lost when the queue was reloaded
+    #[serde(skip)]
+    incomplete: bool,
+}
+
+impl QueuedSubmission {
+    fn new(body: Value, sensitive: Vec<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            idempotency_key: idempotency_key(),
+            body,
+            sensitive,
+            incomplete: false,
+            status: QueuedStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            queued_at: now,
+            next_attempt_at: now,
+        }
+    }
+
+    /// The submission as stored, with its sensitive values blanked and
+    /// sealed by `encryptor` when there is one
+    fn seal(&self, encryptor: Option<&dyn FieldEncryptor>) -> Result<StoredSubmission, String> {
+        let mut item = self.clone();
+        let mut sealed = BTreeMap::new();
+        for path in &self.sensitive {
+            let Some(value) = json_path_mut(&mut item.body, path) else {
+                continue;
+            };
+            if let Some(encryptor) = encryptor {
+                let ciphertext = encryptor
+                    .encrypt(&value.to_string())
+                    .map_err(|e| format!("Failed to encrypt {}: {}", path, e))?;
+                sealed.insert(path.clone(), ciphertext);
+            }
+            *value = blank_json(value);
+        }
+        Ok(StoredSubmission { item, sealed })
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == QueuedStatus::Pending
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.status == QueuedStatus::Failed
+    }
+
+    /// The submission after an attempt that failed with `error`
+    fn after_failure(mut self, error: &FormError, retry: &RetryPolicy) -> Self {
+        self.attempts += 1;
+        self.last_error = Some(error.message().to_string());
+        if is_retryable(error) && self.attempts < retry.max_attempts {
+            let wait = retry.delay(self.attempts, random_unit());
+            self.next_attempt_at = Utc::now()
+                + chrono::Duration::from_std(wait).unwrap_or_else(|_| chrono::Duration::zero());
+        } else {
+            self.status = QueuedStatus::Failed;
+        }
+        self
+    }
+}
+
+/// A queued submission as kept in storage
+#[derive(Serialize, Deserialize)]
+struct StoredSubmission {
+    #[serde(flatten)]
+    item: QueuedSubmission,
+    /// Encrypted sensitive values by path
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    sealed: BTreeMap<String, String>,
+}
+
+impl StoredSubmission {
+    /// The submission with its sensitive values decrypted
+    ///
+    /// Without them it cannot be sent as submitted, so it fails until the
+    /// form is submitted again.
+    fn unseal(self, encryptor: Option<&dyn FieldEncryptor>) -> QueuedSubmission {
+        let mut item = self.item;
+        let restored = item.sensitive.iter().all(|path| {
+            let (Some(encryptor), Some(ciphertext)) = (encryptor, self.sealed.get(path)) else {
+                return false;
+            };
+            let Ok(value) = encryptor
+                .decrypt(ciphertext)
+                .and_then(|plaintext| serde_json::from_str(&plaintext).map_err(|e| e.to_string()))
+            else {
+                return false;
+            };
+            match json_path_mut(&mut item.body, path) {
+                Some(slot) => {
+                    *slot = value;
+                    true
+                }
+                None => false,
+            }
+        });
+        if !restored {
+            item.incomplete = true;
+            item.status = QueuedStatus::Failed;
+            item.last_error =
+                Some("Sensitive values were not kept; submit the form again".to_string());
+        }
+        item
+    }
+}
+
+/// What became of a submission handed to a [`SubmissionQueue`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueueOutcome {
+    /// The server accepted it right away
+    Sent(HttpResponse),
+    /// It was queued under this idempotency key
+    Queued(String),
+}
+
+/// Submissions that could not be sent yet, kept until they are
+///
+/// Submissions made while offline, or that fail without an answer from the
+/// server or with a 408, 429 or 5xx status, are saved in storage and sent
+/// again with exponential backoff and jitter (see [`RetryPolicy`]). Once
+/// [`start`](Self::start)ed in the browser, the queue retries them as they
+/// fall due and as soon as the device comes back online. Submissions the
+/// server rejects, or that run out of attempts, stay in the queue as failed
+/// until they are retried or removed. Sensitive fields are only stored
+/// encrypted, with [`with_encryptor`](Self::with_encryptor).
+///
+/// ```rust,ignore
+/// let queue = SubmissionQueue::local_storage(
+///     HttpSubmitter::post("/api/inspections"),
+///     "inspection-queue",
+/// );
+/// queue.start();
+///
+/// spawn_local(async move {
+///     match queue.submit(&form).await {
+///         Ok(QueueOutcome::Sent(_)) => show_toast("Inspection sent"),
+///         Ok(QueueOutcome::Queued(_)) => show_toast("Saved, will send when online"),
+///         Err(error) => log::warn!("{}", error),
+///     }
+/// });
+/// let waiting = queue.pending();
+/// ```
+#[derive(Clone)]
+pub struct SubmissionQueue {
+    submitter: HttpSubmitter,
+    entry: StorageEntry,
+    network: Rc<dyn NetworkStatus>,
+    retry: RetryPolicy,
+    items: RwSignal<Vec<QueuedSubmission>>,
+    flushing: Rc<Cell<bool>>,
+    /// Whether to flush again once the running flush is done
+    flush_again: Rc<Cell<bool>>,
+    /// Bumped for each scheduled flush; older timers see it and stop
+    schedule: Rc<Cell<u64>>,
+    encryptor: Option<Arc<dyn FieldEncryptor>>,
+}
+
+impl SubmissionQueue {
+    /// Queue kept in `localStorage` under `key`
+    pub fn local_storage(submitter: HttpSubmitter, key: impl Into<String>) -> Self {
+        Self::with_entry(submitter, StorageEntry::local_storage(key))
+    }
+
+    /// Queue kept in `sessionStorage` under `key`
+    pub fn session_storage(submitter: HttpSubmitter, key: impl Into<String>) -> Self {
+        Self::with_entry(submitter, StorageEntry::session_storage(key))
+    }
+
+    /// Queue kept among the entries of `persistence` under `key`
+    pub fn memory(
+        submitter: HttpSubmitter,
+        persistence: &MemoryPersistence,
+        key: impl Into<String>,
+    ) -> Self {
+        Self::with_entry(submitter, StorageEntry::memory(persistence, key))
+    }
+
+    fn with_entry(submitter: HttpSubmitter, entry: StorageEntry) -> Self {
+        let items = load_items(&entry, None);
+        Self {
+            submitter,
+            entry,
+            network: Rc::new(BrowserNetworkStatus),
+            retry: RetryPolicy::default(),
+            items: RwSignal::new(items),
+            flushing: Rc::default(),
+            flush_again: Rc::default(),
+            schedule: Rc::default(),
+            encryptor: None,
+        }
+    }
+
+    /// Keep the sensitive values of queued submissions encrypted with
+    /// `encryptor`
+    ///
+    /// Without an encryptor they are not stored at all, so submissions
+    /// still queued after a reload fail until the form is submitted again.
+    pub fn with_encryptor(mut self, encryptor: impl FieldEncryptor + 'static) -> Self {
+        let encryptor: Arc<dyn FieldEncryptor> = Arc::new(encryptor);
+        self.items
+            .set(load_items(&self.entry, Some(encryptor.as_ref())));
+        self.encryptor = Some(encryptor);
+        self
+    }
+
+    /// Tell whether the device is online with `network` instead of the browser
+    pub fn with_network(mut self, network: impl NetworkStatus + 'static) -> Self {
+        self.network = Rc::new(network);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        self.entry.key()
+    }
+
+    /// Every queued submission, oldest first
+    pub fn items(&self) -> ReadSignal<Vec<QueuedSubmission>> {
+        self.items.read_only()
+    }
+
+    /// Submissions still to be sent
+    pub fn pending(&self) -> Memo<Vec<QueuedSubmission>> {
+        let items = self.items;
+        Memo::new(move |_| {
+            items.with(|items| {
+                items
+                    .iter()
+                    .filter(|item| item.is_pending())
+                    .cloned()
+                    .collect()
+            })
+        })
+    }
+
+    /// Submissions given up on until they are retried or removed
+    pub fn failed(&self) -> Memo<Vec<QueuedSubmission>> {
+        let items = self.items;
+        Memo::new(move |_| {
+            items.with(|items| {
+                items
+                    .iter()
+                    .filter(|item| item.is_failed())
+                    .cloned()
+                    .collect()
+            })
+        })
+    }
+
+    /// Validate and submit the form, queueing it when it cannot be sent now
+    ///
+    /// Errors the server reports for a rejected submission are shown on the
+    /// form as with [`HttpSubmitter::submit`].
+    pub async fn submit<T>(&self, form: &FormHandle<T>) -> Result<QueueOutcome, FormError>
+    where
+        T: Form + PartialEq + Send + Sync,
+    {
+        let data = self.submitter.submission_body(form)?;
+        let result = match serde_json::to_value(&data) {
+            Ok(body) => {
+                let sensitive = json_sensitive_paths(&T::field_metadata(), &body);
+                self.enqueue(body, sensitive).await
+            }
+            Err(e) => Err(FormError::serialization_error(e.to_string(), None)),
+        };
+        match &result {
+            Ok(QueueOutcome::Sent(_)) => form.finish_successful_submission(),
+            // A queued submission may still fail, so it is not a success yet
+            Ok(QueueOutcome::Queued(_)) => form.finish_submission(),
+            Err(error) => {
+                form.finish_submission();
+                show_response_errors(form, error);
+            }
+        }
+        result
+    }
+
+    /// Send `body` as JSON, queueing it when it cannot be sent now
+    ///
+    /// Fails only when the server rejects the submission outright.
+    pub async fn send<B: Serialize + ?Sized>(&self, body: &B) -> Result<QueueOutcome, FormError> {
+        let body = serde_json::to_value(body)
+            .map_err(|e| FormError::serialization_error(e.to_string(), None))?;
+        self.enqueue(body, Vec::new()).await
+    }
+
+    async fn enqueue(
+        &self,
+        body: Value,
+        sensitive: Vec<String>,
+    ) -> Result<QueueOutcome, FormError> {
+        let item = QueuedSubmission::new(body, sensitive);
+        let key = item.idempotency_key.clone();
+        if !self.network.is_online() {
+            self.push(item);
+            return Ok(QueueOutcome::Queued(key));
+        }
+        match self.attempt(&item).await {
+            Ok(response) => Ok(QueueOutcome::Sent(response)),
+            Err(error) if is_retryable(&error) => {
+                self.push(item.after_failure(&error, &self.retry));
+                Ok(QueueOutcome::Queued(key))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Send the pending submissions whose next attempt is due
+    ///
+    /// Does nothing while offline or while another flush is running.
+    /// Returns how many submissions were sent.
+    pub async fn flush(&self) -> usize {
+        if !self.network.is_online() || self.flushing.replace(true) {
+            return 0;
+        }
+        let now = Utc::now();
+        let due: Vec<QueuedSubmission> = self.items.with_untracked(|items| {
+            items
+                .iter()
+                .filter(|item| item.is_pending() && item.next_attempt_at <= now)
+                .cloned()
+                .collect()
+        });
+
+        let mut sent = 0;
+        for item in due {
+            if !self.network.is_online() {
+                break;
+            }
+            let key = item.idempotency_key.clone();
+            match self.attempt(&item).await {
+                Ok(_) => {
+                    self.remove(&key);
+                    sent += 1;
+                }
+                Err(error) => {
+                    let item = item.after_failure(&error, &self.retry);
+                    self.replace(item);
+                }
+            }
+        }
+        self.flushing.set(false);
+        if self.flush_again.replace(false) {
+            self.spawn_flush();
+        }
+        sent
+    }
+
+    /// Make a failed submission pending again, due right away
+    ///
+    /// Returns whether a submission with that key was queued and can be
+    /// sent; one whose sensitive values were not kept cannot.
+    pub fn retry(&self, idempotency_key: &str) -> bool {
+        let Some(mut item) = self.items.with_untracked(|items| {
+            items
+                .iter()
+                .find(|item| item.idempotency_key == idempotency_key && !item.incomplete)
+                .cloned()
+        }) else {
+            return false;
+        };
+        item.status = QueuedStatus::Pending;
+        item.attempts = 0;
+        item.next_attempt_at = Utc::now();
+        self.replace(item);
+        true
+    }
+
+    /// Drop a submission from the queue
+    ///
+    /// Returns whether a submission with that key was queued.
+    pub fn remove(&self, idempotency_key: &str) -> bool {
+        let mut removed = false;
+        self.items.update(|items| {
+            let before = items.len();
+            items.retain(|item| item.idempotency_key != idempotency_key);
+            removed = items.len() != before;
+        });
+        self.save();
+        removed
+    }
+
+    /// Retry queued submissions as they fall due and whenever the device
+    /// comes back online
+    ///
+    /// Retries run on the browser's event loop, so nothing is scheduled when
+    /// rendering on the server; call [`flush`](Self::flush) there instead.
+    pub fn start(&self) {
+        let queue = self.clone();
+        let on_change: NetworkListener = Rc::new(move |online| {
+            if online {
+                queue.spawn_flush();
+            }
+        });
+        if let Err(error) = self.network.subscribe(on_change) {
+            log::warn!("Queued submissions are not retried on reconnect: {}", error);
+        }
+        self.spawn_flush();
+    }
+
+    /// Flush now, then again when the next pending submission falls due
+    ///
+    /// Only the latest call keeps its timer, so reconnecting many times
+    /// leaves a single retry scheduled. While another flush runs, that one
+    /// schedules the next when it is done.
+    fn spawn_flush(&self) {
+        if !cfg!(target_arch = "wasm32") {
+            return;
+        }
+        let generation = self.schedule.get().wrapping_add(1);
+        self.schedule.set(generation);
+        let queue = self.clone();
+        leptos::task::spawn_local(async move {
+            if queue.flushing.get() {
+                queue.flush_again.set(true);
+                return;
+            }
+            queue.flush().await;
+            let superseded = || queue.schedule.get() != generation;
+            if superseded() || !queue.network.is_online() {
+                return;
+            }
+            if let Some(wait) = queue.next_attempt_in() {
+                let delay_ms = u32::try_from(wait.as_millis()).unwrap_or(u32::MAX);
+                gloo_timers::future::TimeoutFuture::new(delay_ms).await;
+                if !superseded() {
+                    queue.spawn_flush();
+                }
+            }
+        });
+    }
+
+    /// Time until the earliest pending submission falls due
+    fn next_attempt_in(&self) -> Option<Duration> {
+        let next = self.items.with_untracked(|items| {
+            items
+                .iter()
+                .filter(|item| item.is_pending())
+                .map(|item| item.next_attempt_at)
+                .min()
+        })?;
+        Some((next - Utc::now()).to_std().unwrap_or_default())
+    }
+
+    async fn attempt(&self, item: &QueuedSubmission) -> Result<HttpResponse, FormError> {
+        self.submitter
+            .clone()
+            .header("Idempotency-Key", item.idempotency_key.clone())
+            .send(&item.body)
+            .await
+    }
+
+    fn push(&self, item: QueuedSubmission) {
+        self.items.update(|items| items.push(item));
+        self.save();
+    }
+
+    /// Replace the submission with the same key, unless it was removed meanwhile
+    fn replace(&self, item: QueuedSubmission) {
+        self.items.update(|items| {
+            if let Some(slot) = items
+                .iter_mut()
+                .find(|slot| slot.idempotency_key == item.idempotency_key)
+            {
+                *slot = item;
+            }
+        });
+        self.save();
+    }
+
+    fn save(&self) {
+        let saved = self.items.with_untracked(|items| {
+            if items.is_empty() {
+                return self.entry.remove();
+            }
+            let stored = items
+                .iter()
+                .map(|item| item.seal(self.encryptor.as_deref()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| FormError::serialization_error(e, None))?;
+            let json = serde_json::to_string(&stored)
+                .map_err(|e| FormError::serialization_error(e.to_string(), None))?;
+            self.entry.write(&json)
+        });
+        if let Err(error) = saved {
+            log::warn!("Failed to save submission queue: {}", error);
+        }
+    }
+}
+
+impl fmt::Debug for SubmissionQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubmissionQueue")
+            .field("submitter", &self.submitter)
+            .field("key", &self.entry.key())
+            .field("retry", &self.retry)
+            .field("queued", &self.items.with_untracked(Vec::len))
+            .finish()
+    }
+}
+
+fn load_items(
+    entry: &StorageEntry,
+    encryptor: Option<&dyn FieldEncryptor>,
+) -> Vec<QueuedSubmission> {
+    let stored: Vec<StoredSubmission> = match entry.read() {
+        Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_else(|error| {
+            log::warn!("Discarded unreadable submission queue: {}", error);
+            Vec::new()
+        }),
+        Ok(None) => Vec::new(),
+        Err(error) => {
+            log::warn!("Failed to load submission queue: {}", error);
+            Vec::new()
+        }
+    };
+    stored
+        .into_iter()
+        .map(|stored| stored.unseal(encryptor))
+        .collect()
+}
+
+/// Whether a failed attempt may succeed later: the server was not reached,
+/// or it answered that it could not handle the submission for now
+fn is_retryable(error: &FormError) -> bool {
+    match error {
+        FormError::SubmissionError { status_code, .. } => match status_code {
+            None => true,
+            Some(status) => matches!(status, 408 | 429) || *status >= 500,
+        },
+        _ => false,
+    }
+}
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// 64 random bits, different on every call
+fn random_bits() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(SEQUENCE.fetch_add(1, Ordering::Relaxed));
+    hasher.write_i64(Utc::now().timestamp_nanos_opt().unwrap_or_default());
+    hasher.finish()
+}
+
+/// A random number in `0..1`
+fn random_unit() -> f64 {
+    (random_bits() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn idempotency_key() -> String {
+    format!("{:016x}{:016x}", random_bits(), random_bits())
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use super::NetworkListener;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    pub(super) fn is_online() -> bool {
+        web_sys::window()
+            .map(|window| window.navigator().on_line())
+            .unwrap_or(true)
+    }
+
+    pub(super) fn listen_to_connectivity(listener: NetworkListener) -> Result<(), String> {
+        let window = web_sys::window().ok_or("No window available")?;
+        for (event, online) in [("online", true), ("offline", false)] {
+            let listener = listener.clone();
+            let on_change = Closure::<dyn Fn()>::new(move || listener(online));
+            window
+                .add_event_listener_with_callback(event, on_change.as_ref().unchecked_ref())
+                .map_err(|e| format!("Failed to listen to {} events: {:?}", event, e))?;
+            // The listener lives as long as the page
+            on_change.forget();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod browser {
+    use super::NetworkListener;
+
+    pub(super) fn is_online() -> bool {
+        true
+    }
+
+    pub(super) fn listen_to_connectivity(_listener: NetworkListener) -> Result<(), String> {
+        Err("Connectivity events are only available in the browser".to_string())
+    }
+}
@@ -0,0 +1,366 @@
+use crate::core::convert::FormValue;
+use crate::core::traits::Form;
+use crate::core::types::{FieldValue, FileData};
+use crate::core::FormHandle;
+use crate::error::FormError;
+use crate::submission::encoding::{
+    form_from_values, multipart_parts, parts_to_values, urlencoded_parts,
+};
+use crate::submission::protection::{
+    csrf_tokens_match, BotProtection, SubmissionRejected, CSRF_FIELD, RENDERED_AT_FIELD,
+};
+use crate::validation::ValidationErrors;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A submission read back on the server, valid or not
+///
+/// Holds what is needed to render the form again after a submission made
+/// without JavaScript: the submitted values and every error found in them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Submission<T> {
+    pub values: T,
+    pub errors: ValidationErrors,
+}
+
+impl<T: Form> Submission<T> {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn into_result(self) -> Result<T, ValidationErrors> {
+        if self.errors.is_empty() {
+            Ok(self.values)
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    /// A form showing the submitted values and their errors
+    pub fn into_form(self) -> FormHandle<T>
+    where
+        T: PartialEq + Send + Sync,
+    {
+        let form = FormHandle::new(self.values);
+        if !self.errors.is_empty() {
+            form.set_errors(self.errors);
+        }
+        form
+    }
+}
+
+/// Decode a submitted body and validate it with the form's own rules
+///
+/// Meant for server functions and HTTP handlers: it uses no signals and no
+/// browser APIs. `content_type` selects the decoder; JSON (including
+/// `application/*+json`), URL-encoded and multipart bodies are understood.
+/// Bodies that cannot be decoded are reported as errors too, on the field
+/// at fault where it is known, so the client can show every problem the
+/// same way.
+///
+/// ```rust,ignore
+/// let signup: Signup = validate_submission(headers.get("content-type"), &body)?;
+/// ```
+pub fn validate_submission<T: Form + FormValue>(
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<T, ValidationErrors> {
+    read_submission(content_type, body).into_result()
+}
+
+/// Decode and validate a submitted body, keeping the values even when invalid
+///
+/// Use this to render the form again when a browser without JavaScript
+/// posted it. Values that cannot be read as their field's type are reported
+/// on that field and replaced by its default.
+///
+/// ```rust,ignore
+/// let submission = read_submission::<Signup>(content_type, &body);
+/// if submission.is_valid() {
+///     return Redirect::to("/welcome");
+/// }
+/// let form = submission.into_form();
+/// view! { <AutoForm form=form action="/signup"/> }
+/// ```
+pub fn read_submission<T: Form + FormValue>(
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Submission<T> {
+    match decode_values(content_type, body) {
+        Ok(values) => read_values(values),
+        Err(errors) => Submission {
+            values: T::default_values(),
+            errors,
+        },
+    }
+}
+
+/// The checks a public form's submissions must pass before they are read
+///
+/// ```rust,ignore
+/// let protection = SubmissionProtection::new()
+///     .csrf_token(session.csrf_token())
+///     .csrf_header_value(headers.get(CSRF_HEADER))
+///     .bots(BotProtection::new().signing_key(&config.form_secret));
+/// let submission = read_protected_submission::<Signup>(content_type, &body, &protection)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SubmissionProtection {
+    csrf_token: Option<String>,
+    csrf_field: Option<String>,
+    csrf_header_value: Option<String>,
+    bots: Option<BotProtection>,
+}
+
+impl SubmissionProtection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the session's CSRF token, in the header or the form field
+    pub fn csrf_token(mut self, expected: impl Into<String>) -> Self {
+        self.csrf_token = Some(expected.into());
+        self
+    }
+
+    /// Read the token from the form field `name` instead of `csrf_token`
+    pub fn csrf_field(mut self, name: impl Into<String>) -> Self {
+        self.csrf_field = Some(name.into());
+        self
+    }
+
+    /// The CSRF header sent with the request, which takes precedence over
+    /// the form field
+    pub fn csrf_header_value(mut self, value: Option<&str>) -> Self {
+        self.csrf_header_value = value.map(str::to_string);
+        self
+    }
+
+    /// Check the honeypot, and the time to submit when `bots` has a signing
+    /// key; only render times signed with it are accepted
+    pub fn bots(mut self, bots: BotProtection) -> Self {
+        self.bots = Some(bots);
+        self
+    }
+
+    /// Check the submitted values, removing the fields the checks use
+    pub fn check(
+        &self,
+        values: &mut HashMap<String, FieldValue>,
+        now: DateTime<Utc>,
+    ) -> Result<(), SubmissionRejected> {
+        let csrf_field = self.csrf_field.as_deref().unwrap_or(CSRF_FIELD);
+        let submitted_token = text_value(values.remove(csrf_field));
+        if let Some(expected) = &self.csrf_token {
+            let token = self
+                .csrf_header_value
+                .clone()
+                .or(submitted_token)
+                .filter(|token| !token.is_empty())
+                .ok_or(SubmissionRejected::MissingCsrfToken)?;
+            if !csrf_tokens_match(expected, &token) {
+                return Err(SubmissionRejected::InvalidCsrfToken);
+            }
+        }
+
+        if let Some(bots) = &self.bots {
+            let honeypot = bots
+                .honeypot_field()
+                .and_then(|field| text_value(values.remove(field)));
+            let stamp = text_value(values.remove(RENDERED_AT_FIELD));
+            // A render time the server did not sign proves nothing, so
+            // without a signing key there is no time to check
+            if bots.is_signed() {
+                let rendered_at = match (bots.min_time(), stamp) {
+                    (Some(_), Some(stamp)) => Some(bots.verify_render_stamp(&stamp)?),
+                    _ => None,
+                };
+                bots.check(honeypot.as_deref(), rendered_at, now)?;
+            } else {
+                bots.check_honeypot(honeypot.as_deref())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check a submission against `protection`, then decode and validate it
+///
+/// Rejected submissions are not decoded any further, so a bot learns
+/// nothing about the form's rules; answer them with a generic error. The
+/// protection fields are removed before the values are read.
+pub fn read_protected_submission<T: Form + FormValue>(
+    content_type: Option<&str>,
+    body: &[u8],
+    protection: &SubmissionProtection,
+) -> Result<Submission<T>, SubmissionRejected> {
+    match decode_values(content_type, body) {
+        Ok(mut values) => {
+            protection.check(&mut values, Utc::now())?;
+            Ok(read_values(values))
+        }
+        Err(errors) => {
+            // An unreadable body carries no tokens either
+            protection.check(&mut HashMap::new(), Utc::now())?;
+            Ok(Submission {
+                values: T::default_values(),
+                errors,
+            })
+        }
+    }
+}
+
+/// Decode a JSON body and validate it
+///
+/// Fields missing from the body keep their default values, so the
+/// payload of [`HttpSubmitter`](crate::submission::HttpSubmitter), which
+/// leaves out hidden fields, is accepted.
+pub fn validate_json<T: Form + FormValue>(body: &[u8]) -> Result<T, ValidationErrors> {
+    read_submission(Some("application/json"), body).into_result()
+}
+
+/// Decode a URL-encoded body and validate it
+pub fn validate_urlencoded<T: Form + FormValue>(body: &str) -> Result<T, ValidationErrors> {
+    read_submission(Some("application/x-www-form-urlencoded"), body.as_bytes()).into_result()
+}
+
+fn decode_values(
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<HashMap<String, FieldValue>, ValidationErrors> {
+    let content_type = content_type.unwrap_or_default();
+    let mime_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    if mime_type == "application/json"
+        || (mime_type.starts_with("application/") && mime_type.ends_with("+json"))
+    {
+        json_values(body)
+    } else if mime_type == "application/x-www-form-urlencoded" {
+        std::str::from_utf8(body)
+            .map_err(|_| form_error("The request body is not UTF-8"))
+            .and_then(|body| urlencoded_parts(body).map_err(decode_errors))
+            .map(parts_to_values)
+    } else if mime_type == "multipart/form-data" {
+        multipart_parts(content_type, body)
+            .map(parts_to_values)
+            .map_err(decode_errors)
+    } else if mime_type.is_empty() {
+        Err(form_error("The request has no content type"))
+    } else {
+        Err(form_error(&format!(
+            "Unsupported content type {}",
+            mime_type
+        )))
+    }
+}
+
+fn text_value(value: Option<FieldValue>) -> Option<String> {
+    match value? {
+        FieldValue::String(text) => Some(text),
+        FieldValue::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn json_values(body: &[u8]) -> Result<HashMap<String, FieldValue>, ValidationErrors> {
+    let json: Value = serde_json::from_slice(body)
+        .map_err(|e| form_error(&format!("Invalid JSON body: {}", e)))?;
+    match json_to_field_value(json) {
+        FieldValue::Object(object) => Ok(object),
+        _ => Err(form_error("Expected a JSON object")),
+    }
+}
+
+/// Build the form, dropping values it cannot read, then validate it
+fn read_values<T: Form + FormValue>(mut object: HashMap<String, FieldValue>) -> Submission<T> {
+    let mut errors = ValidationErrors::new();
+    let values = loop {
+        match form_from_values::<T>(object.clone()) {
+            Ok(values) => break values,
+            Err(error) => {
+                let field = match &error {
+                    FormError::SerializationError {
+                        field: Some(field), ..
+                    } => Some(field.clone()),
+                    _ => None,
+                };
+                errors.merge(decode_errors(error));
+                match field {
+                    Some(field) if object.remove(&field).is_some() => continue,
+                    _ => break T::default_values(),
+                }
+            }
+        }
+    };
+
+    if let Err(invalid) = values.validate() {
+        // A field that could not be read already has its error
+        for (field, messages) in invalid.field_errors {
+            errors.field_errors.entry(field).or_insert(messages);
+        }
+        errors.form_errors.extend(invalid.form_errors);
+    }
+    Submission { values, errors }
+}
+
+/// A decoding failure as validation errors, on the field it names
+fn decode_errors(error: FormError) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    match error {
+        FormError::SerializationError {
+            message,
+            field: Some(field),
+        } => {
+            let prefix = format!("{}: ", field);
+            let message = message.strip_prefix(&prefix).unwrap_or(&message);
+            errors.add_field_error(&field, message.to_string());
+        }
+        other => errors.add_form_error(other.to_string()),
+    }
+    errors
+}
+
+fn form_error(message: &str) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    errors.add_form_error(message.to_string());
+    errors
+}
+
+fn json_to_field_value(value: Value) -> FieldValue {
+    match value {
+        Value::Null => FieldValue::Null,
+        Value::Bool(b) => FieldValue::Boolean(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => FieldValue::Integer(i),
+            None => FieldValue::Number(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => FieldValue::String(s),
+        Value::Array(items) => {
+            FieldValue::Array(items.into_iter().map(json_to_field_value).collect())
+        }
+        Value::Object(object) => {
+            let is_file = object.len() == 4
+                && ["name", "size", "mime_type", "data"]
+                    .iter()
+                    .all(|key| object.contains_key(*key));
+            if is_file {
+                if let Ok(file) = serde_json::from_value::<FileData>(Value::Object(object.clone()))
+                {
+                    return FieldValue::File(file);
+                }
+            }
+            FieldValue::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, json_to_field_value(value)))
+                    .collect(),
+            )
+        }
+    }
+}
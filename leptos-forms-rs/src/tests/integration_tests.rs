@@ -24,6 +24,7 @@ impl Form for SimpleTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             crate::core::FieldMetadata {
                 name: "tags".to_string(),
@@ -33,6 +34,7 @@ impl Form for SimpleTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
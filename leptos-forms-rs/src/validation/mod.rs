@@ -7,7 +7,8 @@ use std::collections::HashMap;
 pub type FieldValidator = Box<dyn Fn(&FieldValue) -> Result<(), String> + Send + Sync>;
 
 /// Validation errors for a form
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ValidationErrors {
     pub field_errors: HashMap<String, Vec<String>>,
     pub form_errors: Vec<String>,
@@ -81,6 +82,27 @@ impl ValidationErrors {
         }
         self.form_errors.extend(other.form_errors);
     }
+
+    /// Merge errors reported by a nested form located at `path`
+    ///
+    /// Field errors are prefixed with the path and form-level errors are
+    /// attached to the nested field itself. An empty path merges as-is.
+    pub fn merge_at(&mut self, path: &str, other: ValidationErrors) {
+        if path.is_empty() {
+            self.merge(other);
+            return;
+        }
+
+        for (field, errors) in other.field_errors {
+            self.field_errors
+                .entry(crate::core::field_path(path, &field))
+                .or_default()
+                .extend(errors);
+        }
+        for error in other.form_errors {
+            self.add_field_error(path, error);
+        }
+    }
 }
 
 impl std::fmt::Display for ValidationErrors {
@@ -458,22 +480,22 @@ impl ValidationRuleEngine {
                     }
                 }
                 Validator::Range(min, max) => {
-                    if let FieldValue::Number(n) = value {
-                        if *n < *min || *n > *max {
+                    if let Some(n) = value.as_number() {
+                        if n < *min || n > *max {
                             errors.push(format!("Value must be between {} and {}", min, max));
                         }
                     }
                 }
                 Validator::Min(min_val) => {
-                    if let FieldValue::Number(n) = value {
-                        if *n < *min_val {
+                    if let Some(n) = value.as_number() {
+                        if n < *min_val {
                             errors.push(format!("Value must be at least {}", min_val));
                         }
                     }
                 }
                 Validator::Max(max_val) => {
-                    if let FieldValue::Number(n) = value {
-                        if *n > *max_val {
+                    if let Some(n) = value.as_number() {
+                        if n > *max_val {
                             errors.push(format!("Value must be at most {}", max_val));
                         }
                     }
@@ -523,6 +545,50 @@ pub fn validate_form<T: crate::core::Form>(form: &T) -> Result<(), ValidationErr
     }
 }
 
+/// Validate dynamic values against field metadata, recursing into nested forms
+///
+/// Errors are reported under `path`, using the same `parent.child` and
+/// `items[0].child` keys as the `Form` derive. Fields hidden by their
+/// `visible_when` rule are skipped.
+pub fn validate_values(
+    metadata: &[crate::core::FieldMetadata],
+    values: &HashMap<String, FieldValue>,
+    path: &str,
+    errors: &mut ValidationErrors,
+) {
+    let engine = ValidationRuleEngine::new();
+
+    for field_meta in metadata {
+        if !field_meta.is_visible_in(values) {
+            continue;
+        }
+        let field_path = crate::core::field_path(path, &field_meta.name);
+        let field_value = values.get(&field_meta.name).unwrap_or(&FieldValue::Null);
+
+        for error in engine.validate_field(&field_path, field_value, &field_meta.validators) {
+            errors.add_field_error(&field_path, error);
+        }
+
+        if field_meta.nested_fields.is_empty() {
+            continue;
+        }
+        match field_value {
+            FieldValue::Object(nested) => {
+                validate_values(&field_meta.nested_fields, nested, &field_path, errors)
+            }
+            FieldValue::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    if let FieldValue::Object(nested) = item {
+                        let item_path = format!("{}[{}]", field_path, index);
+                        validate_values(&field_meta.nested_fields, nested, &item_path, errors);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 /// Built-in validators implementation
 pub struct Validators;
 
@@ -807,53 +873,12 @@ impl ConditionalValidator {
         Ok(())
     }
 
-    #[allow(clippy::only_used_in_recursion)]
     fn evaluate_condition<T: crate::core::Form>(
         &self,
         form: &T,
         condition: &FieldCondition,
     ) -> Result<bool, String> {
-        match condition {
-            FieldCondition::Equals(field, value) => {
-                let field_value = form.get_field_value(field);
-                Ok(field_value == *value)
-            }
-            FieldCondition::NotEquals(field, value) => {
-                let field_value = form.get_field_value(field);
-                Ok(field_value != *value)
-            }
-            FieldCondition::Contains(field, value) => {
-                if let FieldValue::String(field_str) = form.get_field_value(field) {
-                    Ok(field_str.contains(value))
-                } else {
-                    Ok(false)
-                }
-            }
-            FieldCondition::IsEmpty(field) => {
-                let field_value = form.get_field_value(field);
-                Ok(field_value.is_empty())
-            }
-            FieldCondition::IsNotEmpty(field) => {
-                let field_value = form.get_field_value(field);
-                Ok(!field_value.is_empty())
-            }
-            FieldCondition::And(conditions) => {
-                for condition in conditions {
-                    if !self.evaluate_condition(form, condition)? {
-                        return Ok(false);
-                    }
-                }
-                Ok(true)
-            }
-            FieldCondition::Or(conditions) => {
-                for condition in conditions {
-                    if self.evaluate_condition(form, condition)? {
-                        return Ok(true);
-                    }
-                }
-                Ok(false)
-            }
-        }
+        Ok(condition.evaluate(form))
     }
 }
 
@@ -891,8 +916,11 @@ impl ConditionalRule {
     }
 }
 
-/// Field conditions for conditional validation
-#[derive(Debug, Clone)]
+/// Field conditions for conditional validation and field visibility
+///
+/// Field names may be paths such as `address.country`, resolved against the
+/// form or nested object the condition is evaluated on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FieldCondition {
     Equals(String, FieldValue),
     NotEquals(String, FieldValue),
@@ -931,4 +959,26 @@ impl FieldCondition {
     pub fn or(conditions: Vec<FieldCondition>) -> Self {
         Self::Or(conditions)
     }
+
+    /// Evaluate the condition against the fields of `form`
+    pub fn evaluate<T: crate::core::Form>(&self, form: &T) -> bool {
+        self.evaluate_with(&|path| crate::core::get_form_path_value(form, path).unwrap_or_default())
+    }
+
+    /// Evaluate the condition, reading field values through `lookup`
+    pub fn evaluate_with(&self, lookup: &dyn Fn(&str) -> FieldValue) -> bool {
+        match self {
+            Self::Equals(field, value) => lookup(field) == *value,
+            Self::NotEquals(field, value) => lookup(field) != *value,
+            Self::Contains(field, value) => match lookup(field) {
+                FieldValue::String(text) => text.contains(value.as_str()),
+                FieldValue::Array(items) => items.contains(&FieldValue::String(value.clone())),
+                _ => false,
+            },
+            Self::IsEmpty(field) => lookup(field).is_empty(),
+            Self::IsNotEmpty(field) => !lookup(field).is_empty(),
+            Self::And(conditions) => conditions.iter().all(|c| c.evaluate_with(lookup)),
+            Self::Or(conditions) => conditions.iter().any(|c| c.evaluate_with(lookup)),
+        }
+    }
 }
@@ -36,6 +36,7 @@ impl Form for WasmTestForm {
                 dependencies: Vec::new(),
                 attributes: std::collections::HashMap::new(),
                 validators: vec![Validator::Required],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -45,6 +46,7 @@ impl Form for WasmTestForm {
                 dependencies: Vec::new(),
                 attributes: std::collections::HashMap::new(),
                 validators: vec![Validator::Required, Validator::Email],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "age".to_string(),
@@ -58,6 +60,7 @@ impl Form for WasmTestForm {
                 dependencies: Vec::new(),
                 attributes: std::collections::HashMap::new(),
                 validators: vec![Validator::Min(0.0), Validator::Max(120.0)],
+                ..Default::default()
             },
         ]
     }
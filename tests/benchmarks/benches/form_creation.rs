@@ -22,6 +22,7 @@ impl Form for BenchmarkForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "field2".to_string(),
@@ -31,6 +32,7 @@ impl Form for BenchmarkForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "field3".to_string(),
@@ -40,6 +42,7 @@ impl Form for BenchmarkForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "field4".to_string(),
@@ -49,6 +52,7 @@ impl Form for BenchmarkForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "field5".to_string(),
@@ -58,6 +62,7 @@ impl Form for BenchmarkForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
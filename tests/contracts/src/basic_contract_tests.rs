@@ -21,6 +21,7 @@ impl Form for BasicTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required],
+            ..Default::default()
         }]
     }
 
@@ -43,6 +43,7 @@ impl Form for ConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required, Validator::MinLength(2)],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -52,6 +53,7 @@ impl Form for ConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required, Validator::Email],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "age".to_string(),
@@ -65,6 +67,7 @@ impl Form for ConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Min(18.0), Validator::Max(100.0)],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "preferences".to_string(),
@@ -74,6 +77,7 @@ impl Form for ConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: Vec::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "newsletter".to_string(),
@@ -83,6 +87,7 @@ impl Form for ConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: Vec::new(),
+                ..Default::default()
             },
         ]
     }
@@ -236,6 +241,7 @@ impl Form for ComplexConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "user.last_name".to_string(),
@@ -245,6 +251,7 @@ impl Form for ComplexConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "user.email".to_string(),
@@ -254,6 +261,7 @@ impl Form for ComplexConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required, Validator::Email],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "settings.theme".to_string(),
@@ -274,6 +282,7 @@ impl Form for ComplexConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: Vec::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "settings.notifications".to_string(),
@@ -283,6 +292,7 @@ impl Form for ComplexConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: Vec::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "settings.language".to_string(),
@@ -303,6 +313,7 @@ impl Form for ComplexConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: Vec::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "tags".to_string(),
@@ -312,6 +323,7 @@ impl Form for ComplexConsumerForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: Vec::new(),
+                ..Default::default()
             },
         ]
     }
@@ -40,6 +40,7 @@ impl Form for TestContractForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -49,6 +50,7 @@ impl Form for TestContractForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required, Validator::Email],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "age".to_string(),
@@ -62,6 +64,7 @@ impl Form for TestContractForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Min(0.0), Validator::Max(120.0)],
+                ..Default::default()
             },
         ]
     }
@@ -149,6 +152,7 @@ impl Form for LegacyForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required],
+            ..Default::default()
         }]
     }
 
@@ -32,6 +32,7 @@ impl Form for SimpleTestForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -41,6 +42,7 @@ impl Form for SimpleTestForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required, Validator::Email],
+                ..Default::default()
             },
         ]
     }
@@ -29,6 +29,7 @@ impl Form for IntegrationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -38,6 +39,7 @@ impl Form for IntegrationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "password".to_string(),
@@ -47,6 +49,7 @@ impl Form for IntegrationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "confirm_password".to_string(),
@@ -56,6 +59,7 @@ impl Form for IntegrationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "age".to_string(),
@@ -65,6 +69,7 @@ impl Form for IntegrationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "newsletter".to_string(),
@@ -74,6 +79,7 @@ impl Form for IntegrationTestForm {
                 default_value: Some(FieldValue::Boolean(false)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
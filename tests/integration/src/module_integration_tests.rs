@@ -43,6 +43,7 @@ impl Form for IntegrationForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required, Validator::MinLength(2)],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -52,6 +53,7 @@ impl Form for IntegrationForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Required, Validator::Email],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "age".to_string(),
@@ -65,6 +67,7 @@ impl Form for IntegrationForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Min(0.0), Validator::Max(120.0)],
+                ..Default::default()
             },
             FieldMetadata {
                 name: "preferences".to_string(),
@@ -74,6 +77,7 @@ impl Form for IntegrationForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: Vec::new(),
+                ..Default::default()
             },
         ]
     }
@@ -25,7 +25,8 @@ impl Form for AdditionalInputTypesForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
-            },
+..Default::default()
+},
             leptos_forms_rs::core::FieldMetadata {
                 name: "markdown_content".to_string(),
                 field_type: leptos_forms_rs::core::types::FieldType::Markdown,
@@ -34,7 +35,8 @@ impl Form for AdditionalInputTypesForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
-            },
+..Default::default()
+},
             leptos_forms_rs::core::FieldMetadata {
                 name: "code_content".to_string(),
                 field_type: leptos_forms_rs::core::types::FieldType::Code,
@@ -43,7 +45,8 @@ impl Form for AdditionalInputTypesForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
-            },
+..Default::default()
+},
             leptos_forms_rs::core::FieldMetadata {
                 name: "uploaded_files".to_string(),
                 field_type: leptos_forms_rs::core::types::FieldType::File(leptos_forms_rs::core::types::FileConstraints {
@@ -56,7 +59,8 @@ impl Form for AdditionalInputTypesForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
-            },
+..Default::default()
+},
             leptos_forms_rs::core::FieldMetadata {
                 name: "image_files".to_string(),
                 field_type: leptos_forms_rs::core::types::FieldType::File(leptos_forms_rs::core::types::FileConstraints {
@@ -69,7 +73,8 @@ impl Form for AdditionalInputTypesForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
-            },
+..Default::default()
+},
             leptos_forms_rs::core::FieldMetadata {
                 name: "document_files".to_string(),
                 field_type: leptos_forms_rs::core::types::FieldType::File(leptos_forms_rs::core::types::FileConstraints {
@@ -86,7 +91,8 @@ impl Form for AdditionalInputTypesForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
-            },
+..Default::default()
+},
         ]
     }
 
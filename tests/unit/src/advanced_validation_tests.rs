@@ -47,6 +47,7 @@ impl Form for AdvancedValidationForm {
                 Validator::Pattern(r"^[A-Za-z\d@$!%*?&]+$".to_string()),
             ],
             attributes: HashMap::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -60,6 +61,7 @@ impl Form for AdvancedValidationForm {
                 Validator::Custom("password_match".to_string()),
             ],
             attributes: HashMap::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -73,6 +75,7 @@ impl Form for AdvancedValidationForm {
                 Validator::Custom("email_domain_check".to_string()),
             ],
             attributes: HashMap::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -88,6 +91,7 @@ impl Form for AdvancedValidationForm {
                 Validator::Custom("username_availability".to_string()),
             ],
             attributes: HashMap::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -102,6 +106,7 @@ impl Form for AdvancedValidationForm {
             dependencies: Vec::new(),
             validators: vec![Validator::Min(18.0), Validator::Max(120.0)],
             attributes: HashMap::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -115,6 +120,7 @@ impl Form for AdvancedValidationForm {
                 Validator::Custom("date_range_validation".to_string()),
             ],
             attributes: HashMap::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -128,6 +134,7 @@ impl Form for AdvancedValidationForm {
                 Validator::Custom("date_range_validation".to_string()),
             ],
             attributes: HashMap::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -138,6 +145,7 @@ impl Form for AdvancedValidationForm {
             dependencies: Vec::new(),
             validators: vec![Validator::Custom("complex_business_rule".to_string())],
             attributes: HashMap::new(),
+            ..Default::default()
         });
 
         metadata
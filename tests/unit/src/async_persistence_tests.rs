@@ -0,0 +1,195 @@
+//! Tests for asynchronous persistence, file blobs and quota fallback
+
+use futures::executor::block_on;
+use leptos_forms_rs::core::{
+    AsyncFormPersistence, DraftLoad, FileBlobPersistence, FileData, IndexedDbBlobStore,
+    IndexedDbPersistence, LocalStoragePersistence, MemoryBlobStore, MemoryPersistence,
+    QuotaFallback,
+};
+use leptos_forms_rs::{Form, FormError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Claim {
+    title: String,
+    receipt: Option<FileData>,
+    photos: Vec<FileData>,
+}
+
+fn file(name: &str, data: &[u8]) -> FileData {
+    FileData {
+        name: name.to_string(),
+        size: data.len(),
+        mime_type: "image/png".to_string(),
+        data: data.to_vec(),
+    }
+}
+
+fn claim() -> Claim {
+    Claim {
+        title: "Taxi".to_string(),
+        receipt: Some(file("receipt.png", &[1, 2, 3, 4])),
+        photos: vec![file("front.png", &[5, 6]), file("back.png", &[7, 8, 9])],
+    }
+}
+
+fn storage_type(error: &FormError) -> &str {
+    match error {
+        FormError::PersistenceError { storage_type, .. } => storage_type,
+        other => panic!("expected a persistence error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_memory_storage_round_trip_through_async_api() {
+    let storage = MemoryPersistence::new("claim").into_async();
+    assert!(!block_on(AsyncFormPersistence::<Claim>::exists(&storage)).unwrap());
+
+    block_on(storage.save(&claim())).unwrap();
+    assert!(block_on(AsyncFormPersistence::<Claim>::exists(&storage)).unwrap());
+    assert_eq!(block_on(storage.load()).unwrap(), Some(claim()));
+
+    block_on(AsyncFormPersistence::<Claim>::clear(&storage)).unwrap();
+    assert_eq!(
+        block_on(AsyncFormPersistence::<Claim>::load_draft(&storage)).unwrap(),
+        DraftLoad::Empty
+    );
+}
+
+#[test]
+fn test_storage_failures_are_typed_persistence_errors() {
+    let local = LocalStoragePersistence::new("claim").into_async();
+    let error = block_on(local.save(&claim())).unwrap_err();
+    assert_eq!(storage_type(&error), "localStorage");
+    assert!(!error.is_quota_exceeded());
+
+    let indexed_db = IndexedDbPersistence::new("claim");
+    let error = block_on(AsyncFormPersistence::<Claim>::load(&indexed_db)).unwrap_err();
+    assert_eq!(storage_type(&error), "IndexedDB");
+}
+
+#[test]
+fn test_full_storage_reports_quota_exceeded() {
+    let storage = MemoryPersistence::new("claim").with_quota(64).into_async();
+    let error = block_on(storage.save(&claim())).unwrap_err();
+    assert!(error.is_persistence_error());
+    assert!(error.is_quota_exceeded());
+    assert_eq!(storage_type(&error), "memory");
+}
+
+#[test]
+fn test_quota_fallback_saves_to_the_smaller_backend() {
+    let primary = MemoryPersistence::new("claim").with_quota(400);
+    let fallback = MemoryPersistence::new("claim");
+    let storage = QuotaFallback::new(primary.clone().into_async(), fallback.clone().into_async());
+    let large = Claim {
+        photos: vec![file("scan.png", &[42; 512])],
+        ..claim()
+    };
+
+    block_on(storage.save(&large)).unwrap();
+    assert_eq!(primary.raw(), None);
+    assert!(fallback.raw().is_some());
+    assert_eq!(block_on(storage.load()).unwrap(), Some(large));
+
+    // Once drafts fit again, the primary backend takes over
+    let small = Claim {
+        title: "Bus".to_string(),
+        receipt: None,
+        photos: Vec::new(),
+    };
+    block_on(storage.save(&small)).unwrap();
+    assert!(primary.raw().is_some());
+    assert_eq!(fallback.raw(), None);
+    assert_eq!(block_on(storage.load()).unwrap(), Some(small));
+}
+
+#[test]
+fn test_other_errors_do_not_fall_back() {
+    let fallback = MemoryPersistence::new("claim");
+    let storage = QuotaFallback::new(
+        LocalStoragePersistence::new("claim").into_async(),
+        fallback.clone().into_async(),
+    );
+
+    assert!(block_on(storage.save(&claim())).is_err());
+    assert_eq!(fallback.raw(), None);
+}
+
+#[test]
+fn test_file_contents_are_kept_in_the_blob_store() {
+    let drafts = MemoryPersistence::new("claim");
+    let blobs = MemoryBlobStore::new();
+    let storage = FileBlobPersistence::new(drafts.clone().into_async(), blobs.clone());
+
+    block_on(storage.save(&claim())).unwrap();
+    assert_eq!(
+        blobs.ids(),
+        vec!["claim/photos[0]", "claim/photos[1]", "claim/receipt"]
+    );
+    let raw = drafts.raw().unwrap();
+    assert!(raw.contains("receipt.png"));
+    assert!(!raw.contains("[1,2,3,4]"));
+
+    assert_eq!(block_on(storage.load()).unwrap(), Some(claim()));
+
+    // Files removed from the form do not linger
+    let fewer = Claim {
+        photos: Vec::new(),
+        ..claim()
+    };
+    block_on(storage.save(&fewer)).unwrap();
+    assert_eq!(blobs.ids(), vec!["claim/receipt"]);
+
+    block_on(AsyncFormPersistence::<Claim>::clear(&storage)).unwrap();
+    assert!(blobs.ids().is_empty());
+    assert_eq!(drafts.raw(), None);
+}
+
+#[test]
+fn test_failed_saves_keep_the_files_of_the_previous_draft() {
+    let drafts = MemoryPersistence::new("claim").with_quota(800);
+    let blobs = MemoryBlobStore::new();
+    let storage = FileBlobPersistence::new(drafts.into_async(), blobs.clone());
+    block_on(storage.save(&claim())).unwrap();
+
+    let too_large = Claim {
+        title: "x".repeat(1000),
+        photos: Vec::new(),
+        ..claim()
+    };
+    assert!(block_on(storage.save(&too_large))
+        .unwrap_err()
+        .is_quota_exceeded());
+    assert_eq!(
+        blobs.ids(),
+        vec!["claim/photos[0]", "claim/photos[1]", "claim/receipt"]
+    );
+    assert_eq!(block_on(storage.load()).unwrap(), Some(claim()));
+}
+
+#[test]
+fn test_files_fit_in_storage_once_moved_to_blobs() {
+    let large = Claim {
+        photos: vec![file("scan.png", &[42; 512])],
+        ..claim()
+    };
+    let drafts = MemoryPersistence::new("claim").with_quota(400);
+    assert!(block_on(drafts.clone().into_async().save(&large))
+        .unwrap_err()
+        .is_quota_exceeded());
+
+    let storage = FileBlobPersistence::new(drafts.into_async(), MemoryBlobStore::new());
+    block_on(storage.save(&large)).unwrap();
+    assert_eq!(block_on(storage.load()).unwrap(), Some(large));
+}
+
+#[test]
+fn test_indexed_db_blob_store_is_unavailable_natively() {
+    let storage = FileBlobPersistence::new(
+        MemoryPersistence::new("claim").into_async(),
+        IndexedDbBlobStore::new(),
+    );
+    let error = block_on(storage.save(&claim())).unwrap_err();
+    assert_eq!(storage_type(&error), "IndexedDB");
+}
@@ -0,0 +1,237 @@
+//! Tests for `AutoForm` layout, field paths and presentation metadata
+
+use leptos::prelude::*;
+use leptos_forms_rs::components::{layout_fields, parse_input_value, FieldOverrides};
+use leptos_forms_rs::core::{
+    parse_field_path, FieldPathSegment, FieldType, FieldValue, FormHandle, NumberType,
+};
+use leptos_forms_rs::{FieldMetadata, Form};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Address {
+    #[form(required, label = "Street address")]
+    street: String,
+    city: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Contact {
+    #[form(email)]
+    email: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct ProfileForm {
+    #[form(group = "Account", order = 2)]
+    username: String,
+    #[form(hidden)]
+    internal_id: String,
+    #[form(group = "Account", order = 1, placeholder = "you@example.com")]
+    email: String,
+    #[form(help_text = "Shown on your public profile")]
+    display_name: String,
+    address: Address,
+    contacts: Vec<Contact>,
+}
+
+fn field(name: &str, attributes: &[(&str, &str)]) -> FieldMetadata {
+    FieldMetadata {
+        name: name.to_string(),
+        attributes: attributes
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_auto_form_component_is_exported() {
+    use leptos_forms_rs::AutoForm;
+
+    // If we get here, the component compiles and can be imported
+    let _ = AutoForm::<ProfileForm>;
+}
+
+#[test]
+fn test_derive_presentation_attributes() {
+    let metadata = ProfileForm::field_metadata();
+
+    let username = &metadata[0];
+    assert_eq!(username.group(), Some("Account"));
+    assert_eq!(username.order(), Some(2));
+    assert!(metadata[1].is_hidden());
+    assert_eq!(
+        metadata[2].attributes.get("placeholder").unwrap(),
+        "you@example.com"
+    );
+    assert_eq!(
+        metadata[3].attributes.get("help_text").unwrap(),
+        "Shown on your public profile"
+    );
+    assert_eq!(metadata[4].nested_fields[0].label(), "Street address");
+}
+
+#[test]
+fn test_field_labels_fall_back_to_humanized_names() {
+    assert_eq!(field("display_name", &[]).label(), "Display name");
+    assert_eq!(field("zip-code", &[]).label(), "Zip code");
+    assert_eq!(field("email", &[("label", "E-mail")]).label(), "E-mail");
+}
+
+#[test]
+fn test_built_metadata_matches_derived_metadata() {
+    let derived = &Address::field_metadata()[0];
+    let built = FieldMetadata::new("street", FieldType::Text)
+        .required()
+        .attribute("label", "Street address");
+    assert_eq!(built.name, derived.name);
+    assert_eq!(built.is_required, derived.is_required);
+    assert_eq!(built.validators, derived.validators);
+    assert_eq!(built.attributes, derived.attributes);
+    assert_eq!(built.label(), "Street address");
+
+    let nested = FieldMetadata::new("address", FieldType::Nested("Address".to_string()))
+        .nested_fields(Address::field_metadata())
+        .sensitive();
+    assert_eq!(nested.nested_fields.len(), 2);
+    assert!(nested.is_sensitive());
+    assert!(!nested.is_required);
+}
+
+#[test]
+fn test_layout_orders_groups_and_hides_fields() {
+    let groups = layout_fields(&ProfileForm::field_metadata());
+    let layout: Vec<(Option<&str>, Vec<&str>)> = groups
+        .iter()
+        .map(|group| {
+            (
+                group.name.as_deref(),
+                group.fields.iter().map(|f| f.name.as_str()).collect(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        layout,
+        vec![
+            (Some("Account"), vec!["email", "username"]),
+            (None, vec!["display_name", "address", "contacts"]),
+        ]
+    );
+}
+
+#[test]
+fn test_layout_gathers_group_members() {
+    let fields = vec![
+        field("first", &[("group", "Name")]),
+        field("phone", &[]),
+        field("last", &[("group", "Name")]),
+        field("secret", &[("hidden", "true")]),
+        field("fax", &[]),
+    ];
+    let groups = layout_fields(&fields);
+
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].name.as_deref(), Some("Name"));
+    assert_eq!(groups[0].fields.len(), 2);
+    assert_eq!(groups[1].fields.len(), 2);
+}
+
+#[test]
+fn test_parse_input_value() {
+    let integer = FieldType::Number(NumberType {
+        min: None,
+        max: None,
+        step: Some(1.0),
+    });
+    let decimal = FieldType::Number(NumberType {
+        min: None,
+        max: None,
+        step: None,
+    });
+
+    assert_eq!(
+        parse_input_value(&integer, "42".to_string()),
+        FieldValue::Integer(42)
+    );
+    assert_eq!(
+        parse_input_value(&decimal, "4.5".to_string()),
+        FieldValue::Number(4.5)
+    );
+    assert_eq!(
+        parse_input_value(&integer, " ".to_string()),
+        FieldValue::Null
+    );
+    assert_eq!(
+        parse_input_value(&FieldType::Date, String::new()),
+        FieldValue::Null
+    );
+    assert_eq!(
+        parse_input_value(&FieldType::Text, "hi".to_string()),
+        FieldValue::String("hi".to_string())
+    );
+}
+
+#[test]
+fn test_parse_field_path() {
+    assert_eq!(
+        parse_field_path("contacts[1].email"),
+        vec![
+            FieldPathSegment::Key("contacts".to_string()),
+            FieldPathSegment::Index(1),
+            FieldPathSegment::Key("email".to_string()),
+        ]
+    );
+    assert_eq!(
+        parse_field_path("matrix[0][2]"),
+        vec![
+            FieldPathSegment::Key("matrix".to_string()),
+            FieldPathSegment::Index(0),
+            FieldPathSegment::Index(2),
+        ]
+    );
+}
+
+#[test]
+fn test_form_handle_path_values() {
+    let mut profile = ProfileForm::default_values();
+    profile.contacts.push(Contact {
+        email: "a@example.com".to_string(),
+    });
+    let handle = FormHandle::new(profile);
+
+    handle.set_field_path_value("address.city", FieldValue::String("Oslo".to_string()));
+    handle.set_field_path_value(
+        "contacts[0].email",
+        FieldValue::String("b@example.com".to_string()),
+    );
+    // Paths past the end of an array are ignored
+    handle.set_field_path_value(
+        "contacts[3].email",
+        FieldValue::String("c@example.com".to_string()),
+    );
+
+    let values = handle.values().get_untracked();
+    assert_eq!(values.address.city, "Oslo");
+    assert_eq!(values.contacts.len(), 1);
+    assert_eq!(values.contacts[0].email, "b@example.com");
+    assert_eq!(
+        handle.get_field_path_value("address.city"),
+        Some(FieldValue::String("Oslo".to_string()))
+    );
+    assert_eq!(handle.get_field_path_value("address.country"), None);
+    assert_eq!(handle.get_field_path_value("unknown"), None);
+}
+
+#[test]
+fn test_field_overrides_keyed_by_path() {
+    let overrides = FieldOverrides::new()
+        .with("address.city", |slot| slot.path)
+        .with("contacts[0]", |_| "custom");
+
+    assert!(overrides.contains("address.city"));
+    assert!(overrides.contains("contacts[0]"));
+    assert!(!overrides.contains("address"));
+}
@@ -0,0 +1,181 @@
+//! Tests for computed fields declared by the derive or registered on `FormHandle`
+
+use leptos::prelude::*;
+use leptos_forms_rs::core::{ComputedField, FieldValue, FormHandle, FormValue};
+use leptos_forms_rs::validation::Validator;
+use leptos_forms_rs::Form;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct LineItem {
+    qty: u32,
+    price: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Order {
+    customer: String,
+    items: Vec<LineItem>,
+    tax_rate: f64,
+    #[form(
+        computed = order_total,
+        depends_on = "items",
+        depends_on = "tax_rate",
+        max = 1000
+    )]
+    total: f64,
+    #[form(computed = item_count, include_in_payload = false)]
+    item_count: u32,
+}
+
+fn order_total(order: &Order) -> FieldValue {
+    let subtotal: f64 = order
+        .items
+        .iter()
+        .map(|item| item.qty as f64 * item.price)
+        .sum();
+    FieldValue::Number(subtotal * (1.0 + order.tax_rate))
+}
+
+fn item_count(order: &Order) -> FieldValue {
+    FieldValue::Integer(order.items.iter().map(|item| item.qty as i64).sum())
+}
+
+fn order() -> Order {
+    Order {
+        customer: "Ada".to_string(),
+        items: vec![LineItem {
+            qty: 2,
+            price: 10.0,
+        }],
+        tax_rate: 0.5,
+        total: 0.0,
+        item_count: 0,
+    }
+}
+
+fn item(qty: u32, price: f64) -> FieldValue {
+    LineItem { qty, price }.to_field_value()
+}
+
+#[test]
+fn test_derive_declares_computed_fields() {
+    let computed = Order::computed_fields();
+    assert_eq!(computed.len(), 2);
+    assert_eq!(computed[0].name(), "total");
+    assert_eq!(computed[0].inputs(), ["items", "tax_rate"]);
+    assert!(computed[0].is_in_payload());
+    assert!(!computed[1].is_in_payload());
+
+    let metadata = Order::field_metadata();
+    let total = metadata.iter().find(|f| f.name == "total").unwrap();
+    assert!(total.is_readonly());
+    assert_eq!(total.dependencies, vec!["items", "tax_rate"]);
+    assert!(!metadata[0].is_readonly());
+}
+
+#[test]
+fn test_handle_computes_on_creation_and_change() {
+    let handle = FormHandle::new(order());
+    let total = handle.computed_value("total");
+    assert_eq!(handle.values().get_untracked().total, 30.0);
+    assert_eq!(total.get_untracked(), FieldValue::Number(30.0));
+
+    handle.add_array_item("items", item(1, 4.0));
+    assert_eq!(handle.values().get_untracked().total, 36.0);
+    assert_eq!(handle.values().get_untracked().item_count, 3);
+
+    handle.set_field_value("tax_rate", FieldValue::Number(0.0));
+    assert_eq!(total.get_untracked(), FieldValue::Number(24.0));
+}
+
+#[test]
+fn test_computed_fields_are_read_only() {
+    let handle = FormHandle::new(order());
+    handle.set_field_value("total", FieldValue::Number(1.0));
+    handle.set_field_path_value("total", FieldValue::Number(1.0));
+    assert_eq!(handle.values().get_untracked().total, 30.0);
+    assert!(handle.is_computed_field("total"));
+    assert!(!handle.is_computed_field("customer"));
+}
+
+#[test]
+fn test_recomputed_only_when_inputs_change() {
+    let handle = FormHandle::new(order());
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counter = runs.clone();
+    let label = handle.add_computed(
+        ComputedField::new("label", move |order: &Order| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            FieldValue::String(order.customer.to_uppercase())
+        })
+        .depends_on(["customer"]),
+    );
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(label.get_untracked(), FieldValue::String("ADA".to_string()));
+
+    handle.set_field_value("tax_rate", FieldValue::Number(0.2));
+    handle.add_array_item("items", item(1, 1.0));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    handle.set_field_value("customer", FieldValue::String("Grace".to_string()));
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        label.get_untracked(),
+        FieldValue::String("GRACE".to_string())
+    );
+
+    // Setting the same value again does not recompute
+    handle.set_field_value("customer", FieldValue::String("Grace".to_string()));
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_computed_fields_are_validated() {
+    let mut expensive = order();
+    expensive.items[0].price = 1000.0;
+    let handle = FormHandle::new(expensive);
+    assert!(handle.validate().is_err());
+    assert!(handle.errors().get_untracked().has_field_error("total"));
+
+    let handle = FormHandle::new(order());
+    handle.add_computed(
+        ComputedField::new("discount", |order: &Order| {
+            FieldValue::Number(if order.customer == "Ada" { -5.0 } else { 0.0 })
+        })
+        .validate_with(Validator::Min(0.0)),
+    );
+    assert!(handle.validate().is_err());
+    assert!(handle.errors().get_untracked().has_field_error("discount"));
+
+    handle.set_field_value("customer", FieldValue::String("Grace".to_string()));
+    assert!(handle.validate().is_ok());
+}
+
+#[test]
+fn test_payload_inclusion_is_configurable() {
+    let handle = FormHandle::new(order());
+    handle.computed("shipping", |_: &Order| FieldValue::Number(4.5));
+    handle.add_computed(
+        ComputedField::new("internal_score", |_: &Order| FieldValue::Integer(7))
+            .include_in_payload(false),
+    );
+
+    let data = handle.submission_data();
+    assert_eq!(data.get("total"), Some(&FieldValue::Number(30.0)));
+    assert_eq!(data.get("shipping"), Some(&FieldValue::Number(4.5)));
+    assert!(!data.contains_key("item_count"));
+    assert!(!data.contains_key("internal_score"));
+}
+
+#[test]
+fn test_reset_recomputes() {
+    let handle = FormHandle::new(order());
+    handle.reset();
+    let values = handle.values().get_untracked();
+    assert!(values.items.is_empty());
+    assert_eq!(values.total, 0.0);
+    assert_eq!(values.item_count, 0);
+}
@@ -24,6 +24,7 @@ impl Form for TestForm {
                 default_value: Some(FieldValue::String("personal".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "company_name".to_string(),
@@ -33,6 +34,7 @@ impl Form for TestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec!["account_type".to_string()],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "tax_id".to_string(),
@@ -42,6 +44,7 @@ impl Form for TestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec!["account_type".to_string()],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "personal_id".to_string(),
@@ -51,6 +54,7 @@ impl Form for TestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec!["account_type".to_string()],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -36,6 +36,7 @@ impl Form for ConditionalForm {
                 default_value: Some(FieldValue::String("personal".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "company_name".to_string(),
@@ -45,6 +46,7 @@ impl Form for ConditionalForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec!["account_type".to_string()],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "tax_id".to_string(),
@@ -54,6 +56,7 @@ impl Form for ConditionalForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec!["account_type".to_string()],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "personal_id".to_string(),
@@ -63,6 +66,7 @@ impl Form for ConditionalForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec!["account_type".to_string()],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "email".to_string(),
@@ -72,6 +76,7 @@ impl Form for ConditionalForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -0,0 +1,203 @@
+//! Tests for keeping drafts in sync between tabs
+
+use any_spawner::Executor;
+use leptos::prelude::*;
+use leptos_forms_rs::core::{
+    merge_remote_draft, CrossTabMode, DraftConflict, DraftSyncChannel, FieldValue, FormHandle,
+    FormPersistence, MemoryPersistence, MemorySyncChannel, PersistenceOptions,
+};
+use leptos_forms_rs::hooks::{use_form_drafts, FormDrafts};
+use leptos_forms_rs::Form;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Record {
+    title: String,
+    notes: String,
+    #[form(sensitive)]
+    secret: String,
+}
+
+fn record() -> Record {
+    Record {
+        title: "Draft".to_string(),
+        notes: String::new(),
+        secret: String::new(),
+    }
+}
+
+fn options(mode: CrossTabMode) -> PersistenceOptions {
+    PersistenceOptions {
+        enabled: true,
+        cross_tab_sync: Some(mode),
+        ..Default::default()
+    }
+}
+
+/// Two tabs sharing storage, each with its own form
+fn two_tabs(mode: CrossTabMode) -> [(FormHandle<Record>, FormDrafts); 2] {
+    let _ = Executor::init_futures_executor();
+    let channel = MemorySyncChannel::new();
+    let storage = MemoryPersistence::new("record");
+    let tabs = [channel.tab(), channel.tab()].map(|tab| {
+        let form = FormHandle::new(record());
+        let drafts = use_form_drafts(&form, storage.clone().with_sync_channel(tab), options(mode));
+        (form, drafts)
+    });
+    Executor::poll_local();
+    tabs
+}
+
+fn edit(form: &FormHandle<Record>, field: &str, value: &str) {
+    form.set_field_value(field, FieldValue::String(value.to_string()));
+}
+
+#[test]
+fn test_memory_channel_delivers_to_other_tabs_only() {
+    let channel = MemorySyncChannel::new();
+    let (first, second) = (channel.tab(), channel.tab());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    for (tab, name) in [(&first, "first"), (&second, "second")] {
+        let received = received.clone();
+        tab.subscribe(
+            "record",
+            Arc::new(move |draft| received.lock().unwrap().push((name, draft))),
+        )
+        .unwrap();
+    }
+
+    first.publish("record", Some("{}"));
+    first.publish("other", Some("{}"));
+    second.publish("record", None);
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![("second", Some("{}".to_string())), ("first", None)]
+    );
+}
+
+#[test]
+fn test_persistence_without_channel_cannot_watch() {
+    let storage = MemoryPersistence::new("record");
+    assert!(!FormPersistence::<Record>::watch(
+        &storage,
+        Arc::new(|_| {})
+    ));
+
+    let synced = storage.with_sync_channel(MemorySyncChannel::new());
+    assert!(FormPersistence::<Record>::watch(&synced, Arc::new(|_| {})));
+}
+
+#[test]
+fn test_live_merge_applies_remote_edits_to_untouched_fields() {
+    let [(first, first_drafts), (second, second_drafts)] = two_tabs(CrossTabMode::LiveMerge);
+
+    edit(&first, "title", "Quarterly report");
+    first_drafts.save.run(());
+
+    let values = second.values().get_untracked();
+    assert_eq!(values.title, "Quarterly report");
+    assert_eq!(second_drafts.conflict.get_untracked(), None);
+
+    // Edits in different fields meet without conflict
+    edit(&second, "notes", "Check figures");
+    second_drafts.save.run(());
+    assert_eq!(
+        first.values().get_untracked(),
+        Record {
+            title: "Quarterly report".to_string(),
+            notes: "Check figures".to_string(),
+            secret: String::new(),
+        }
+    );
+    assert_eq!(first_drafts.conflict.get_untracked(), None);
+}
+
+#[test]
+fn test_live_merge_reports_fields_edited_in_both_tabs() {
+    let [(first, first_drafts), (second, second_drafts)] = two_tabs(CrossTabMode::LiveMerge);
+
+    edit(&second, "title", "Mine");
+    edit(&first, "title", "Theirs");
+    edit(&first, "notes", "Shared");
+    first_drafts.save.run(());
+
+    let values = second.values().get_untracked();
+    assert_eq!(values.title, "Mine");
+    assert_eq!(values.notes, "Shared");
+    assert_eq!(
+        second_drafts.conflict.get_untracked(),
+        Some(DraftConflict {
+            fields: vec!["title".to_string()],
+        })
+    );
+
+    second_drafts.accept_remote.run(());
+    assert_eq!(second.values().get_untracked().title, "Theirs");
+    assert_eq!(second_drafts.conflict.get_untracked(), None);
+}
+
+#[test]
+fn test_keep_local_saves_over_the_other_tab() {
+    let [(first, first_drafts), (second, second_drafts)] = two_tabs(CrossTabMode::LiveMerge);
+
+    edit(&second, "title", "Mine");
+    edit(&first, "title", "Theirs");
+    first_drafts.save.run(());
+    assert!(second_drafts.conflict.get_untracked().is_some());
+
+    second_drafts.keep_local.run(());
+    assert_eq!(second_drafts.conflict.get_untracked(), None);
+    // The first tab has not edited the title since its save
+    assert_eq!(first.values().get_untracked().title, "Mine");
+    assert_eq!(first_drafts.conflict.get_untracked(), None);
+}
+
+#[test]
+fn test_conflict_mode_reports_differences_without_applying() {
+    let [(first, first_drafts), (second, second_drafts)] = two_tabs(CrossTabMode::Conflict);
+
+    edit(&first, "title", "Quarterly report");
+    edit(&first, "notes", "Check figures");
+    first_drafts.save.run(());
+
+    assert_eq!(second.values().get_untracked(), record());
+    assert_eq!(
+        second_drafts.conflict.get_untracked(),
+        Some(DraftConflict {
+            fields: vec!["notes".to_string(), "title".to_string()],
+        })
+    );
+}
+
+#[test]
+fn test_cleared_draft_leaves_other_tab_alone() {
+    let [(first, first_drafts), (second, second_drafts)] = two_tabs(CrossTabMode::LiveMerge);
+
+    edit(&first, "title", "Submitted");
+    first_drafts.save.run(());
+    first_drafts.clear.run(());
+
+    assert_eq!(second.values().get_untracked().title, "Submitted");
+    assert_eq!(second_drafts.conflict.get_untracked(), None);
+}
+
+#[test]
+fn test_merge_keeps_sensitive_fields_local() {
+    let base = record();
+    let local = Record {
+        secret: "hunter2".to_string(),
+        ..record()
+    };
+    let remote = Record {
+        title: "Renamed".to_string(),
+        ..record()
+    };
+
+    let merge = merge_remote_draft(&base, &local, &remote);
+    assert_eq!(merge.applied, vec!["title".to_string()]);
+    assert!(merge.conflicts.is_empty());
+    assert_eq!(merge.values.title, "Renamed");
+    assert_eq!(merge.values.secret, "hunter2");
+}
@@ -27,6 +27,7 @@ impl Form for DevToolsTestForm {
                 default_value: Some(FieldValue::String(String::new())),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -36,6 +37,7 @@ impl Form for DevToolsTestForm {
                 default_value: Some(FieldValue::String(String::new())),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "age".to_string(),
@@ -49,6 +51,7 @@ impl Form for DevToolsTestForm {
                 default_value: Some(FieldValue::Integer(0)),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "tags".to_string(),
@@ -58,6 +61,7 @@ impl Form for DevToolsTestForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "is_active".to_string(),
@@ -67,6 +71,7 @@ impl Form for DevToolsTestForm {
                 default_value: Some(FieldValue::Boolean(false)),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -0,0 +1,156 @@
+//! Tests for draft timestamps, expiry and the draft manager
+
+use any_spawner::Executor;
+use leptos::prelude::*;
+use leptos_forms_rs::core::{
+    DraftLoad, DraftManager, FormPersistence, MemoryPersistence, PersistenceOptions,
+};
+use leptos_forms_rs::hooks::use_form_drafts;
+use leptos_forms_rs::{Form, FormHandle};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Application {
+    name: String,
+    email: String,
+    years: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Feedback {
+    comment: String,
+}
+
+fn application(name: &str) -> Application {
+    Application {
+        name: name.to_string(),
+        email: format!("{}@example.com", name.to_lowercase()),
+        years: 3,
+    }
+}
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[test]
+fn test_drafts_record_when_they_were_created_and_edited() {
+    let storage = MemoryPersistence::new("application:1").with_ttl(DAY);
+    let manager = DraftManager::memory(&storage, "application:");
+
+    storage.save(&application("Ada")).unwrap();
+    let first = manager.list().unwrap().remove(0);
+    let created_at = first.created_at.expect("creation time");
+    assert_eq!(first.updated_at, Some(created_at));
+    let expires_at = first.expires_at.expect("expiry time");
+    assert_eq!((expires_at - created_at).num_seconds(), 24 * 60 * 60);
+
+    storage.save(&application("Grace")).unwrap();
+    let second = manager.list().unwrap().remove(0);
+    assert_eq!(second.created_at, Some(created_at));
+    assert!(second.updated_at.unwrap() >= created_at);
+    assert!(second.expires_at.unwrap() >= expires_at);
+}
+
+#[test]
+fn test_expired_drafts_are_purged_on_load() {
+    let storage = MemoryPersistence::new("application:1").with_ttl(Duration::ZERO);
+    storage.save(&application("Ada")).unwrap();
+
+    let draft = FormPersistence::<Application>::load_draft(&storage).unwrap();
+    assert!(matches!(draft, DraftLoad::Discarded { .. }));
+    assert_eq!(storage.raw(), None);
+}
+
+#[test]
+fn test_expired_drafts_are_not_restored_by_the_hook() {
+    let _ = Executor::init_futures_executor();
+    let storage = MemoryPersistence::new("application:1").with_ttl(Duration::ZERO);
+    storage.save(&application("Ada")).unwrap();
+    let options = PersistenceOptions {
+        enabled: true,
+        ..Default::default()
+    };
+
+    let form = FormHandle::new(application("Blank"));
+    use_form_drafts(&form, storage.clone(), options);
+    Executor::poll_local();
+    assert_eq!(form.values().get_untracked(), application("Blank"));
+    assert_eq!(storage.raw(), None);
+}
+
+#[test]
+fn test_manager_lists_drafts_under_prefix_with_metadata() {
+    let storage = MemoryPersistence::new("application:1");
+    storage.save(&application("Ada")).unwrap();
+    let second = storage.with_key("application:2");
+    second.save(&application("Grace")).unwrap();
+    storage
+        .with_key("feedback:1")
+        .save(&Feedback {
+            comment: "Great".to_string(),
+        })
+        .unwrap();
+    storage
+        .with_key("application:settings")
+        .set_raw("not a draft");
+
+    let drafts = DraftManager::memory(&storage, "application:")
+        .list()
+        .unwrap();
+    let mut keys: Vec<&str> = drafts.iter().map(|draft| draft.key.as_str()).collect();
+    keys.sort();
+    assert_eq!(keys, vec!["application:1", "application:2"]);
+    assert!(drafts[0].updated_at >= drafts[1].updated_at);
+    for draft in &drafts {
+        assert_eq!(draft.form_name.as_deref(), Some("Application"));
+        assert_eq!(draft.field_count, 3);
+        assert_eq!(draft.schema_version, 0);
+    }
+}
+
+#[test]
+fn test_manager_purges_expired_drafts() {
+    let storage = MemoryPersistence::new("application:1");
+    storage.save(&application("Ada")).unwrap();
+    let expired = storage.with_key("application:2").with_ttl(Duration::ZERO);
+    expired.save(&application("Grace")).unwrap();
+    let manager = DraftManager::memory(&storage, "application:");
+
+    assert_eq!(manager.purge_expired().unwrap(), vec!["application:2"]);
+    assert_eq!(expired.raw(), None);
+    assert_eq!(manager.list().unwrap().len(), 1);
+
+    expired.save(&application("Grace")).unwrap();
+    let keys: Vec<String> = manager.list().unwrap().into_iter().map(|d| d.key).collect();
+    assert_eq!(keys, vec!["application:1"]);
+    assert_eq!(expired.raw(), None);
+}
+
+#[test]
+fn test_manager_restores_and_deletes_a_draft() {
+    let storage = MemoryPersistence::new("application:1");
+    storage.save(&application("Ada")).unwrap();
+    storage
+        .with_key("application:2")
+        .save(&application("Grace"))
+        .unwrap();
+    let manager = DraftManager::memory(&storage, "application:");
+
+    let restored = manager.restore::<Application>("application:2").unwrap();
+    assert_eq!(restored, DraftLoad::Restored(application("Grace")));
+
+    manager.delete("application:2").unwrap();
+    assert_eq!(
+        manager.restore::<Application>("application:2").unwrap(),
+        DraftLoad::Empty
+    );
+    assert_eq!(manager.list().unwrap().len(), 1);
+}
+
+#[test]
+fn test_web_storage_manager_fails_outside_the_browser() {
+    assert!(DraftManager::local_storage("application:").list().is_err());
+    assert!(DraftManager::session_storage("application:")
+        .purge_expired()
+        .is_err());
+}
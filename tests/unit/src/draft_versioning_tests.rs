@@ -0,0 +1,285 @@
+//! Tests for versioned drafts, their migrations and fallback policies
+
+use any_spawner::Executor;
+use leptos::prelude::*;
+use leptos_forms_rs::core::{
+    DraftFallback, DraftLoad, DraftVersioning, FieldType, FormHandle, FormPersistence,
+    MemoryPersistence, PersistenceOptions,
+};
+use leptos_forms_rs::hooks::use_form_drafts;
+use leptos_forms_rs::{FieldMetadata, Form, FormSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Signup {
+    first_name: String,
+    last_name: String,
+    age: u32,
+}
+
+fn ada() -> Signup {
+    Signup {
+        first_name: "Ada".to_string(),
+        last_name: "Lovelace".to_string(),
+        age: 36,
+    }
+}
+
+/// A version 1 draft, saved before `name` was split in two
+fn version_one_draft() -> String {
+    json!({
+        "schema_version": 1,
+        "fingerprint": "0000000000000000",
+        "values": { "name": "Ada Lovelace", "age": 36 }
+    })
+    .to_string()
+}
+
+fn split_name(mut draft: Value) -> Result<Value, String> {
+    let name = draft["name"].as_str().ok_or("missing name")?.to_string();
+    let (first, last) = name.split_once(' ').unwrap_or((&name, ""));
+    draft["first_name"] = first.into();
+    draft["last_name"] = last.into();
+    draft.as_object_mut().unwrap().remove("name");
+    Ok(draft)
+}
+
+fn field(name: &str, field_type: FieldType) -> FieldMetadata {
+    FieldMetadata {
+        name: name.to_string(),
+        field_type,
+        ..Default::default()
+    }
+}
+
+fn schema(fields: Vec<FieldMetadata>) -> FormSchema {
+    FormSchema {
+        name: "Signup".to_string(),
+        field_metadata: fields,
+    }
+}
+
+#[test]
+fn test_fingerprint_follows_the_shape_of_the_schema() {
+    let text = || FieldType::Text;
+    let base = schema(vec![
+        field("name", text()),
+        field("agree", FieldType::Boolean),
+    ]);
+    let fingerprint = base.fingerprint();
+    assert_eq!(fingerprint.len(), 16);
+    assert_eq!(
+        Signup::schema().fingerprint(),
+        Signup::schema().fingerprint()
+    );
+
+    // Order, labels and string flavours do not change what is saved
+    let mut labelled = field("name", FieldType::Email);
+    labelled
+        .attributes
+        .insert("label".to_string(), "Full name".to_string());
+    let reordered = schema(vec![field("agree", FieldType::Boolean), labelled]);
+    assert_eq!(reordered.fingerprint(), fingerprint);
+
+    let renamed = schema(vec![
+        field("full_name", text()),
+        field("agree", FieldType::Boolean),
+    ]);
+    assert_ne!(renamed.fingerprint(), fingerprint);
+    let retyped = schema(vec![field("name", text()), field("agree", text())]);
+    assert_ne!(retyped.fingerprint(), fingerprint);
+}
+
+#[test]
+fn test_drafts_are_saved_with_version_and_fingerprint() {
+    let storage = MemoryPersistence::new("signup").with_versioning(DraftVersioning::new(2));
+    storage.save(&ada()).unwrap();
+
+    let saved: Value = serde_json::from_str(&storage.raw().unwrap()).unwrap();
+    assert_eq!(saved["schema_version"], 2);
+    assert_eq!(saved["fingerprint"], Signup::schema().fingerprint());
+    assert_eq!(saved["values"]["first_name"], "Ada");
+    assert_eq!(storage.load_draft(), Ok(DraftLoad::Restored(ada())));
+}
+
+#[test]
+fn test_unversioned_drafts_load_as_version_zero() {
+    let storage = MemoryPersistence::new("signup");
+    storage.set_raw(serde_json::to_string(&ada()).unwrap());
+    assert_eq!(storage.load(), Ok(Some(ada())));
+
+    let upgraded = storage
+        .with_key("signup")
+        .with_versioning(DraftVersioning::new(1).migrate(0, |mut draft| {
+            draft["age"] = json!(draft["age"].as_u64().unwrap_or_default() + 1);
+            Ok(draft)
+        }));
+    let mut older = ada();
+    older.age = 37;
+    assert_eq!(
+        upgraded.load_draft(),
+        Ok(DraftLoad::Migrated {
+            values: older,
+            from_version: 0
+        })
+    );
+}
+
+#[test]
+fn test_old_drafts_are_migrated() {
+    let storage = MemoryPersistence::new("signup")
+        .with_versioning(DraftVersioning::new(2).migrate(1, split_name));
+    storage.set_raw(version_one_draft());
+
+    assert_eq!(
+        storage.load_draft(),
+        Ok(DraftLoad::Migrated {
+            values: ada(),
+            from_version: 1
+        })
+    );
+}
+
+#[test]
+fn test_discard_fallback_removes_the_draft() {
+    let storage = MemoryPersistence::new("signup").with_versioning(DraftVersioning::new(2));
+    storage.set_raw(version_one_draft());
+
+    let draft: Result<DraftLoad<Signup>, String> = storage.load_draft();
+    assert!(matches!(draft, Ok(DraftLoad::Discarded { .. })));
+    assert!(storage.raw().is_none());
+
+    // Drafts of a newer version than the form cannot be downgraded
+    let newer = MemoryPersistence::new("signup").with_versioning(DraftVersioning::new(0));
+    newer.set_raw(version_one_draft());
+    assert_eq!(FormPersistence::<Signup>::load(&newer), Ok(None));
+}
+
+#[test]
+fn test_partial_fallback_restores_matching_fields() {
+    let storage = MemoryPersistence::new("signup")
+        .with_versioning(DraftVersioning::new(2).fallback(DraftFallback::Partial));
+    storage.set_raw(version_one_draft());
+
+    assert_eq!(
+        storage.load_draft(),
+        Ok(DraftLoad::Partial {
+            values: Signup {
+                first_name: String::new(),
+                last_name: String::new(),
+                age: 36,
+            },
+            skipped: vec!["name".to_string()],
+        })
+    );
+
+    // Fields whose values no longer fit keep their defaults too
+    storage.set_raw(
+        json!({
+            "schema_version": 2,
+            "fingerprint": "",
+            "values": { "first_name": "Ada", "age": "thirty-six" }
+        })
+        .to_string(),
+    );
+    let draft: Result<DraftLoad<Signup>, String> = storage.load_draft();
+    let Ok(DraftLoad::Partial { values, skipped }) = draft else {
+        panic!("expected a partial restore");
+    };
+    assert_eq!(values.first_name, "Ada");
+    assert_eq!(values.age, 0);
+    assert_eq!(skipped, vec!["age"]);
+
+    // As do values of the right type the form still rejects
+    storage.set_raw(
+        json!({
+            "schema_version": 2,
+            "fingerprint": "",
+            "values": { "first_name": "Ada", "last_name": 7, "age": -1 }
+        })
+        .to_string(),
+    );
+    let draft: Result<DraftLoad<Signup>, String> = storage.load_draft();
+    let Ok(DraftLoad::Partial { values, skipped }) = draft else {
+        panic!("expected a partial restore");
+    };
+    assert_eq!(values.first_name, "Ada");
+    assert_eq!(skipped, vec!["age", "last_name"]);
+}
+
+#[test]
+fn test_drafts_of_a_changed_form_without_a_new_version_do_not_fit() {
+    // Saved at the current version by a form that had a `nickname` field
+    let draft = json!({
+        "schema_version": 2,
+        "fingerprint": "0123456789abcdef",
+        "values": { "first_name": "Ada", "last_name": "Lovelace", "age": 36, "nickname": "A" }
+    })
+    .to_string();
+
+    let discarding = MemoryPersistence::new("signup").with_versioning(DraftVersioning::new(2));
+    discarding.set_raw(draft.clone());
+    let loaded: Result<DraftLoad<Signup>, String> = discarding.load_draft();
+    assert!(matches!(loaded, Ok(DraftLoad::Discarded { .. })));
+
+    let partial = MemoryPersistence::new("signup")
+        .with_versioning(DraftVersioning::new(2).fallback(DraftFallback::Partial));
+    partial.set_raw(draft);
+    assert_eq!(
+        partial.load_draft(),
+        Ok(DraftLoad::Partial {
+            values: ada(),
+            skipped: vec!["nickname".to_string()],
+        })
+    );
+}
+
+#[test]
+fn test_changed_forms_keep_their_drafts_without_versioning() {
+    // Saved by an earlier build of the form, before versioning was set up
+    let storage = MemoryPersistence::new("signup");
+    storage.set_raw(
+        json!({
+            "schema_version": 0,
+            "fingerprint": "0123456789abcdef",
+            "values": { "first_name": "Ada", "last_name": "Lovelace", "age": 36 }
+        })
+        .to_string(),
+    );
+    assert_eq!(storage.load_draft(), Ok(DraftLoad::Restored(ada())));
+}
+
+#[test]
+fn test_prompt_fallback_surfaces_the_stale_draft() {
+    let _ = Executor::init_futures_executor();
+    let storage = MemoryPersistence::new("signup")
+        .with_versioning(DraftVersioning::new(2).fallback(DraftFallback::Prompt));
+    storage.set_raw(version_one_draft());
+    let options = PersistenceOptions {
+        enabled: true,
+        ..Default::default()
+    };
+
+    let form = FormHandle::new(Signup::default_values());
+    let drafts = use_form_drafts(&form, storage.clone(), options.clone());
+    Executor::poll_local();
+    let stale = drafts.stale.get_untracked().expect("stale draft");
+    assert_eq!(stale.version, 1);
+    assert_eq!(stale.values["name"], "Ada Lovelace");
+    assert!(!stale.reason.is_empty());
+    assert_eq!(form.values().get_untracked(), Signup::default_values());
+    assert!(storage.raw().is_some());
+
+    drafts.restore_partial.run(());
+    assert_eq!(form.values().get_untracked().age, 36);
+    assert!(drafts.stale.get_untracked().is_none());
+
+    let form = FormHandle::new(Signup::default_values());
+    let drafts = use_form_drafts(&form, storage.clone(), options);
+    Executor::poll_local();
+    assert!(drafts.stale.get_untracked().is_some());
+    drafts.discard.run(());
+    assert!(drafts.stale.get_untracked().is_none());
+    assert!(storage.raw().is_none());
+}
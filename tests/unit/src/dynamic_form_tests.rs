@@ -0,0 +1,207 @@
+//! Tests for runtime forms built from a `FormSchema`
+
+use leptos::prelude::Owner;
+use leptos_forms_rs::core::{FieldType, FieldValue, FormHandle};
+use leptos_forms_rs::{DynamicForm, Form, FormSchema};
+use serde_json::json;
+use std::collections::HashMap;
+
+struct BillingSlot;
+
+/// Run `test` in a reactive scope of its own, like a component
+fn in_scope<R>(test: impl FnOnce() -> R) -> R {
+    Owner::new().with(test)
+}
+
+fn schema_named(name: &str) -> FormSchema {
+    serde_json::from_value(json!({ "name": name, "field_metadata": [] })).unwrap()
+}
+
+fn profile_schema() -> FormSchema {
+    serde_json::from_value(json!({
+        "name": "Profile",
+        "field_metadata": [
+            {
+                "name": "username",
+                "field_type": "Text",
+                "is_required": true,
+                "default_value": null,
+                "dependencies": [],
+                "attributes": {},
+                "validators": ["Required", { "MinLength": 3 }]
+            },
+            {
+                "name": "email",
+                "field_type": "Email",
+                "is_required": true,
+                "default_value": null,
+                "dependencies": [],
+                "attributes": {},
+                "validators": ["Required", "Email"]
+            },
+            {
+                "name": "newsletter",
+                "field_type": "Boolean",
+                "is_required": false,
+                "default_value": true,
+                "dependencies": [],
+                "attributes": {},
+                "validators": []
+            },
+            {
+                "name": "address",
+                "field_type": { "Nested": "Address" },
+                "is_required": false,
+                "default_value": null,
+                "dependencies": [],
+                "attributes": {},
+                "validators": [],
+                "nested_fields": [
+                    {
+                        "name": "city",
+                        "field_type": "Text",
+                        "is_required": true,
+                        "default_value": null,
+                        "dependencies": [],
+                        "attributes": {},
+                        "validators": ["Required"]
+                    }
+                ]
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_dynamic_form_defaults_from_schema() {
+    in_scope(|| {
+        let form = DynamicForm::<()>::from_schema(profile_schema());
+
+        assert_eq!(DynamicForm::<()>::schema().name, "Profile");
+        assert_eq!(DynamicForm::<()>::field_metadata().len(), 4);
+        assert_eq!(
+            form.get_field_value("username"),
+            FieldValue::String(String::new())
+        );
+        assert_eq!(
+            form.get_field_value("newsletter"),
+            FieldValue::Boolean(true)
+        );
+
+        let mut address = HashMap::new();
+        address.insert("city".to_string(), FieldValue::String(String::new()));
+        assert_eq!(form.get_field_value("address"), FieldValue::Object(address));
+    });
+}
+
+#[test]
+fn test_dynamic_form_validation() {
+    // The form carries its schema, so no scope is needed
+    let mut form = DynamicForm::<()>::from_schema(profile_schema());
+    form.set_field_value("username", FieldValue::String("ab".to_string()));
+    form.set_field_value("email", FieldValue::String("not-an-email".to_string()));
+
+    let errors = form.validate().unwrap_err();
+    assert!(errors.has_field_error("username"));
+    assert!(errors.has_field_error("email"));
+    assert!(errors.has_field_error("address.city"));
+
+    let mut address = HashMap::new();
+    address.insert("city".to_string(), FieldValue::String("Paris".to_string()));
+    form.set_field_value("username", FieldValue::String("alice".to_string()));
+    form.set_field_value("email", FieldValue::String("alice@example.com".to_string()));
+    form.set_field_value("address", FieldValue::Object(address));
+    assert!(form.validate().is_ok());
+}
+
+#[test]
+fn test_dynamic_form_ignores_unknown_fields() {
+    let mut form = DynamicForm::<()>::from_schema(profile_schema());
+    form.set_field_value("is_admin", FieldValue::Boolean(true));
+
+    assert_eq!(form.get_field_value("is_admin"), FieldValue::Null);
+    assert!(!form.values().contains_key("is_admin"));
+}
+
+#[test]
+fn test_dynamic_form_with_form_handle() {
+    in_scope(|| {
+        let handle = FormHandle::new(DynamicForm::<()>::from_schema(profile_schema()));
+
+        handle.set_field_value("username", FieldValue::String("bob".to_string()));
+        assert_eq!(
+            handle.get_field_value("username"),
+            Some(FieldValue::String("bob".to_string()))
+        );
+        assert!(handle.is_field_required("email"));
+        assert!(matches!(
+            handle.get_field_type("address"),
+            Some(FieldType::Nested(_))
+        ));
+        assert!(handle.validate().is_err());
+    });
+}
+
+#[test]
+fn test_dynamic_form_serializes_as_plain_object() {
+    in_scope(|| {
+        let mut form = DynamicForm::<()>::from_schema(profile_schema());
+        form.set_field_value("username", FieldValue::String("carol".to_string()));
+
+        let json = serde_json::to_value(&form).unwrap();
+        assert_eq!(json["username"], json!("carol"));
+        assert_eq!(json["newsletter"], json!(true));
+        assert_eq!(json["address"]["city"], json!(""));
+
+        // Forms read back use the schema in scope
+        let mut restored: DynamicForm = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, form);
+        restored.set_field_value("email", FieldValue::String("c@example.com".to_string()));
+        assert!(restored.values().contains_key("email"));
+
+        let mut values = HashMap::new();
+        values.insert(
+            "email".to_string(),
+            FieldValue::String("c@example.com".to_string()),
+        );
+        values.insert("unknown".to_string(), FieldValue::Integer(1));
+        let from_values = DynamicForm::<()>::from_values(values);
+        assert_eq!(
+            from_values.get_field_value("email"),
+            FieldValue::String("c@example.com".to_string())
+        );
+        assert!(!from_values.values().contains_key("unknown"));
+    });
+}
+
+#[test]
+fn test_dynamic_form_without_schema() {
+    let form = DynamicForm::<()>::default_values();
+    assert!(form.values().is_empty());
+    assert!(DynamicForm::<()>::schema_in_scope().is_none());
+    assert_eq!(DynamicForm::<()>::schema().name, "DynamicForm");
+    assert!(form.validate().is_ok());
+}
+
+#[test]
+fn test_schemas_stay_within_their_scope() {
+    // Like two requests rendered side by side
+    let (first, second) = (Owner::new(), Owner::new());
+    first.with(|| DynamicForm::<()>::provide_schema(schema_named("Quote")));
+    second.with(|| DynamicForm::<()>::provide_schema(schema_named("Claim")));
+
+    assert_eq!(first.with(DynamicForm::<()>::schema).name, "Quote");
+    assert_eq!(second.with(DynamicForm::<()>::schema).name, "Claim");
+    assert!(DynamicForm::<()>::schema_in_scope().is_none());
+
+    // Children see the schema of their parent, and slots keep theirs apart
+    first.with(|| {
+        DynamicForm::<BillingSlot>::provide_schema(schema_named("Billing"));
+        let child = Owner::new();
+        child.with(|| {
+            assert_eq!(DynamicForm::<()>::schema().name, "Quote");
+            assert_eq!(DynamicForm::<BillingSlot>::schema().name, "Billing");
+        });
+    });
+}
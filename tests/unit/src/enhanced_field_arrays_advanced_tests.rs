@@ -31,6 +31,7 @@ impl Form for AdvancedFieldArrayForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "tags".to_string(),
@@ -40,6 +41,7 @@ impl Form for AdvancedFieldArrayForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -39,6 +39,7 @@ impl Form for EnhancedFieldArrayForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "items".to_string(),
@@ -48,6 +49,7 @@ impl Form for EnhancedFieldArrayForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "nested_arrays".to_string(),
@@ -57,6 +59,7 @@ impl Form for EnhancedFieldArrayForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "contacts".to_string(),
@@ -66,6 +69,7 @@ impl Form for EnhancedFieldArrayForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -0,0 +1,248 @@
+//! Tests for `visible_when` / `enabled_when` rules on field metadata
+
+use leptos::prelude::*;
+use leptos_forms_rs::core::{
+    hidden_field_paths, is_field_enabled, is_field_visible, is_within_path, visible_form_data,
+    FieldValue, FormHandle, HiddenFieldPolicy,
+};
+use leptos_forms_rs::validation::FieldCondition;
+use leptos_forms_rs::{validate_values, FieldMetadata, Form, FormSchema, ValidationErrors};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Parcel {
+    #[form(required)]
+    kind: String,
+    #[form(required, visible_when = fragile_notes_visible)]
+    handling_notes: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Checkout {
+    #[form(required)]
+    name: String,
+    ship_elsewhere: bool,
+    #[form(required, visible_when = shipping_visible)]
+    shipping_street: String,
+    membership: String,
+    #[form(enabled_when = discount_enabled)]
+    discount_code: String,
+    parcels: Vec<Parcel>,
+}
+
+fn shipping_visible() -> FieldCondition {
+    FieldCondition::equals("ship_elsewhere", FieldValue::Boolean(true))
+}
+
+fn discount_enabled() -> FieldCondition {
+    FieldCondition::equals("membership", FieldValue::String("gold".to_string()))
+}
+
+fn fragile_notes_visible() -> FieldCondition {
+    FieldCondition::equals("kind", FieldValue::String("fragile".to_string()))
+}
+
+fn checkout() -> Checkout {
+    Checkout {
+        name: "Ada".to_string(),
+        ship_elsewhere: false,
+        shipping_street: String::new(),
+        membership: String::new(),
+        discount_code: String::new(),
+        parcels: Vec::new(),
+    }
+}
+
+#[test]
+fn test_derive_sets_conditions_on_metadata() {
+    let metadata = Checkout::field_metadata();
+    let field = |name: &str| metadata.iter().find(|f| f.name == name).unwrap();
+
+    assert_eq!(
+        field("shipping_street").visible_when,
+        Some(shipping_visible())
+    );
+    assert_eq!(
+        field("discount_code").enabled_when,
+        Some(discount_enabled())
+    );
+    assert_eq!(field("name").visible_when, None);
+}
+
+#[test]
+fn test_hidden_fields_are_not_validated() {
+    let mut form = checkout();
+    assert!(form.validate().is_ok());
+
+    form.ship_elsewhere = true;
+    let errors = form.validate().unwrap_err();
+    assert!(errors.has_field_error("shipping_street"));
+
+    // Rules on nested fields are evaluated against their own item
+    let mut form = checkout();
+    form.parcels.push(Parcel {
+        kind: "books".to_string(),
+        handling_notes: String::new(),
+    });
+    form.parcels.push(Parcel {
+        kind: "fragile".to_string(),
+        handling_notes: String::new(),
+    });
+    let errors = form.validate().unwrap_err();
+    assert!(!errors.has_field_error("parcels[0].handling_notes"));
+    assert!(errors.has_field_error("parcels[1].handling_notes"));
+}
+
+#[test]
+fn test_visibility_of_paths() {
+    let mut form = checkout();
+    form.parcels.push(Parcel {
+        kind: "fragile".to_string(),
+        handling_notes: String::new(),
+    });
+
+    assert!(!is_field_visible(&form, "shipping_street"));
+    assert!(is_field_visible(&form, "parcels[0].handling_notes"));
+    assert!(is_field_visible(&form, "unknown"));
+    assert!(!is_field_enabled(&form, "discount_code"));
+    assert_eq!(hidden_field_paths(&form), vec!["shipping_street"]);
+
+    form.membership = "gold".to_string();
+    form.parcels[0].kind = "books".to_string();
+    assert!(is_field_enabled(&form, "discount_code"));
+    assert!(!is_field_visible(&form, "parcels[0].handling_notes"));
+    assert_eq!(
+        hidden_field_paths(&form),
+        vec!["shipping_street", "parcels[0].handling_notes"]
+    );
+
+    assert!(is_within_path("parcels[0].kind", "parcels"));
+    assert!(is_within_path("parcels", "parcels"));
+    assert!(!is_within_path("parcels_count", "parcels"));
+}
+
+#[test]
+fn test_submission_data_excludes_hidden_fields() {
+    let mut form = checkout();
+    form.shipping_street = "Old street".to_string();
+    let handle = FormHandle::new(form);
+
+    let data = handle.submission_data();
+    assert!(!data.contains_key("shipping_street"));
+    assert!(data.contains_key("name"));
+    assert!(!visible_form_data(&handle.values().get_untracked()).contains_key("shipping_street"));
+
+    handle.set_field_value("ship_elsewhere", FieldValue::Boolean(true));
+    assert_eq!(
+        handle.submission_data().get("shipping_street"),
+        Some(&FieldValue::String("Old street".to_string()))
+    );
+}
+
+#[test]
+fn test_form_handle_validation_and_signals_follow_rules() {
+    let handle = FormHandle::new(checkout());
+    let visible = handle.field_visible("shipping_street");
+    let enabled = handle.field_enabled("discount_code");
+
+    assert!(handle.validate().is_ok());
+    assert!(!visible.get_untracked());
+    assert!(!enabled.get_untracked());
+
+    handle.set_field_value("ship_elsewhere", FieldValue::Boolean(true));
+    handle.set_field_value("membership", FieldValue::String("gold".to_string()));
+    assert!(visible.get_untracked());
+    assert!(enabled.get_untracked());
+    assert!(handle.validate().is_err());
+    assert!(handle.validate_field("shipping_street").is_err());
+}
+
+#[test]
+fn test_hidden_field_policies() {
+    let handle = FormHandle::new(checkout());
+    assert_eq!(handle.hidden_field_policy(), HiddenFieldPolicy::Keep);
+
+    handle.set_field_value("ship_elsewhere", FieldValue::Boolean(true));
+    handle.set_field_value("shipping_street", FieldValue::String("Main".to_string()));
+    handle.set_field_value("ship_elsewhere", FieldValue::Boolean(false));
+    assert_eq!(handle.values().get_untracked().shipping_street, "Main");
+
+    // Switching to Clear resets fields that are already hidden
+    handle.set_hidden_field_policy(HiddenFieldPolicy::Clear);
+    assert_eq!(handle.values().get_untracked().shipping_street, "");
+
+    handle.set_field_value("ship_elsewhere", FieldValue::Boolean(true));
+    handle.set_field_value("shipping_street", FieldValue::String("Main".to_string()));
+    handle.set_field_value("ship_elsewhere", FieldValue::Boolean(false));
+    assert_eq!(handle.values().get_untracked().shipping_street, "");
+}
+
+#[test]
+fn test_conditions_in_runtime_schemas() {
+    let schema: FormSchema = serde_json::from_value(json!({
+        "name": "Contact",
+        "field_metadata": [
+            {
+                "name": "channel",
+                "field_type": "Text",
+                "is_required": true,
+                "default_value": null,
+                "dependencies": [],
+                "attributes": {},
+                "validators": ["Required"]
+            },
+            {
+                "name": "phone",
+                "field_type": "Text",
+                "is_required": true,
+                "default_value": null,
+                "dependencies": [],
+                "attributes": {},
+                "validators": ["Required"],
+                "visible_when": { "Equals": ["channel", "phone"] }
+            }
+        ]
+    }))
+    .unwrap();
+    let metadata: Vec<FieldMetadata> = schema.field_metadata.clone();
+    assert_eq!(
+        metadata[1].visible_when,
+        Some(FieldCondition::equals(
+            "channel",
+            FieldValue::String("phone".to_string())
+        ))
+    );
+
+    // Fields without rules serialize as before
+    let exported = serde_json::to_value(&schema).unwrap();
+    assert!(exported["field_metadata"][0].get("visible_when").is_none());
+
+    let mut values = HashMap::new();
+    values.insert("phone".to_string(), FieldValue::String(String::new()));
+    values.insert(
+        "channel".to_string(),
+        FieldValue::String("email".to_string()),
+    );
+    let mut errors = ValidationErrors::new();
+    validate_values(&metadata, &values, "", &mut errors);
+    assert!(errors.is_empty());
+
+    values.insert(
+        "channel".to_string(),
+        FieldValue::String("phone".to_string()),
+    );
+    validate_values(&metadata, &values, "", &mut errors);
+    assert!(errors.has_field_error("phone"));
+}
+
+#[test]
+fn test_contains_condition_checks_selected_items() {
+    let condition = FieldCondition::contains("tags", "urgent");
+    let tags = FieldValue::Array(vec![FieldValue::String("urgent".to_string())]);
+
+    assert!(condition.evaluate_with(&|_| tags.clone()));
+    assert!(condition.evaluate_with(&|_| FieldValue::String("not urgent".to_string())));
+    assert!(!condition.evaluate_with(&|_| FieldValue::Null));
+}
@@ -25,6 +25,7 @@ impl Form for ComponentTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email_field".to_string(),
@@ -34,6 +35,7 @@ impl Form for ComponentTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "password_field".to_string(),
@@ -43,6 +45,7 @@ impl Form for ComponentTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "number_field".to_string(),
@@ -56,6 +59,7 @@ impl Form for ComponentTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "boolean_field".to_string(),
@@ -65,6 +69,7 @@ impl Form for ComponentTestForm {
                 default_value: Some(FieldValue::Boolean(false)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
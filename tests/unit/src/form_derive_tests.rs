@@ -0,0 +1,609 @@
+//! Tests for the `Form` derive macro: nested forms, arrays of nested forms,
+//! custom validation functions, serde naming interop and generic forms
+
+use leptos_forms_rs::core::{FieldType, FieldValue, FormValue};
+use leptos_forms_rs::validation::{ValidationErrors, Validator};
+use leptos_forms_rs::Form;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Address {
+    #[form(required)]
+    street: String,
+    #[form(required, min_length = 2)]
+    city: String,
+    #[form(default = "US")]
+    country: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Contact {
+    #[form(required)]
+    name: String,
+    #[form(required, email)]
+    email: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct CustomerForm {
+    #[form(required, max_length = 50)]
+    company: String,
+    employees: u32,
+    active: bool,
+    billing_address: Address,
+    contacts: Vec<Contact>,
+    notes: Option<String>,
+}
+
+fn valid_customer() -> CustomerForm {
+    CustomerForm {
+        company: "Acme".to_string(),
+        employees: 12,
+        active: true,
+        billing_address: Address {
+            street: "1 Main St".to_string(),
+            city: "Springfield".to_string(),
+            country: "US".to_string(),
+        },
+        contacts: vec![Contact {
+            name: "Jane".to_string(),
+            email: "jane@acme.com".to_string(),
+        }],
+        notes: None,
+    }
+}
+
+#[test]
+fn test_derive_scalar_field_metadata() {
+    let metadata = CustomerForm::field_metadata();
+    let names: Vec<&str> = metadata.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "company",
+            "employees",
+            "active",
+            "billing_address",
+            "contacts",
+            "notes"
+        ]
+    );
+
+    let company = &metadata[0];
+    assert!(matches!(company.field_type, FieldType::Text));
+    assert!(company.is_required);
+    assert_eq!(
+        company.validators,
+        vec![Validator::Required, Validator::MaxLength(50)]
+    );
+
+    assert!(matches!(metadata[1].field_type, FieldType::Number(_)));
+    assert!(matches!(metadata[2].field_type, FieldType::Boolean));
+    assert!(matches!(metadata[5].field_type, FieldType::Text));
+}
+
+#[test]
+fn test_derive_nested_field_metadata() {
+    let metadata = CustomerForm::field_metadata();
+
+    let address = &metadata[3];
+    assert!(matches!(&address.field_type, FieldType::Nested(name) if name == "Address"));
+    let nested_names: Vec<&str> = address
+        .nested_fields
+        .iter()
+        .map(|m| m.name.as_str())
+        .collect();
+    assert_eq!(nested_names, vec!["street", "city", "country"]);
+    assert!(address.nested_fields[1].is_required);
+
+    let contacts = &metadata[4];
+    match &contacts.field_type {
+        FieldType::Array(inner) => {
+            assert!(matches!(inner.as_ref(), FieldType::Nested(name) if name == "Contact"))
+        }
+        other => panic!("Expected array field type, got {:?}", other),
+    }
+    assert_eq!(contacts.nested_fields.len(), 2);
+    assert_eq!(contacts.nested_fields[1].validators[1], Validator::Email);
+}
+
+#[test]
+fn test_derive_default_values() {
+    let form = CustomerForm::default_values();
+    assert_eq!(form.company, "");
+    assert_eq!(form.billing_address.country, "US");
+    assert!(form.contacts.is_empty());
+
+    let country = &Address::field_metadata()[2];
+    assert_eq!(
+        country.default_value,
+        Some(FieldValue::String("US".to_string()))
+    );
+}
+
+#[test]
+fn test_derive_nested_field_values() {
+    let form = valid_customer();
+
+    let address = form.get_field_value("billing_address");
+    let address = address.as_object().expect("nested form is an object");
+    assert_eq!(
+        address.get("city"),
+        Some(&FieldValue::String("Springfield".to_string()))
+    );
+
+    let contacts = form.get_field_value("contacts");
+    let contacts = contacts.as_array().expect("contacts is an array");
+    assert_eq!(contacts.len(), 1);
+    assert!(contacts[0].as_object().unwrap().contains_key("email"));
+
+    assert_eq!(form.get_field_value("employees"), FieldValue::Integer(12));
+    assert_eq!(form.get_field_value("notes"), FieldValue::Null);
+}
+
+#[test]
+fn test_derive_set_nested_field_values() {
+    let mut form = valid_customer();
+
+    let mut contact = std::collections::HashMap::new();
+    contact.insert("name".to_string(), FieldValue::String("Bob".to_string()));
+    contact.insert(
+        "email".to_string(),
+        FieldValue::String("bob@acme.com".to_string()),
+    );
+    let mut contacts = form.get_field_value("contacts").as_array().unwrap().clone();
+    contacts.push(FieldValue::Object(contact));
+
+    form.set_field_value("contacts", FieldValue::Array(contacts));
+    form.set_field_value("employees", FieldValue::String("42".to_string()));
+
+    assert_eq!(form.contacts.len(), 2);
+    assert_eq!(form.contacts[1].email, "bob@acme.com");
+    assert_eq!(form.employees, 42);
+}
+
+#[test]
+fn test_derive_nested_from_field_value_rejects_mismatched_types() {
+    let result = Address::from_field_value(FieldValue::String("nope".to_string()));
+    assert!(result.is_err());
+
+    let mut object = std::collections::HashMap::new();
+    object.insert("street".to_string(), FieldValue::Array(vec![]));
+    let error = Address::from_field_value(FieldValue::Object(object)).unwrap_err();
+    assert!(error.starts_with("street:"));
+}
+
+#[test]
+fn test_integers_beyond_i64_do_not_wrap() {
+    assert_eq!(42u64.to_field_value(), FieldValue::Integer(42));
+
+    let large = u64::MAX.to_field_value();
+    assert_eq!(large, FieldValue::Number(u64::MAX as f64));
+    assert_eq!(u64::from_field_value(large), Ok(u64::MAX));
+    assert!(u32::from_field_value(FieldValue::Number(1e12)).is_err());
+    assert!(u8::from_field_value(FieldValue::Number(-1.0)).is_err());
+}
+
+#[test]
+fn test_derive_valid_nested_form() {
+    assert!(valid_customer().validate().is_ok());
+}
+
+#[test]
+fn test_derive_validation_errors_use_nested_paths() {
+    let mut form = valid_customer();
+    form.company = String::new();
+    form.billing_address.city = "X".to_string();
+    form.contacts.push(Contact {
+        name: "Bob".to_string(),
+        email: "not-an-email".to_string(),
+    });
+
+    let errors = form.validate().unwrap_err();
+    assert!(errors.has_field_error("company"));
+    assert!(errors.has_field_error("billing_address.city"));
+    assert!(errors.has_field_error("contacts[1].email"));
+    assert!(!errors.has_field_error("contacts[0].email"));
+    assert!(!errors.has_field_error("billing_address.street"));
+}
+
+fn validate_sku(sku: &str) -> Result<(), String> {
+    if sku.starts_with("SKU-") {
+        Ok(())
+    } else {
+        Err("SKU must start with SKU-".to_string())
+    }
+}
+
+mod rules {
+    pub fn positive(quantity: &u32) -> Result<(), String> {
+        if *quantity > 0 {
+            Ok(())
+        } else {
+            Err("Quantity must be positive".to_string())
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+#[form(validate = Self::check_dates)]
+struct BookingForm {
+    start_day: u32,
+    end_day: u32,
+}
+
+impl BookingForm {
+    fn check_dates(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.end_day < self.start_day {
+            errors.add_field_error("end_day", "End must be after start".to_string());
+        }
+        if self.start_day == 0 {
+            errors.add_form_error("Booking must have a start".to_string());
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct LineItem {
+    #[form(required, validate_with = validate_sku)]
+    sku: String,
+    #[form(validate_with = rules::positive)]
+    quantity: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct OrderForm {
+    items: Vec<LineItem>,
+    booking: BookingForm,
+}
+
+#[test]
+fn test_derive_validate_with_uses_typed_field_access() {
+    let item = LineItem {
+        sku: "SKU-1".to_string(),
+        quantity: 3,
+    };
+    assert!(item.validate().is_ok());
+
+    let item = LineItem {
+        sku: "ABC".to_string(),
+        quantity: 0,
+    };
+    let errors = item.validate().unwrap_err();
+    assert_eq!(
+        errors.get_field_error("sku"),
+        Some(&vec!["SKU must start with SKU-".to_string()])
+    );
+    assert_eq!(
+        errors.get_field_error("quantity"),
+        Some(&vec!["Quantity must be positive".to_string()])
+    );
+}
+
+#[test]
+fn test_derive_struct_level_validation() {
+    let booking = BookingForm {
+        start_day: 5,
+        end_day: 3,
+    };
+    let errors = booking.validate().unwrap_err();
+    assert!(errors.has_field_error("end_day"));
+    assert!(errors.form_errors.is_empty());
+
+    let booking = BookingForm {
+        start_day: 0,
+        end_day: 3,
+    };
+    let errors = booking.validate().unwrap_err();
+    assert_eq!(
+        errors.form_errors,
+        vec!["Booking must have a start".to_string()]
+    );
+}
+
+#[test]
+fn test_derive_custom_validation_in_nested_forms() {
+    let order = OrderForm {
+        items: vec![
+            LineItem {
+                sku: "SKU-1".to_string(),
+                quantity: 1,
+            },
+            LineItem {
+                sku: "bad".to_string(),
+                quantity: 1,
+            },
+        ],
+        booking: BookingForm {
+            start_day: 0,
+            end_day: 0,
+        },
+    };
+
+    let errors = order.validate().unwrap_err();
+    assert!(errors.has_field_error("items[1].sku"));
+    assert!(!errors.has_field_error("items[0].sku"));
+    assert_eq!(
+        errors.get_field_error("booking"),
+        Some(&vec!["Booking must have a start".to_string()])
+    );
+    assert!(errors.form_errors.is_empty());
+}
+
+#[test]
+fn test_validation_errors_merge_at_prefixes_nested_errors() {
+    let mut nested = ValidationErrors::new();
+    nested.add_field_error("city", "Required".to_string());
+    nested.add_form_error("Incomplete address".to_string());
+
+    let mut errors = ValidationErrors::new();
+    errors.merge_at("contacts[0].address", nested.clone());
+    assert!(errors.has_field_error("contacts[0].address.city"));
+    assert!(errors.has_field_error("contacts[0].address"));
+
+    let mut top_level = ValidationErrors::new();
+    top_level.merge_at("", nested);
+    assert!(top_level.has_field_error("city"));
+    assert_eq!(top_level.form_errors.len(), 1);
+}
+
+fn default_plan() -> String {
+    "basic".to_string()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+#[serde(rename_all = "camelCase")]
+struct SignupForm {
+    #[form(required)]
+    first_name: String,
+    #[serde(rename = "mail")]
+    #[form(required, email)]
+    email_address: String,
+    #[serde(default = "default_plan")]
+    plan_name: String,
+    #[serde(skip)]
+    session_token: String,
+    billing_address: Address,
+    #[serde(rename = "accountType")]
+    #[form(rename = "accountType")]
+    r#type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+#[serde(default)]
+struct PreferencesForm {
+    theme: String,
+    #[form(default = 20)]
+    page_size: u32,
+}
+
+impl Default for PreferencesForm {
+    fn default() -> Self {
+        Self {
+            theme: "dark".to_string(),
+            page_size: 50,
+        }
+    }
+}
+
+fn starter_settings() -> SettingsForm {
+    SettingsForm {
+        theme: "light".to_string(),
+        revision: 7,
+        audited_by: "nobody".to_string(),
+    }
+}
+
+fn auditor() -> String {
+    "auditor".to_string()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+#[serde(default = "starter_settings")]
+struct SettingsForm {
+    theme: String,
+    #[serde(skip_deserializing)]
+    revision: u32,
+    #[serde(skip_deserializing, default = "auditor")]
+    audited_by: String,
+}
+
+#[test]
+fn test_derive_field_names_follow_serde_renames() {
+    let names: Vec<String> = SignupForm::field_metadata()
+        .into_iter()
+        .map(|m| m.name)
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "firstName",
+            "mail",
+            "planName",
+            "billingAddress",
+            "accountType"
+        ]
+    );
+}
+
+#[test]
+fn test_derive_schema_matches_json_payload() {
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+    #[serde(rename_all = "kebab-case")]
+    struct ProfileForm {
+        display_name: String,
+        #[serde(rename = "mail")]
+        email_address: String,
+        #[serde(skip_serializing)]
+        internal_note: String,
+    }
+
+    let form = ProfileForm::default_values();
+    let json = leptos_forms_rs::form_to_json(&form).unwrap();
+    let mut payload_keys: Vec<String> = json.as_object().unwrap().keys().cloned().collect();
+    payload_keys.sort();
+
+    let mut schema_keys: Vec<String> = ProfileForm::schema()
+        .field_metadata
+        .into_iter()
+        .map(|m| m.name)
+        .collect();
+    schema_keys.sort();
+
+    // Serde still reads the write-only field back, so the form keeps it
+    assert_eq!(payload_keys, vec!["display-name", "mail"]);
+    assert_eq!(schema_keys, vec!["display-name", "internal-note", "mail"]);
+    let submitted: ProfileForm =
+        serde_json::from_str(r#"{"display-name": "Ada", "mail": "", "internal-note": "vip"}"#)
+            .unwrap();
+    assert_eq!(
+        submitted.get_field_value("internal-note"),
+        FieldValue::String("vip".to_string())
+    );
+}
+
+#[test]
+fn test_derive_renamed_field_access_and_errors() {
+    let mut form = SignupForm::default_values();
+    form.set_field_value("firstName", FieldValue::String("Ada".to_string()));
+    form.set_field_value("mail", FieldValue::String("invalid".to_string()));
+    form.set_field_value("session_token", FieldValue::String("secret".to_string()));
+
+    assert_eq!(form.first_name, "Ada");
+    assert_eq!(
+        form.get_field_value("mail"),
+        FieldValue::String("invalid".to_string())
+    );
+    assert_eq!(form.get_field_value("email_address"), FieldValue::Null);
+    assert!(form.session_token.is_empty());
+
+    let errors = form.validate().unwrap_err();
+    assert!(errors.has_field_error("mail"));
+    assert!(errors.has_field_error("billingAddress.street"));
+    assert!(!errors.has_field_error("firstName"));
+}
+
+#[test]
+fn test_derive_honours_serde_defaults() {
+    let form = SignupForm::default_values();
+    assert_eq!(form.plan_name, "basic");
+
+    let plan = SignupForm::schema();
+    let plan = plan.get_field("planName").unwrap();
+    assert_eq!(
+        plan.default_value,
+        Some(FieldValue::String("basic".to_string()))
+    );
+
+    let preferences = PreferencesForm::default_values();
+    assert_eq!(preferences.theme, "dark");
+    assert_eq!(preferences.page_size, 20);
+}
+
+#[test]
+fn test_derive_defaults_match_what_serde_reads() {
+    let settings = SettingsForm::default_values();
+    assert_eq!(
+        settings,
+        serde_json::from_str::<SettingsForm>("{}").unwrap()
+    );
+    assert_eq!(settings.theme, "light");
+    assert_eq!(settings.revision, 7);
+    assert_eq!(settings.audited_by, "auditor");
+
+    // Fields serde never reads are not form fields
+    let names: Vec<String> = SettingsForm::field_metadata()
+        .into_iter()
+        .map(|m| m.name)
+        .collect();
+    assert_eq!(names, vec!["theme"]);
+    let submitted: SettingsForm =
+        serde_json::from_str(r#"{"theme": "dark", "revision": 99}"#).unwrap();
+    assert_eq!(submitted.revision, 7);
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Paged<T: Serialize> {
+    #[form(min = 1)]
+    page: u32,
+    items: Vec<T>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Labeled<T>
+where
+    T: Clone,
+{
+    #[form(required)]
+    label: String,
+    value: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Scoped<'a, T> {
+    value: Option<T>,
+    #[serde(skip)]
+    marker: std::marker::PhantomData<&'a ()>,
+}
+
+#[test]
+fn test_derive_generic_wrapper_metadata() {
+    let metadata = Paged::<Contact>::field_metadata();
+    assert_eq!(metadata[1].name, "items");
+    match &metadata[1].field_type {
+        FieldType::Array(inner) => {
+            assert!(matches!(inner.as_ref(), FieldType::Nested(name) if name == "Contact"))
+        }
+        other => panic!("Expected an array field, got {:?}", other),
+    }
+    assert_eq!(metadata[1].nested_fields.len(), 2);
+
+    assert!(matches!(
+        Labeled::<u32>::field_metadata()[1].field_type,
+        FieldType::Number(_)
+    ));
+    assert!(matches!(
+        Labeled::<Address>::field_metadata()[1].field_type,
+        FieldType::Nested(_)
+    ));
+}
+
+#[test]
+fn test_derive_generic_wrapper_values_and_validation() {
+    let mut paged = Paged::<Contact>::default_values();
+    assert!(paged.items.is_empty());
+
+    paged.set_field_value(
+        "items",
+        FieldValue::Array(vec![Contact::default_values().to_field_value()]),
+    );
+    assert_eq!(paged.items.len(), 1);
+
+    let errors = paged.validate().unwrap_err();
+    assert!(errors.has_field_error("page"));
+    assert!(errors.has_field_error("items[0].email"));
+
+    let mut labeled = Labeled::<Address>::default_values();
+    labeled.label = "Home".to_string();
+    labeled.value.street = "1 Main St".to_string();
+    let errors = labeled.validate().unwrap_err();
+    assert!(errors.has_field_error("value.city"));
+    assert!(!errors.has_field_error("label"));
+}
+
+#[test]
+fn test_derive_generic_wrapper_with_lifetime() {
+    let mut scoped = Scoped::<'static, u32>::default_values();
+    assert_eq!(scoped.value, None);
+
+    scoped.set_field_value("value", FieldValue::Integer(7));
+    assert_eq!(scoped.value, Some(7));
+    assert_eq!(scoped.get_field_value("value"), FieldValue::Integer(7));
+    assert_eq!(Scoped::<u32>::field_metadata().len(), 1);
+}
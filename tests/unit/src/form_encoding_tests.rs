@@ -0,0 +1,207 @@
+//! Tests for URL-encoded and multipart form bodies
+
+use leptos_forms_rs::core::{FieldValue, FileData};
+use leptos_forms_rs::submission::{
+    decode_multipart, decode_urlencoded, encode_multipart, encode_urlencoded, form_parts,
+    parts_to_values, FormPart,
+};
+use leptos_forms_rs::{Form, FormError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Address {
+    street: String,
+    city: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct LineItem {
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Order {
+    customer: String,
+    note: Option<String>,
+    express: bool,
+    tags: Vec<String>,
+    address: Address,
+    items: Vec<LineItem>,
+    invoice: Option<FileData>,
+}
+
+fn order() -> Order {
+    Order {
+        customer: "Ada Lovelace & Co".to_string(),
+        note: None,
+        express: true,
+        tags: vec!["gift".to_string(), "fragile".to_string()],
+        address: Address {
+            street: "1 Main St".to_string(),
+            city: "Zürich".to_string(),
+        },
+        items: vec![
+            LineItem {
+                sku: "A-1".to_string(),
+                quantity: 2,
+            },
+            LineItem {
+                sku: "B-2".to_string(),
+                quantity: 1,
+            },
+        ],
+        invoice: None,
+    }
+}
+
+fn invoice() -> FileData {
+    FileData {
+        name: "invoice.pdf".to_string(),
+        size: 5,
+        mime_type: "application/pdf".to_string(),
+        data: b"%PDF-".to_vec(),
+    }
+}
+
+#[test]
+fn test_parts_use_bracket_notation_in_field_order() {
+    let names: Vec<String> = form_parts(&order())
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "customer",
+            "express",
+            "tags[0]",
+            "tags[1]",
+            "address[city]",
+            "address[street]",
+            "items[0][quantity]",
+            "items[0][sku]",
+            "items[1][quantity]",
+            "items[1][sku]",
+        ]
+    );
+}
+
+#[test]
+fn test_urlencoded_round_trip() {
+    let body = encode_urlencoded(&order());
+    assert!(body.starts_with("customer=Ada+Lovelace+%26+Co&express=true&tags%5B0%5D=gift"));
+    assert!(body.contains("address%5Bcity%5D=Z%C3%BCrich"));
+    assert!(!body.contains("note"));
+
+    assert_eq!(decode_urlencoded::<Order>(&body).unwrap(), order());
+}
+
+#[test]
+fn test_urlencoded_accepts_browser_conventions() {
+    let body = "customer=Ada&tags[]=gift&tags[]=fragile&address[city]=Paris\
+                &items[1][sku]=B-2&items[0][sku]=A-1&items[0][quantity]=3&note=";
+    let decoded: Order = decode_urlencoded(body).unwrap();
+    assert_eq!(decoded.tags, vec!["gift", "fragile"]);
+    assert_eq!(decoded.address.city, "Paris");
+    assert_eq!(decoded.address.street, "");
+    assert_eq!(decoded.items[0].sku, "A-1");
+    assert_eq!(decoded.items[0].quantity, 3);
+    assert_eq!(decoded.items[1].sku, "B-2");
+    assert_eq!(decoded.note, None);
+    // Unchecked checkboxes are simply missing
+    assert!(!decoded.express);
+
+    let repeated: Order = decode_urlencoded("tags=a&tags=b&express=on").unwrap();
+    assert_eq!(repeated.tags, vec!["a", "b"]);
+    assert!(repeated.express);
+
+    let single: Order = decode_urlencoded("tags=only").unwrap();
+    assert_eq!(single.tags, vec!["only"]);
+}
+
+#[test]
+fn test_invalid_values_name_the_field() {
+    let error = decode_urlencoded::<Order>("items[0][quantity]=many").unwrap_err();
+    match error {
+        FormError::SerializationError { field, .. } => assert_eq!(field.as_deref(), Some("items")),
+        other => panic!("expected a serialization error, got {:?}", other),
+    }
+    assert!(decode_urlencoded::<Order>("customer=%ZZ").is_err());
+}
+
+#[test]
+fn test_multipart_round_trip_with_files() {
+    let with_invoice = Order {
+        invoice: Some(invoice()),
+        ..order()
+    };
+    let multipart = encode_multipart(&with_invoice);
+    assert!(multipart
+        .content_type()
+        .starts_with("multipart/form-data; boundary="));
+
+    let body = String::from_utf8_lossy(&multipart.body);
+    assert!(body.contains(
+        "Content-Disposition: form-data; name=\"invoice\"; filename=\"invoice.pdf\"\r\n\
+         Content-Type: application/pdf\r\n\r\n%PDF-\r\n"
+    ));
+    assert!(body.contains("name=\"items[1][sku]\"\r\n\r\nB-2\r\n"));
+    assert!(body.ends_with(&format!("--{}--\r\n", multipart.boundary)));
+
+    let decoded: Order = decode_multipart(&multipart.content_type(), &multipart.body).unwrap();
+    assert_eq!(decoded, with_invoice);
+}
+
+#[test]
+fn test_multipart_from_a_browser() {
+    let body = concat!(
+        "preamble\r\n",
+        "------WebKitFormBoundaryX\r\n",
+        "Content-Disposition: form-data; name=\"customer\"\r\n\r\n",
+        "Grace\r\n",
+        "------WebKitFormBoundaryX\r\n",
+        "Content-Disposition: form-data; name=\"tags[]\"\r\n\r\n",
+        "line one\r\nline two\r\n",
+        "------WebKitFormBoundaryX\r\n",
+        "Content-Disposition: form-data; name=\"invoice\"; filename=\"\"\r\n",
+        "Content-Type: application/octet-stream\r\n\r\n",
+        "\r\n",
+        "------WebKitFormBoundaryX--\r\n",
+    );
+    let decoded: Order = decode_multipart(
+        "multipart/form-data; boundary=\"----WebKitFormBoundaryX\"",
+        body.as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(decoded.customer, "Grace");
+    assert_eq!(decoded.tags, vec!["line one\r\nline two"]);
+    assert_eq!(decoded.invoice, None);
+
+    assert!(decode_multipart::<Order>("multipart/form-data", body.as_bytes()).is_err());
+}
+
+#[test]
+fn test_parts_without_a_form_stay_text() {
+    let values = parts_to_values(vec![
+        (
+            "filters[status]".to_string(),
+            FormPart::Text("open".to_string()),
+        ),
+        ("ids[]".to_string(), FormPart::Text("4".to_string())),
+        ("ids[]".to_string(), FormPart::Text("7".to_string())),
+    ]);
+    assert_eq!(
+        values["ids"],
+        FieldValue::Array(vec![
+            FieldValue::String("4".to_string()),
+            FieldValue::String("7".to_string())
+        ])
+    );
+    match &values["filters"] {
+        FieldValue::Object(filters) => {
+            assert_eq!(filters["status"], FieldValue::String("open".to_string()))
+        }
+        other => panic!("expected an object, got {:?}", other),
+    }
+}
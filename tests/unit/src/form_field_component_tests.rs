@@ -20,6 +20,7 @@ impl Form for TestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -29,6 +30,7 @@ impl Form for TestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -28,6 +28,7 @@ impl Form for ComprehensiveTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "name".to_string(),
@@ -41,6 +42,7 @@ impl Form for ComprehensiveTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -50,6 +52,7 @@ impl Form for ComprehensiveTestForm {
                 default_value: None,
                 dependencies: vec!["name".to_string()],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "age".to_string(),
@@ -67,6 +70,7 @@ impl Form for ComprehensiveTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "is_active".to_string(),
@@ -76,6 +80,7 @@ impl Form for ComprehensiveTestForm {
                 default_value: Some(FieldValue::Boolean(true)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "tags".to_string(),
@@ -85,6 +90,7 @@ impl Form for ComprehensiveTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -24,6 +24,7 @@ impl Form for HooksTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email".to_string(),
@@ -33,6 +34,7 @@ impl Form for HooksTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "age".to_string(),
@@ -46,6 +48,7 @@ impl Form for HooksTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "is_active".to_string(),
@@ -55,6 +58,7 @@ impl Form for HooksTestForm {
                 default_value: Some(FieldValue::Boolean(false)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -1,8 +1,13 @@
+use any_spawner::Executor;
 use leptos::prelude::*;
 use leptos_forms_rs::core::types::FieldValue;
 use leptos_forms_rs::core::Form;
 use leptos_forms_rs::core::FormHandle;
-use leptos_forms_rs::hooks::use_form_persistence;
+use leptos_forms_rs::core::{
+    AutoSaveSchedule, FormPersistence, LocalStoragePersistence, MemoryPersistence,
+    PersistenceOptions, PersistenceStorage, SessionStoragePersistence,
+};
+use leptos_forms_rs::hooks::{use_form, use_form_persistence, use_form_persistence_with};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -24,6 +29,7 @@ impl Form for PersistenceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "email".to_string(),
@@ -33,6 +39,7 @@ impl Form for PersistenceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "preferences".to_string(),
@@ -44,6 +51,7 @@ impl Form for PersistenceTestForm {
                 default_value: Some(FieldValue::Array(vec![])),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "settings".to_string(),
@@ -53,6 +61,7 @@ impl Form for PersistenceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -109,6 +118,7 @@ impl Form for PersistenceTestForm {
 
 #[test]
 fn test_form_persistence_hook_creation() {
+    let _ = Executor::init_futures_executor();
     // Test that we can create the form persistence hook
     let form: FormHandle<PersistenceTestForm> =
         FormHandle::new(PersistenceTestForm::default_values());
@@ -120,6 +130,7 @@ fn test_form_persistence_hook_creation() {
 
 #[test]
 fn test_form_persistence_hook_default_storage_key() {
+    let _ = Executor::init_futures_executor();
     // Test that the hook works with default storage key
     let form: FormHandle<PersistenceTestForm> =
         FormHandle::new(PersistenceTestForm::default_values());
@@ -131,6 +142,7 @@ fn test_form_persistence_hook_default_storage_key() {
 
 #[test]
 fn test_form_persistence_hook_types() {
+    let _ = Executor::init_futures_executor();
     // Test that the hook returns the correct types
     let form: FormHandle<PersistenceTestForm> =
         FormHandle::new(PersistenceTestForm::default_values());
@@ -146,6 +158,7 @@ fn test_form_persistence_hook_types() {
 
 #[test]
 fn test_form_persistence_storage_key_generation() {
+    let _ = Executor::init_futures_executor();
     // Test that storage keys are generated correctly for different form types
     let form1: FormHandle<PersistenceTestForm> =
         FormHandle::new(PersistenceTestForm::default_values());
@@ -161,6 +174,7 @@ fn test_form_persistence_storage_key_generation() {
 
 #[test]
 fn test_form_persistence_complex_data_types() {
+    let _ = Executor::init_futures_executor();
     // Test that the hook can handle complex data types
     let form: FormHandle<PersistenceTestForm> =
         FormHandle::new(PersistenceTestForm::default_values());
@@ -186,6 +200,7 @@ fn test_form_persistence_complex_data_types() {
 
 #[test]
 fn test_form_persistence_error_handling() {
+    let _ = Executor::init_futures_executor();
     // Test that the hook handles errors gracefully
     let form: FormHandle<PersistenceTestForm> =
         FormHandle::new(PersistenceTestForm::default_values());
@@ -195,3 +210,220 @@ fn test_form_persistence_error_handling() {
     // For now, we just verify the hook can be created
     assert!(true);
 }
+
+fn filled_form() -> PersistenceTestForm {
+    PersistenceTestForm {
+        username: "ada".to_string(),
+        email: "ada@example.com".to_string(),
+        preferences: vec!["dark_mode".to_string()],
+        settings: std::collections::HashMap::from([("lang".to_string(), "en".to_string())]),
+    }
+}
+
+fn enabled(clear_on_submit: bool) -> PersistenceOptions {
+    PersistenceOptions {
+        enabled: true,
+        auto_save: true,
+        clear_on_submit,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_memory_persistence_round_trip() {
+    let storage = MemoryPersistence::new("signup");
+    assert!(!FormPersistence::<PersistenceTestForm>::exists(&storage));
+    assert_eq!(
+        FormPersistence::<PersistenceTestForm>::load(&storage),
+        Ok(None)
+    );
+
+    storage.save(&filled_form()).unwrap();
+    assert!(FormPersistence::<PersistenceTestForm>::exists(&storage));
+    assert_eq!(storage.load(), Ok(Some(filled_form())));
+    assert!(storage.raw().unwrap().contains("ada@example.com"));
+
+    // Other keys share the store without seeing each other's values
+    let other = storage.with_key("profile");
+    assert!(!FormPersistence::<PersistenceTestForm>::exists(&other));
+    other.save(&PersistenceTestForm::default_values()).unwrap();
+    assert_eq!(storage.load(), Ok(Some(filled_form())));
+
+    FormPersistence::<PersistenceTestForm>::clear(&storage).unwrap();
+    assert!(!FormPersistence::<PersistenceTestForm>::exists(&storage));
+    assert!(FormPersistence::<PersistenceTestForm>::exists(&other));
+}
+
+#[test]
+fn test_memory_persistence_rejects_corrupted_data() {
+    let storage = MemoryPersistence::new("signup");
+    storage.set_raw("{\"username\": ");
+    assert!(FormPersistence::<PersistenceTestForm>::load(&storage).is_err());
+
+    // Drafts that no longer fit the form are discarded by default
+    storage.set_raw("{\"username\": 42}");
+    assert_eq!(
+        FormPersistence::<PersistenceTestForm>::load(&storage),
+        Ok(None)
+    );
+    assert!(!FormPersistence::<PersistenceTestForm>::exists(&storage));
+}
+
+#[test]
+fn test_web_storage_fails_gracefully_outside_the_browser() {
+    let local = LocalStoragePersistence::new("signup");
+    let session = SessionStoragePersistence::new("signup");
+    assert_eq!(local.key(), "signup");
+    assert!(local.save(&filled_form()).is_err());
+    assert!(FormPersistence::<PersistenceTestForm>::load(&session).is_err());
+    assert!(!FormPersistence::<PersistenceTestForm>::exists(&session));
+}
+
+#[test]
+fn test_persistence_hook_restores_and_saves() {
+    let _ = Executor::init_futures_executor();
+    let storage = MemoryPersistence::new("signup");
+    storage.save(&filled_form()).unwrap();
+
+    let form = FormHandle::new(PersistenceTestForm::default_values());
+    let (save, load, clear) = use_form_persistence_with(&form, storage.clone(), enabled(true));
+    // The draft is restored once the form is mounted
+    assert_eq!(
+        form.values().get_untracked(),
+        PersistenceTestForm::default_values()
+    );
+    Executor::poll_local();
+    assert_eq!(form.values().get_untracked(), filled_form());
+
+    form.set_values(PersistenceTestForm::default_values());
+    save.run(());
+    assert_eq!(
+        storage.load(),
+        Ok(Some(PersistenceTestForm::default_values()))
+    );
+
+    storage.save(&filled_form()).unwrap();
+    load.run(());
+    assert_eq!(form.values().get_untracked(), filled_form());
+
+    clear.run(());
+    assert!(!FormPersistence::<PersistenceTestForm>::exists(&storage));
+}
+
+#[test]
+fn test_persistence_hook_uses_the_chosen_storage() {
+    assert_eq!(
+        PersistenceOptions::from("signup").storage,
+        PersistenceStorage::Local
+    );
+
+    let form = FormHandle::new(filled_form());
+    let (save, load, clear) = use_form_persistence(
+        &form,
+        PersistenceOptions {
+            storage: PersistenceStorage::Memory,
+            ..Default::default()
+        },
+    );
+    // Disabled options leave the values alone until asked
+    assert_eq!(form.values().get_untracked(), filled_form());
+
+    // Memory works natively, where Web Storage would fail
+    save.run(());
+    form.set_values(PersistenceTestForm::default_values());
+    load.run(());
+    assert_eq!(form.values().get_untracked(), filled_form());
+
+    clear.run(());
+    form.set_values(PersistenceTestForm::default_values());
+    load.run(());
+    assert_eq!(
+        form.values().get_untracked(),
+        PersistenceTestForm::default_values()
+    );
+}
+
+#[test]
+fn test_persistence_hook_respects_disabled_options() {
+    let storage = MemoryPersistence::new("signup");
+    storage.save(&filled_form()).unwrap();
+
+    let form = FormHandle::new(PersistenceTestForm::default_values());
+    let _ = use_form_persistence_with(&form, storage.clone(), PersistenceOptions::default());
+    assert_eq!(
+        form.values().get_untracked(),
+        PersistenceTestForm::default_values()
+    );
+
+    assert!(form.submit().is_err());
+    form.set_values(filled_form());
+    assert!(form.submit().is_ok());
+    assert!(FormPersistence::<PersistenceTestForm>::exists(&storage));
+}
+
+#[test]
+fn test_persistence_hook_clears_after_submit() {
+    let _ = Executor::init_futures_executor();
+    let storage = MemoryPersistence::new("signup");
+    let form = FormHandle::new(PersistenceTestForm::default_values());
+    let (save, _load, _clear) = use_form_persistence_with(&form, storage.clone(), enabled(true));
+    save.run(());
+
+    // Submissions that fail validation keep the draft
+    assert!(form.submit().is_err());
+    assert!(FormPersistence::<PersistenceTestForm>::exists(&storage));
+
+    // So do submissions that are sent but not accepted
+    form.set_values(filled_form());
+    assert!(form.submit().is_ok());
+    assert!(FormPersistence::<PersistenceTestForm>::exists(&storage));
+    form.finish_submission();
+    assert!(FormPersistence::<PersistenceTestForm>::exists(&storage));
+
+    assert!(form.submit().is_ok());
+    form.finish_successful_submission();
+    assert!(!FormPersistence::<PersistenceTestForm>::exists(&storage));
+
+    // Submitting through `use_form` sends nothing, so only finishing the
+    // submission clears the draft
+    let (form, submit, _reset) = use_form(filled_form());
+    let local = storage.with_key("local");
+    let (save, _load, _clear) = use_form_persistence_with(&form, local.clone(), enabled(true));
+    save.run(());
+    submit.run(());
+    Executor::poll_local();
+    assert!(form.is_submitting().get_untracked());
+    assert!(FormPersistence::<PersistenceTestForm>::exists(&local));
+    form.finish_successful_submission();
+    assert!(!FormPersistence::<PersistenceTestForm>::exists(&local));
+
+    let kept = storage.with_key("kept");
+    let form = FormHandle::new(filled_form());
+    let (save, _load, _clear) = use_form_persistence_with(&form, kept.clone(), enabled(false));
+    save.run(());
+    assert!(form.submit().is_ok());
+    form.finish_successful_submission();
+    assert!(FormPersistence::<PersistenceTestForm>::exists(&kept));
+}
+
+#[test]
+fn test_only_the_latest_auto_save_runs() {
+    let schedule = AutoSaveSchedule::new();
+    let first = schedule.schedule();
+    let second = schedule.schedule();
+    assert!(!schedule.is_due(first));
+    assert!(schedule.is_due(second));
+}
+
+#[test]
+fn test_clearing_cancels_scheduled_auto_saves() {
+    let schedule = AutoSaveSchedule::new();
+    // An edit made just before submitting
+    let pending = schedule.schedule();
+    schedule.cancel();
+    assert!(!schedule.is_due(pending));
+
+    // Edits made afterwards are saved again
+    let later = schedule.schedule();
+    assert!(schedule.is_due(later));
+}
@@ -28,6 +28,7 @@ impl Form for TypesTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email_field".to_string(),
@@ -37,6 +38,7 @@ impl Form for TypesTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "password_field".to_string(),
@@ -46,6 +48,7 @@ impl Form for TypesTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "number_field".to_string(),
@@ -59,6 +62,7 @@ impl Form for TypesTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "boolean_field".to_string(),
@@ -68,6 +72,7 @@ impl Form for TypesTestForm {
                 default_value: Some(FieldValue::Boolean(false)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "url_field".to_string(),
@@ -77,6 +82,7 @@ impl Form for TypesTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -24,6 +24,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email_field".to_string(),
@@ -33,6 +34,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "min_length_field".to_string(),
@@ -42,6 +44,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "max_length_field".to_string(),
@@ -51,6 +54,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
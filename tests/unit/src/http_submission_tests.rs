@@ -0,0 +1,238 @@
+//! Tests for submitting forms over HTTP and mapping server errors
+
+use futures::executor::block_on;
+use leptos::prelude::*;
+use leptos_forms_rs::submission::{
+    parse_error_body, HttpClient, HttpFuture, HttpMethod, HttpRequest, HttpResponse, HttpSubmitter,
+};
+use leptos_forms_rs::{Form, FormError, FormHandle};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Signup {
+    #[form(required)]
+    name: String,
+    #[form(email)]
+    email: String,
+}
+
+fn signup() -> Signup {
+    Signup {
+        name: "Ada".to_string(),
+        email: "ada@example.com".to_string(),
+    }
+}
+
+/// Answers every request with the same response, remembering the requests
+#[derive(Clone)]
+struct StubClient {
+    response: Result<HttpResponse, FormError>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl StubClient {
+    fn new(response: HttpResponse) -> Self {
+        Self {
+            response: Ok(response),
+            requests: Arc::default(),
+        }
+    }
+
+    fn failing(error: FormError) -> Self {
+        Self {
+            response: Err(error),
+            requests: Arc::default(),
+        }
+    }
+
+    fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpClient for StubClient {
+    fn send(&self, request: HttpRequest) -> HttpFuture {
+        self.requests.lock().unwrap().push(request);
+        Box::pin(std::future::ready(self.response.clone()))
+    }
+}
+
+fn status_and_body(error: &FormError) -> (Option<u16>, Option<&str>) {
+    match error {
+        FormError::SubmissionError {
+            status_code,
+            response,
+            ..
+        } => (*status_code, response.as_deref()),
+        other => panic!("expected a submission error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_form_is_posted_as_json_with_headers() {
+    let client = StubClient::new(HttpResponse::new(201, r#"{"id":7}"#));
+    let submitter = HttpSubmitter::post("/api/signups")
+        .header("X-Client", "web")
+        .bearer_token("secret")
+        .timeout(Duration::from_secs(5))
+        .with_client(client.clone());
+    let form = FormHandle::new(signup());
+
+    let response = block_on(submitter.submit(&form)).unwrap();
+    assert_eq!(response.json::<Value>().unwrap(), json!({"id": 7}));
+    assert!(!form.is_submitting().get_untracked());
+
+    let requests = client.requests();
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(request.method, HttpMethod::Post);
+    assert_eq!(request.url, "/api/signups");
+    assert_eq!(request.header("content-type"), Some("application/json"));
+    assert_eq!(request.header("Authorization"), Some("Bearer secret"));
+    assert_eq!(request.header("X-Client"), Some("web"));
+    assert_eq!(request.timeout, Some(Duration::from_secs(5)));
+    let body: Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(body, json!({"name": "Ada", "email": "ada@example.com"}));
+}
+
+#[test]
+fn test_auth_token_is_read_for_each_request() {
+    let client = StubClient::new(HttpResponse::new(204, ""));
+    let token = Arc::new(Mutex::new(None::<String>));
+    let current = token.clone();
+    let submitter = HttpSubmitter::put("/api/profile")
+        .auth_token(move || current.lock().unwrap().clone())
+        .with_client(client.clone());
+
+    block_on(submitter.send(&signup())).unwrap();
+    *token.lock().unwrap() = Some("fresh".to_string());
+    block_on(submitter.send(&signup())).unwrap();
+
+    let requests = client.requests();
+    assert_eq!(requests[0].method, HttpMethod::Put);
+    assert_eq!(requests[0].header("Authorization"), None);
+    assert_eq!(requests[1].header("Authorization"), Some("Bearer fresh"));
+}
+
+#[test]
+fn test_invalid_forms_are_not_sent() {
+    let client = StubClient::new(HttpResponse::new(200, ""));
+    let submitter = HttpSubmitter::post("/api/signups").with_client(client.clone());
+    let form = FormHandle::new(Signup {
+        name: String::new(),
+        ..signup()
+    });
+
+    assert!(block_on(submitter.submit(&form)).is_err());
+    assert!(client.requests().is_empty());
+}
+
+#[test]
+fn test_error_statuses_become_submission_errors() {
+    for status in [404, 500, 503] {
+        let client = StubClient::new(HttpResponse::new(status, "Unavailable"));
+        let submitter = HttpSubmitter::post("/api/signups").with_client(client);
+
+        let error = block_on(submitter.send(&signup())).unwrap_err();
+        assert_eq!(status_and_body(&error), (Some(status), Some("Unavailable")));
+        assert!(error.to_string().contains(&status.to_string()));
+    }
+}
+
+#[test]
+fn test_problem_details_are_shown_on_the_form() {
+    let body = json!({
+        "type": "https://example.com/probs/validation",
+        "title": "Your request is not valid",
+        "status": 422,
+        "invalid-params": [
+            {"name": "email", "reason": "is already registered"}
+        ]
+    });
+    let client = StubClient::new(
+        HttpResponse::new(422, body.to_string()).with_content_type("application/problem+json"),
+    );
+    let submitter = HttpSubmitter::post("/api/signups").with_client(client);
+    let form = FormHandle::new(signup());
+
+    let error = block_on(submitter.submit(&form)).unwrap_err();
+    assert_eq!(status_and_body(&error).0, Some(422));
+    assert!(error.to_string().contains("Your request is not valid"));
+
+    let errors = form.errors().get_untracked();
+    assert_eq!(
+        errors.get_field_error("email"),
+        Some(&vec!["is already registered".to_string()])
+    );
+    assert!(!form.is_submitting().get_untracked());
+}
+
+#[test]
+fn test_errors_map_becomes_field_errors() {
+    let errors = parse_error_body(
+        r#"{"message": "Invalid", "errors": {"name": ["is taken", "is too short"], "items.0.sku": "is unknown"}}"#,
+    );
+    assert_eq!(
+        errors.get_field_error("name"),
+        Some(&vec!["is taken".to_string(), "is too short".to_string()])
+    );
+    assert_eq!(
+        errors.get_field_error("items[0].sku"),
+        Some(&vec!["is unknown".to_string()])
+    );
+    assert!(errors.form_errors.is_empty());
+}
+
+#[test]
+fn test_problem_without_fields_becomes_form_error() {
+    let errors = parse_error_body(r#"{"title": "Conflict", "detail": "Signups are closed"}"#);
+    assert!(errors.field_errors.is_empty());
+    assert_eq!(errors.form_errors, vec!["Signups are closed".to_string()]);
+
+    assert!(!parse_error_body("<html>Bad gateway</html>").has_errors());
+    assert!(!parse_error_body("[1, 2]").has_errors());
+}
+
+#[test]
+fn test_network_failures_pass_through() {
+    let client = StubClient::failing(FormError::submission_error("timed out", None, None));
+    let submitter = HttpSubmitter::post("/api/signups").with_client(client);
+    let form = FormHandle::new(signup());
+
+    let error = block_on(submitter.submit(&form)).unwrap_err();
+    assert_eq!(status_and_body(&error), (None, None));
+    assert!(!form.errors().get_untracked().has_errors());
+    assert!(!form.is_submitting().get_untracked());
+}
+
+#[test]
+fn test_only_accepted_submissions_count_as_successful() {
+    let form = FormHandle::new(signup());
+    let successes = Arc::new(Mutex::new(0));
+    let count = successes.clone();
+    form.on_submit_success(move |_| *count.lock().unwrap() += 1);
+
+    let failing = HttpSubmitter::post("/api/signups").with_client(StubClient::new(
+        HttpResponse::new(503, "Service unavailable"),
+    ));
+    assert!(block_on(failing.submit(&form)).is_err());
+    let unreachable = HttpSubmitter::post("/api/signups").with_client(StubClient::failing(
+        FormError::submission_error("timed out", None, None),
+    ));
+    assert!(block_on(unreachable.submit(&form)).is_err());
+    assert_eq!(*successes.lock().unwrap(), 0);
+
+    let accepting = HttpSubmitter::post("/api/signups")
+        .with_client(StubClient::new(HttpResponse::new(201, "{}")));
+    block_on(accepting.submit(&form)).unwrap();
+    assert_eq!(*successes.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_fetch_is_unavailable_natively() {
+    let error = block_on(HttpSubmitter::post("/api/signups").send(&signup())).unwrap_err();
+    assert_eq!(status_and_body(&error), (None, None));
+}
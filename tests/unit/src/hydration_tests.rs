@@ -0,0 +1,157 @@
+//! Tests for handing form state from the server to the hydrating client
+
+use leptos::prelude::*;
+use leptos_forms_rs::core::{FieldValue, FormSnapshot};
+use leptos_forms_rs::submission::read_submission;
+use leptos_forms_rs::{use_field_is_touched, use_form, Form, FormHandle};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Signup {
+    #[form(required)]
+    name: String,
+    #[form(required, email)]
+    email: String,
+    age: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Login {
+    email: String,
+    #[form(sensitive)]
+    password: String,
+    #[form(sensitive)]
+    pin: u32,
+}
+
+fn signup() -> Signup {
+    Signup {
+        name: "Ada".to_string(),
+        email: "ada".to_string(),
+        age: Some(36),
+    }
+}
+
+#[test]
+fn test_fields_are_touched_once_left() {
+    let form = FormHandle::new(signup());
+    let touched = use_field_is_touched(&form, "email");
+    assert!(!touched.get_untracked());
+
+    form.mark_touched("email");
+    assert!(touched.get_untracked());
+    assert!(!form.field_touched("name").get_untracked());
+
+    // Editing keeps the touched fields, resetting forgets them
+    form.set_field_value("name", FieldValue::String("Grace".to_string()));
+    assert!(touched.get_untracked());
+    form.reset();
+    assert!(!touched.get_untracked());
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_form_is_touched_once_any_field_is_left() {
+    use leptos_forms_rs::use_field_touched;
+
+    let form = FormHandle::new(signup());
+    let touched = use_field_touched(&form);
+    assert!(!touched.get_untracked());
+
+    form.mark_touched("name");
+    assert!(touched.get_untracked());
+    assert!(form.is_touched().get_untracked());
+}
+
+#[test]
+fn test_snapshot_holds_values_errors_and_touched_fields() {
+    let form = FormHandle::new(signup());
+    let _ = form.validate();
+    form.mark_touched("email");
+
+    let snapshot = form.snapshot();
+    assert_eq!(snapshot.values, signup());
+    assert!(snapshot.errors.has_field_error("email"));
+    assert_eq!(snapshot.touched.into_iter().collect::<Vec<_>>(), ["email"]);
+}
+
+#[test]
+fn test_snapshot_survives_serialization() {
+    let form = FormHandle::new(signup());
+    let _ = form.validate();
+    form.mark_touched("name");
+    form.mark_touched("email");
+
+    let json = serde_json::to_string(&form.snapshot()).unwrap();
+    let snapshot: FormSnapshot<Signup> = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot, form.snapshot());
+}
+
+#[test]
+fn test_form_restored_from_snapshot_matches_the_original() {
+    let form = FormHandle::new(signup());
+    let _ = form.validate();
+    form.mark_touched("email");
+
+    let restored = FormHandle::from_snapshot(form.snapshot());
+    assert_eq!(restored.values().get_untracked(), signup());
+    assert_eq!(
+        restored.errors().get_untracked(),
+        form.errors().get_untracked()
+    );
+    assert!(restored.field_touched("email").get_untracked());
+    assert!(!restored.is_valid().get_untracked());
+}
+
+#[test]
+fn test_hydrated_form_outside_hydration_runs_init() {
+    let body = "name=&email=ada%40example.com&age=36";
+    let form = FormHandle::hydrated(|| {
+        read_submission::<Signup>(Some("application/x-www-form-urlencoded"), body.as_bytes())
+            .into_form()
+    });
+
+    assert_eq!(form.values().get_untracked().age, Some(36));
+    assert!(form.errors().get_untracked().has_field_error("name"));
+}
+
+#[test]
+fn test_hydrated_form_outside_hydration_keeps_sensitive_values() {
+    // Only the state written into the page for hydration leaves them out
+    let form = FormHandle::hydrated(|| {
+        FormHandle::new(Login {
+            email: "ada@example.com".to_string(),
+            password: "hunter2".to_string(),
+            pin: 1234,
+        })
+    });
+
+    let values = form.values().get_untracked();
+    assert_eq!(values.email, "ada@example.com");
+    assert_eq!(values.password, "hunter2");
+    assert_eq!(values.pin, 1234);
+}
+
+#[test]
+fn test_hydrated_form_is_the_one_init_built() {
+    let submitted = Arc::new(Mutex::new(Vec::new()));
+    let seen = submitted.clone();
+    let form = FormHandle::hydrated(move || {
+        let form = FormHandle::new(signup());
+        form.on_submit(move |values: &Signup| seen.lock().unwrap().push(values.name.clone()));
+        form
+    });
+
+    form.set_field_value("email", FieldValue::String("ada@example.com".to_string()));
+    assert!(form.submit().is_ok());
+    assert_eq!(*submitted.lock().unwrap(), ["Ada"]);
+}
+
+#[test]
+fn test_use_form_starts_from_initial_values() {
+    let (form, _submit, _reset) = use_form(signup());
+    assert_eq!(form.values().get_untracked(), signup());
+    assert!(form.errors().get_untracked().is_empty());
+    assert!(form.state().get_untracked().touched.is_empty());
+}
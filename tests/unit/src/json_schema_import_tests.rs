@@ -0,0 +1,233 @@
+//! Tests for building a runtime `FormSchema` from a JSON Schema document
+
+use leptos_forms_rs::core::{FieldType, FieldValue, JsonSchemaImportError};
+use leptos_forms_rs::validation::Validator;
+use leptos_forms_rs::{Form, FormError, FormSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+fn customer_schema() -> serde_json::Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Customer",
+        "type": "object",
+        "required": ["email", "name"],
+        "properties": {
+            "name": { "type": "string", "minLength": 2, "maxLength": 80, "title": "Full name" },
+            "email": { "type": "string", "format": "email" },
+            "website": { "type": ["string", "null"], "format": "uri" },
+            "born_on": { "type": "string", "format": "date" },
+            "last_seen": { "type": "string", "format": "date-time" },
+            "postcode": { "type": "string", "pattern": "^[0-9]{5}$" },
+            "age": { "type": "integer", "minimum": 18, "maximum": 120 },
+            "discount": { "type": "number", "default": 0.5 },
+            "tier": { "type": "string", "enum": ["free", "pro"] },
+            "active": { "type": "boolean" },
+            "billing_address": {
+                "type": "object",
+                "required": ["city"],
+                "properties": {
+                    "city": { "type": "string" }
+                }
+            },
+            "contacts": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "title": "Contact",
+                    "properties": {
+                        "phone": { "type": "string" }
+                    }
+                }
+            },
+            "labels": {
+                "type": "array",
+                "uniqueItems": true,
+                "items": { "type": "string", "enum": ["vip", "trial"] }
+            }
+        }
+    })
+}
+
+#[test]
+fn test_import_scalar_types_and_formats() {
+    let schema = FormSchema::from_json_schema(&customer_schema()).unwrap();
+    assert_eq!(schema.name, "Customer");
+    // Fields come in the order of the document, not sorted by name
+    assert_eq!(schema.field_metadata[0].name, "name");
+    assert_eq!(schema.field_metadata[1].name, "email");
+
+    let name = schema.get_field("name").unwrap();
+    assert!(name.is_required);
+    assert!(matches!(name.field_type, FieldType::Text));
+    assert_eq!(
+        name.validators,
+        vec![
+            Validator::Required,
+            Validator::MinLength(2),
+            Validator::MaxLength(80)
+        ]
+    );
+    assert_eq!(name.attributes.get("label").unwrap(), "Full name");
+
+    let email = schema.get_field("email").unwrap();
+    assert!(matches!(email.field_type, FieldType::Email));
+    assert_eq!(
+        email.validators,
+        vec![Validator::Required, Validator::Email]
+    );
+
+    let website = schema.get_field("website").unwrap();
+    assert!(!website.is_required);
+    assert_eq!(website.validators, vec![Validator::Url]);
+
+    assert!(matches!(
+        schema.get_field("born_on").unwrap().field_type,
+        FieldType::Date
+    ));
+    assert!(matches!(
+        schema.get_field("last_seen").unwrap().field_type,
+        FieldType::DateTime
+    ));
+    assert_eq!(
+        schema.get_field("postcode").unwrap().validators,
+        vec![Validator::Pattern("^[0-9]{5}$".to_string())]
+    );
+    assert!(matches!(
+        schema.get_field("active").unwrap().field_type,
+        FieldType::Boolean
+    ));
+}
+
+#[test]
+fn test_import_numbers_enums_and_defaults() {
+    let schema = FormSchema::from_json_schema(&customer_schema()).unwrap();
+
+    let age = schema.get_field("age").unwrap();
+    match &age.field_type {
+        FieldType::Number(number) => {
+            assert_eq!(number.min, Some(18.0));
+            assert_eq!(number.max, Some(120.0));
+            assert_eq!(number.step, Some(1.0));
+        }
+        other => panic!("Expected a number field, got {:?}", other),
+    }
+    assert_eq!(
+        age.validators,
+        vec![Validator::Min(18.0), Validator::Max(120.0)]
+    );
+
+    let discount = schema.get_field("discount").unwrap();
+    assert_eq!(discount.default_value, Some(FieldValue::Number(0.5)));
+
+    match &schema.get_field("tier").unwrap().field_type {
+        FieldType::Select(options) => {
+            let values: Vec<&str> = options.iter().map(|o| o.value.as_str()).collect();
+            assert_eq!(values, vec!["free", "pro"]);
+        }
+        other => panic!("Expected a select field, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_import_nested_objects_and_arrays() {
+    let schema = FormSchema::from_json_schema(&customer_schema()).unwrap();
+
+    let billing = schema.get_field("billing_address").unwrap();
+    assert!(matches!(&billing.field_type, FieldType::Nested(name) if name == "BillingAddress"));
+    assert_eq!(billing.nested_fields.len(), 1);
+    assert!(billing.nested_fields[0].is_required);
+
+    let contacts = schema.get_field("contacts").unwrap();
+    match &contacts.field_type {
+        FieldType::Array(inner) => {
+            assert!(matches!(inner.as_ref(), FieldType::Nested(name) if name == "Contact"))
+        }
+        other => panic!("Expected an array field, got {:?}", other),
+    }
+    assert_eq!(contacts.nested_fields[0].name, "phone");
+
+    assert!(matches!(
+        schema.get_field("labels").unwrap().field_type,
+        FieldType::MultiSelect(_)
+    ));
+}
+
+#[test]
+fn test_import_reports_unsupported_keywords() {
+    let document = json!({
+        "type": "object",
+        "properties": {
+            "code": { "type": "string", "format": "ipv4" },
+            "choice": { "oneOf": [{ "type": "string" }, { "type": "integer" }] },
+            "count": { "type": "integer", "exclusiveMinimum": 0 },
+            "items": { "type": "array", "minItems": 1, "items": { "type": "string" } }
+        },
+        "patternProperties": { "^x-": { "type": "string" } }
+    });
+
+    let error = FormSchema::from_json_schema(&document).unwrap_err();
+    let keywords: Vec<(&str, &str)> = error
+        .issues
+        .iter()
+        .map(|issue| (issue.path.as_str(), issue.keyword.as_str()))
+        .collect();
+
+    assert!(keywords.contains(&("", "patternProperties")));
+    assert!(keywords.contains(&("/properties/code", "format")));
+    assert!(keywords.contains(&("/properties/choice", "type")));
+    assert!(keywords.contains(&("/properties/count", "exclusiveMinimum")));
+    assert!(keywords.contains(&("/properties/items", "minItems")));
+
+    let message = error.to_string();
+    assert!(message.contains("at /properties/count: `exclusiveMinimum`"));
+
+    let form_error: FormError = error.into();
+    assert!(matches!(form_error, FormError::ConfigurationError { .. }));
+}
+
+#[test]
+fn test_import_rejects_non_object_root() {
+    let error: JsonSchemaImportError =
+        FormSchema::from_json_schema(&json!({ "type": "string" })).unwrap_err();
+    assert_eq!(error.issues[0].keyword, "type");
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Address {
+    #[form(required)]
+    city: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct SignupForm {
+    #[form(required, email)]
+    email: String,
+    #[form(min = 18)]
+    age: u32,
+    address: Address,
+    previous: Vec<Address>,
+}
+
+#[test]
+fn test_exported_schema_round_trips() {
+    let exported = SignupForm::schema().to_json_schema();
+    let imported = FormSchema::from_json_schema(&exported).unwrap();
+
+    assert_eq!(imported.name, "SignupForm");
+    let names: Vec<&str> = imported
+        .field_metadata
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["email", "age", "address", "previous"]);
+
+    let email = imported.get_field("email").unwrap();
+    assert!(email.is_required);
+    assert!(matches!(email.field_type, FieldType::Email));
+    assert!(matches!(
+        imported.get_field("address").unwrap().field_type,
+        FieldType::Nested(ref name) if name == "Address"
+    ));
+    assert_eq!(imported.to_json_schema(), exported);
+}
@@ -6,13 +6,23 @@
 // Test modules
 mod additional_input_types_tests;
 mod advanced_validation_tests;
+mod async_persistence_tests;
+mod auto_form_tests;
+mod computed_field_tests;
 mod conditional_validation_hook_tests;
 mod conditional_validation_tests;
+mod cross_tab_sync_tests;
 mod devtools_integration_tests;
+mod draft_manager_tests;
+mod draft_versioning_tests;
+mod dynamic_form_tests;
 mod enhanced_field_arrays_advanced_tests;
 mod enhanced_field_arrays_tests;
+mod field_visibility_tests;
 mod form_component_tests;
 mod form_components;
+mod form_derive_tests;
+mod form_encoding_tests;
 mod form_field_component_tests;
 mod form_handle;
 mod form_handle_comprehensive;
@@ -20,12 +30,23 @@ mod form_hooks;
 mod form_persistence_tests;
 mod form_types;
 mod form_validation;
+mod http_submission_tests;
+mod hydration_tests;
 mod input_component_tests;
+mod json_schema_import_tests;
 mod minimal_test;
 mod multi_step_forms_tests;
 mod multi_step_forms_wizard_tests;
 mod performance_benchmarking_tests;
+mod progressive_enhancement_tests;
 mod real_time_validation_tests;
+mod schema_export_tests;
+mod select_options_tests;
+mod sensitive_field_tests;
+mod server_action_form_tests;
+mod server_validation_tests;
 mod stress_testing;
+mod submission_protection_tests;
+mod submission_queue_tests;
 mod validation_engine_tests;
 mod validation_rules_engine_tests;
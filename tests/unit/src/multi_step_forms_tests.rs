@@ -53,6 +53,7 @@ impl Form for MultiStepTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::MinLength(2)],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -63,6 +64,7 @@ impl Form for MultiStepTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::MinLength(2)],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -73,6 +75,7 @@ impl Form for MultiStepTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::Email],
+            ..Default::default()
         });
 
         // Step 2 fields
@@ -87,6 +90,7 @@ impl Form for MultiStepTestForm {
                 Validator::Required,
                 Validator::Pattern(r"^\d{10}$".to_string()),
             ],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -97,6 +101,7 @@ impl Form for MultiStepTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::MinLength(10)],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -107,6 +112,7 @@ impl Form for MultiStepTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::MinLength(2)],
+            ..Default::default()
         });
 
         // Step 3 fields
@@ -118,6 +124,7 @@ impl Form for MultiStepTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -128,6 +135,7 @@ impl Form for MultiStepTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -138,6 +146,7 @@ impl Form for MultiStepTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required],
+            ..Default::default()
         });
 
         metadata
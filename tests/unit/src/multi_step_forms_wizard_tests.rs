@@ -53,6 +53,7 @@ impl Form for WizardTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::MinLength(2)],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -71,6 +72,7 @@ impl Form for WizardTestForm {
                 Validator::Min(18.0),
                 Validator::Max(120.0),
             ],
+            ..Default::default()
         });
 
         // Step 2 fields
@@ -82,6 +84,7 @@ impl Form for WizardTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::Email],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -95,6 +98,7 @@ impl Form for WizardTestForm {
                 Validator::Required,
                 Validator::Pattern(r"^\d{10}$".to_string()),
             ],
+            ..Default::default()
         });
 
         // Step 3 fields
@@ -106,6 +110,7 @@ impl Form for WizardTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -116,6 +121,7 @@ impl Form for WizardTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required],
+            ..Default::default()
         });
 
         // Step 4 fields
@@ -127,6 +133,7 @@ impl Form for WizardTestForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required],
+            ..Default::default()
         });
 
         metadata
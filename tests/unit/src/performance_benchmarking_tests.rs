@@ -31,6 +31,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_2".to_string(),
@@ -40,6 +41,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_3".to_string(),
@@ -49,6 +51,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_4".to_string(),
@@ -58,6 +61,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_5".to_string(),
@@ -67,6 +71,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_6".to_string(),
@@ -76,6 +81,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_7".to_string(),
@@ -85,6 +91,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_8".to_string(),
@@ -94,6 +101,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_9".to_string(),
@@ -103,6 +111,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "field_10".to_string(),
@@ -112,6 +121,7 @@ impl Form for PerformanceTestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
@@ -0,0 +1,113 @@
+//! Tests for forms that are posted and re-rendered without JavaScript
+
+use leptos::prelude::*;
+use leptos_forms_rs::components::has_file_fields;
+use leptos_forms_rs::core::{input_name, FileData};
+use leptos_forms_rs::submission::{encode_urlencoded, form_parts, read_submission};
+use leptos_forms_rs::Form;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Contact {
+    #[form(required, email)]
+    email: String,
+    phone: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Registration {
+    #[form(required)]
+    name: String,
+    age: Option<u32>,
+    terms: bool,
+    contacts: Vec<Contact>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Application {
+    name: String,
+    attachments: Vec<FileData>,
+}
+
+const URLENCODED: Option<&str> = Some("application/x-www-form-urlencoded");
+
+fn registration() -> Registration {
+    Registration {
+        name: "Ada".to_string(),
+        age: Some(36),
+        terms: true,
+        contacts: vec![Contact {
+            email: "ada@example.com".to_string(),
+            phone: String::new(),
+        }],
+    }
+}
+
+#[test]
+fn test_input_names_use_bracket_notation() {
+    assert_eq!(input_name("name"), "name");
+    assert_eq!(input_name("billing_address.city"), "billing_address[city]");
+    assert_eq!(input_name("contacts[1].email"), "contacts[1][email]");
+    assert_eq!(input_name("matrix[0][2]"), "matrix[0][2]");
+}
+
+#[test]
+fn test_input_names_match_the_encoded_body() {
+    let names: Vec<String> = form_parts(&registration())
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert!(names.contains(&input_name("contacts[0].email")));
+    assert!(names.contains(&input_name("contacts[0].phone")));
+}
+
+#[test]
+fn test_file_fields_need_multipart() {
+    assert!(has_file_fields(&Application::field_metadata()));
+    assert!(!has_file_fields(&Registration::field_metadata()));
+}
+
+#[test]
+fn test_valid_submission_reads_back() {
+    let body = encode_urlencoded(&registration());
+    let submission = read_submission::<Registration>(URLENCODED, body.as_bytes());
+    assert!(submission.is_valid());
+    assert_eq!(submission.into_result(), Ok(registration()));
+}
+
+#[test]
+fn test_invalid_submission_keeps_values_and_errors() {
+    let body = "name=&age=36&terms=true&contacts[0][email]=nope&contacts[0][phone]=555";
+    let submission = read_submission::<Registration>(URLENCODED, body.as_bytes());
+
+    assert!(!submission.is_valid());
+    assert!(submission.errors.has_field_error("name"));
+    assert!(submission.errors.has_field_error("contacts[0].email"));
+    assert_eq!(submission.values.age, Some(36));
+    assert!(submission.values.terms);
+    assert_eq!(submission.values.contacts[0].phone, "555");
+}
+
+#[test]
+fn test_unreadable_values_are_reported_on_their_field() {
+    let body = "name=Ada&age=old&terms=true";
+    let submission = read_submission::<Registration>(URLENCODED, body.as_bytes());
+
+    assert_eq!(
+        submission.errors.get_field_error("age"),
+        Some(&vec!["Invalid integer: old".to_string()])
+    );
+    assert_eq!(submission.values.name, "Ada");
+    assert_eq!(submission.values.age, None);
+    assert!(submission.values.terms);
+}
+
+#[test]
+fn test_submission_renders_into_a_form() {
+    let body = "name=&age=36";
+    let form = read_submission::<Registration>(URLENCODED, body.as_bytes()).into_form();
+
+    assert_eq!(form.values().get_untracked().age, Some(36));
+    assert!(form.errors().get_untracked().has_field_error("name"));
+    assert!(!form.is_valid().get_untracked());
+}
@@ -22,6 +22,7 @@ impl Form for TestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             leptos_forms_rs::core::FieldMetadata {
                 name: "username".to_string(),
@@ -31,6 +32,7 @@ impl Form for TestForm {
                 default_value: Some(FieldValue::String("".to_string())),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }
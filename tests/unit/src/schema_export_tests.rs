@@ -0,0 +1,180 @@
+//! Tests for exporting form schemas as JSON Schema and TypeScript declarations
+
+use leptos_forms_rs::core::{FieldType, SelectOption, JSON_SCHEMA_DIALECT};
+use leptos_forms_rs::{FieldMetadata, Form, FormSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Address {
+    #[form(required)]
+    street: String,
+    #[form(pattern = "^[0-9]{5}$")]
+    zip: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+#[serde(rename_all = "kebab-case")]
+struct AccountForm {
+    #[form(required, email)]
+    email: String,
+    #[form(min_length = 8, max_length = 64)]
+    display_name: String,
+    #[form(min = 18, max = 130)]
+    age: u32,
+    score: f64,
+    newsletter: bool,
+    website: Option<String>,
+    address: Address,
+    previous_addresses: Vec<Address>,
+    tags: Vec<String>,
+}
+
+fn plan_schema() -> FormSchema {
+    FormSchema {
+        name: "PlanForm".to_string(),
+        field_metadata: vec![FieldMetadata {
+            name: "plan".to_string(),
+            field_type: FieldType::Select(vec![
+                SelectOption {
+                    value: "basic".to_string(),
+                    label: "Basic".to_string(),
+                    disabled: false,
+                },
+                SelectOption {
+                    value: "pro".to_string(),
+                    label: "Pro".to_string(),
+                    disabled: false,
+                },
+            ]),
+            is_required: true,
+            ..Default::default()
+        }],
+    }
+}
+
+#[test]
+fn test_json_schema_document() {
+    let schema = AccountForm::schema().to_json_schema();
+
+    assert_eq!(schema["$schema"], json!(JSON_SCHEMA_DIALECT));
+    assert_eq!(schema["title"], json!("AccountForm"));
+    assert_eq!(schema["type"], json!("object"));
+    assert_eq!(schema["required"], json!(["email"]));
+    assert_eq!(schema["additionalProperties"], json!(false));
+
+    let properties = &schema["properties"];
+    assert_eq!(
+        properties["email"],
+        json!({ "type": "string", "format": "email", "minLength": 1 })
+    );
+    assert_eq!(properties["display-name"]["minLength"], json!(8));
+    assert_eq!(properties["display-name"]["maxLength"], json!(64));
+    assert_eq!(properties["age"]["type"], json!(["integer", "null"]));
+    assert_eq!(properties["age"]["minimum"], json!(18.0));
+    assert_eq!(properties["age"]["maximum"], json!(130.0));
+    assert_eq!(properties["score"]["type"], json!(["number", "null"]));
+    assert_eq!(properties["newsletter"]["type"], json!(["boolean", "null"]));
+    assert_eq!(properties["website"]["type"], json!(["string", "null"]));
+}
+
+#[test]
+fn test_json_schema_nested_and_arrays() {
+    let schema = AccountForm::schema().to_json_schema();
+    let properties = &schema["properties"];
+
+    let address = &properties["address"];
+    assert_eq!(address["type"], json!(["object", "null"]));
+    assert_eq!(address["title"], json!("Address"));
+    assert_eq!(address["required"], json!(["street"]));
+    assert_eq!(address["properties"]["zip"]["pattern"], json!("^[0-9]{5}$"));
+
+    let previous = &properties["previous-addresses"];
+    assert_eq!(previous["type"], json!(["array", "null"]));
+    assert_eq!(previous["items"]["title"], json!("Address"));
+    assert_eq!(
+        previous["items"]["properties"]["street"]["type"],
+        json!("string")
+    );
+
+    assert_eq!(properties["tags"]["items"], json!({ "type": "string" }));
+}
+
+#[test]
+fn test_json_schema_select_enum() {
+    let schema = plan_schema().to_json_schema();
+    assert_eq!(
+        schema["properties"]["plan"],
+        json!({ "type": "string", "enum": ["basic", "pro"] })
+    );
+}
+
+#[test]
+fn test_json_schema_optional_select_accepts_null() {
+    let mut schema = plan_schema();
+    schema.field_metadata[0].is_required = false;
+    let exported = schema.to_json_schema();
+    assert_eq!(
+        exported["properties"]["plan"],
+        json!({ "type": ["string", "null"], "enum": ["basic", "pro", null] })
+    );
+
+    let imported = FormSchema::from_json_schema(&exported).unwrap();
+    match &imported.get_field("plan").unwrap().field_type {
+        FieldType::Select(options) => assert_eq!(options.len(), 2),
+        other => panic!("expected a select, got {:?}", other),
+    }
+    assert_eq!(imported.to_json_schema(), exported);
+}
+
+#[test]
+fn test_json_schema_property_names_match_payload() {
+    let form = AccountForm::default_values();
+    let payload = serde_json::to_value(&form).unwrap();
+    let schema = AccountForm::schema().to_json_schema();
+
+    let mut payload_keys: Vec<_> = payload.as_object().unwrap().keys().collect();
+    let mut schema_keys: Vec<_> = schema["properties"].as_object().unwrap().keys().collect();
+    payload_keys.sort();
+    schema_keys.sort();
+    assert_eq!(payload_keys, schema_keys);
+}
+
+#[test]
+fn test_typescript_declarations() {
+    let declarations = AccountForm::schema().to_typescript();
+
+    assert!(declarations.contains("export interface AccountForm {\n"));
+    assert!(declarations.contains("  email: string;\n"));
+    assert!(declarations.contains("  \"display-name\"?: string | null;\n"));
+    assert!(declarations.contains("  age?: number | null;\n"));
+    assert!(declarations.contains("  newsletter?: boolean | null;\n"));
+    assert!(declarations.contains("  address?: Address | null;\n"));
+    assert!(declarations.contains("  \"previous-addresses\"?: Address[] | null;\n"));
+    assert!(declarations.contains("  tags?: string[] | null;\n"));
+
+    // Nested forms are declared once
+    assert_eq!(
+        declarations.matches("export interface Address {").count(),
+        1
+    );
+    assert!(declarations.contains("  street: string;\n"));
+
+    let plan = plan_schema().to_typescript();
+    assert!(plan.contains("  plan: \"basic\" | \"pro\";\n"));
+}
+
+#[test]
+fn test_write_schema_files() {
+    let dir = std::env::temp_dir().join(format!("leptos-forms-schema-{}", std::process::id()));
+    AccountForm::schema().write_schema_files(&dir).unwrap();
+
+    let json = std::fs::read_to_string(dir.join("AccountForm.schema.json")).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, AccountForm::schema().to_json_schema());
+
+    let declarations = std::fs::read_to_string(dir.join("AccountForm.d.ts")).unwrap();
+    assert_eq!(declarations, AccountForm::schema().to_typescript());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,202 @@
+//! Tests for dependent, possibly asynchronous option sources of select fields
+
+use futures::channel::oneshot;
+use futures::executor::block_on;
+use futures::{pin_mut, poll};
+use leptos::prelude::*;
+use leptos_forms_rs::core::{FieldOptions, FieldValue, FormHandle, OptionsProvider, SelectOption};
+use leptos_forms_rs::{Form, FormError};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Location {
+    country: String,
+    state: String,
+    city: String,
+    languages: Vec<String>,
+}
+
+fn option(value: &str) -> SelectOption {
+    SelectOption {
+        value: value.to_string(),
+        label: value.to_uppercase(),
+        disabled: false,
+    }
+}
+
+fn location(country: &str, state: &str, city: &str) -> Location {
+    Location {
+        country: country.to_string(),
+        state: state.to_string(),
+        city: city.to_string(),
+        languages: Vec::new(),
+    }
+}
+
+fn states() -> OptionsProvider {
+    OptionsProvider::from_fn("state", |inputs| match &inputs[0] {
+        FieldValue::String(country) if country == "us" => vec![option("ca"), option("ny")],
+        FieldValue::String(country) if country == "de" => vec![option("by"), option("be")],
+        _ => Vec::new(),
+    })
+    .depends_on(["country"])
+}
+
+fn string(value: &str) -> FieldValue {
+    FieldValue::String(value.to_string())
+}
+
+#[test]
+fn test_options_load_and_are_cached() {
+    let loads = Arc::new(AtomicUsize::new(0));
+    let counter = loads.clone();
+    let cities = FieldOptions::new(
+        OptionsProvider::new("city", move |inputs| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                Ok(match &inputs[1] {
+                    FieldValue::String(state) if state == "ca" => vec![option("la"), option("sf")],
+                    _ => vec![option("nyc")],
+                })
+            }
+        })
+        .depends_on(["country", "state"]),
+    );
+    let form = FormHandle::new(location("us", "ca", ""));
+
+    block_on(cities.refresh(&form));
+    assert_eq!(
+        cities.options().get_untracked(),
+        vec![option("la"), option("sf")]
+    );
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    form.set_field_value("state", string("ny"));
+    block_on(cities.refresh(&form));
+    form.set_field_value("state", string("ca"));
+    block_on(cities.refresh(&form));
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+    assert_eq!(cities.options().get_untracked().len(), 2);
+
+    cities.clear_cache();
+    block_on(cities.refresh(&form));
+    assert_eq!(loads.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_cascade_clears_invalid_selections() {
+    let form = FormHandle::new(location("us", "ca", "la"));
+    let states = FieldOptions::new(states());
+    let cities = FieldOptions::new(
+        OptionsProvider::from_fn("city", |inputs| match &inputs[0] {
+            FieldValue::String(state) if state == "ca" => vec![option("la")],
+            _ => vec![option("munich")],
+        })
+        .depends_on(["state"]),
+    );
+
+    block_on(states.refresh(&form));
+    block_on(cities.refresh(&form));
+    assert_eq!(form.get_field_value("state"), Some(string("ca")));
+    assert_eq!(form.get_field_value("city"), Some(string("la")));
+
+    form.set_field_value("country", string("de"));
+    block_on(states.refresh(&form));
+    assert_eq!(form.get_field_value("state"), Some(string("")));
+    assert_eq!(
+        states.options().get_untracked(),
+        vec![option("by"), option("be")]
+    );
+
+    // With its dependency empty the city select has nothing to offer
+    block_on(cities.refresh(&form));
+    assert!(cities.options().get_untracked().is_empty());
+    assert_eq!(form.get_field_value("city"), Some(string("")));
+}
+
+#[test]
+fn test_multi_select_keeps_offered_items() {
+    let mut initial = location("us", "", "");
+    initial.languages = vec!["en".to_string(), "de".to_string()];
+    let form = FormHandle::new(initial);
+    let languages = FieldOptions::new(
+        OptionsProvider::from_fn("languages", |_| vec![option("en"), option("es")])
+            .depends_on(["country"]),
+    );
+
+    block_on(languages.refresh(&form));
+    assert_eq!(form.values().get_untracked().languages, vec!["en"]);
+}
+
+#[test]
+fn test_loading_and_error_signals() {
+    let (sender, receiver) = oneshot::channel::<Result<Vec<SelectOption>, FormError>>();
+    let receiver = Arc::new(Mutex::new(Some(receiver)));
+    let states = FieldOptions::new(
+        OptionsProvider::new("state", move |_| {
+            let receiver = receiver.lock().unwrap().take();
+            async move {
+                match receiver {
+                    Some(receiver) => receiver.await.unwrap(),
+                    None => Ok(vec![option("ca")]),
+                }
+            }
+        })
+        .depends_on(["country"]),
+    );
+    let form = FormHandle::new(location("us", "ca", ""));
+
+    block_on(async {
+        let refresh = states.refresh(&form);
+        pin_mut!(refresh);
+        assert!(poll!(&mut refresh).is_pending());
+        assert!(states.is_loading().get_untracked());
+
+        sender
+            .send(Err(FormError::unknown("service unavailable", None)))
+            .unwrap();
+        refresh.await;
+    });
+    assert!(!states.is_loading().get_untracked());
+    assert!(states.error().get_untracked().is_some());
+    // A failed load leaves the selection alone
+    assert_eq!(form.get_field_value("state"), Some(string("ca")));
+
+    block_on(states.refresh(&form));
+    assert!(states.error().get_untracked().is_none());
+    assert_eq!(states.options().get_untracked(), vec![option("ca")]);
+}
+
+#[test]
+fn test_superseded_loads_are_dropped() {
+    let (sender, receiver) = oneshot::channel::<Result<Vec<SelectOption>, FormError>>();
+    let receiver = Arc::new(Mutex::new(Some(receiver)));
+    let states = FieldOptions::new(
+        OptionsProvider::new("state", move |_| {
+            let receiver = receiver.lock().unwrap().take();
+            async move {
+                match receiver {
+                    Some(receiver) => receiver.await.unwrap(),
+                    None => Ok(vec![option("by")]),
+                }
+            }
+        })
+        .depends_on(["country"]),
+    );
+    let form = FormHandle::new(location("us", "", ""));
+
+    block_on(async {
+        let slow = states.refresh(&form);
+        pin_mut!(slow);
+        assert!(poll!(&mut slow).is_pending());
+
+        form.set_field_value("country", string("de"));
+        states.refresh(&form).await;
+
+        sender.send(Ok(vec![option("ca")])).unwrap();
+        slow.await;
+    });
+    assert_eq!(states.options().get_untracked(), vec![option("by")]);
+}
@@ -0,0 +1,199 @@
+//! Tests for sensitive fields in persistence, analytics and devtools
+
+use leptos_forms_rs::core::{
+    redacted_form_data, sensitive_field_paths, FieldEncryptor, FieldType, FieldValue,
+    FormAnalytics, FormHandle, FormPersistence, MemoryPersistence, REDACTED,
+};
+use leptos_forms_rs::devtools::DebugUtilities;
+use leptos_forms_rs::validation::ValidationErrors;
+use leptos_forms_rs::{FieldMetadata, Form, FormSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Card {
+    holder: String,
+    #[form(sensitive)]
+    number: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Application {
+    name: String,
+    #[form(sensitive)]
+    ssn: String,
+    #[form(sensitive)]
+    pin: u32,
+    cards: Vec<Card>,
+}
+
+fn application() -> Application {
+    Application {
+        name: "Ada".to_string(),
+        ssn: "123-45-6789".to_string(),
+        pin: 4321,
+        cards: vec![Card {
+            holder: "Ada".to_string(),
+            number: "4111111111111111".to_string(),
+        }],
+    }
+}
+
+/// Reverses the text, which is enough to tell encrypted values apart
+struct Reverse;
+
+impl FieldEncryptor for Reverse {
+    fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        Ok(format!(
+            "enc:{}",
+            plaintext.chars().rev().collect::<String>()
+        ))
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> Result<String, String> {
+        let reversed = ciphertext.strip_prefix("enc:").ok_or("not encrypted")?;
+        Ok(reversed.chars().rev().collect())
+    }
+}
+
+#[test]
+fn test_sensitive_fields_in_metadata() {
+    let metadata = Application::field_metadata();
+    let field = |name: &str| metadata.iter().find(|f| f.name == name).unwrap();
+    assert!(field("ssn").sensitive);
+    assert!(field("ssn").is_sensitive());
+    assert!(!field("name").is_sensitive());
+    assert!(field("cards").nested_fields[1].is_sensitive());
+
+    let password = FieldMetadata {
+        name: "password".to_string(),
+        field_type: FieldType::Password,
+        ..Default::default()
+    };
+    assert!(!password.sensitive);
+    assert!(password.is_sensitive());
+
+    // The flag only appears in serialized schemas when set
+    let exported = serde_json::to_value(Application::schema()).unwrap();
+    assert_eq!(exported["field_metadata"][1]["sensitive"], true);
+    assert!(exported["field_metadata"][0].get("sensitive").is_none());
+}
+
+#[test]
+fn test_sensitive_paths_and_redaction() {
+    let form = application();
+    assert_eq!(
+        sensitive_field_paths(&form),
+        vec!["ssn", "pin", "cards[0].number"]
+    );
+
+    let data = redacted_form_data(&form);
+    let redacted = FieldValue::String(REDACTED.to_string());
+    assert_eq!(data["name"], FieldValue::String("Ada".to_string()));
+    assert_eq!(data["ssn"], redacted);
+    assert_eq!(data["pin"], redacted);
+    let FieldValue::Array(cards) = &data["cards"] else {
+        panic!("expected cards");
+    };
+    let FieldValue::Object(card) = &cards[0] else {
+        panic!("expected a card");
+    };
+    assert_eq!(card["number"], redacted);
+    assert_eq!(card["holder"], FieldValue::String("Ada".to_string()));
+}
+
+#[test]
+fn test_sensitive_fields_are_left_out_of_drafts() {
+    let storage = MemoryPersistence::new("application");
+    storage.save(&application()).unwrap();
+
+    let raw = storage.raw().unwrap();
+    assert!(!raw.contains("123-45-6789"));
+    assert!(!raw.contains("4321"));
+    assert!(!raw.contains("4111111111111111"));
+
+    let restored: Application = storage.load().unwrap().unwrap();
+    assert_eq!(restored.name, "Ada");
+    assert_eq!(restored.ssn, "");
+    assert_eq!(restored.pin, 0);
+    assert_eq!(restored.cards[0].holder, "Ada");
+    assert_eq!(restored.cards[0].number, "");
+}
+
+#[test]
+fn test_sensitive_fields_can_be_stored_encrypted() {
+    let storage = MemoryPersistence::new("application").with_encryptor(Reverse);
+    storage.save(&application()).unwrap();
+
+    let raw = storage.raw().unwrap();
+    assert!(!raw.contains("123-45-6789"));
+    let saved: Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(saved["values"]["ssn"], "");
+    assert!(saved["sealed"]["ssn"].as_str().unwrap().starts_with("enc:"));
+    assert_eq!(storage.load(), Ok(Some(application())));
+
+    // Without the encryptor the values stay blank rather than failing
+    let plain = MemoryPersistence::new("application");
+    plain.set_raw(raw);
+    let restored: Application = plain.load().unwrap().unwrap();
+    assert_eq!(restored.ssn, "");
+    assert_eq!(restored.name, "Ada");
+}
+
+#[test]
+fn test_devtools_exports_are_redacted() {
+    let handle = FormHandle::new(application());
+    let exported = DebugUtilities::export_form_data(&handle);
+    assert!(exported.contains("Ada"));
+    assert!(exported.contains(REDACTED));
+    assert!(!exported.contains("123-45-6789"));
+    assert!(!exported.contains("4111111111111111"));
+
+    let snapshot = DebugUtilities::create_form_snapshot(&handle);
+    assert_eq!(
+        snapshot.field_values["ssn"],
+        FieldValue::String(REDACTED.to_string())
+    );
+}
+
+#[derive(Default)]
+struct RecordingAnalytics {
+    tracked: Mutex<Vec<HashMap<String, FieldValue>>>,
+}
+
+impl FormAnalytics<Application> for RecordingAnalytics {
+    fn track_view(&self, _form_name: &str) {}
+
+    fn track_field_interaction(&self, _form_name: &str, _field_name: &str, _action: &str) {}
+
+    fn track_submission(&self, _form_name: &str, _success: bool) {}
+
+    fn track_validation_errors(&self, _form_name: &str, _errors: &ValidationErrors) {}
+
+    fn track_form_data(&self, _form_name: &str, data: &HashMap<String, FieldValue>) {
+        self.tracked.lock().unwrap().push(data.clone());
+    }
+}
+
+#[test]
+fn test_analytics_receive_redacted_values() {
+    let analytics = RecordingAnalytics::default();
+    analytics.track_values("Application", &application());
+
+    let tracked = analytics.tracked.lock().unwrap();
+    assert_eq!(tracked[0]["ssn"], FieldValue::String(REDACTED.to_string()));
+    assert_eq!(tracked[0]["name"], FieldValue::String("Ada".to_string()));
+}
+
+#[test]
+fn test_sensitive_fields_in_json_schema() {
+    let schema = Application::schema().to_json_schema();
+    assert_eq!(schema["properties"]["ssn"]["writeOnly"], true);
+    assert!(schema["properties"]["name"].get("writeOnly").is_none());
+
+    let imported = FormSchema::from_json_schema(&schema).unwrap();
+    assert!(imported.get_field("ssn").unwrap().sensitive);
+    assert!(!imported.get_field("name").unwrap().sensitive);
+}
@@ -0,0 +1,216 @@
+//! Tests for showing the errors of server functions on forms
+
+use any_spawner::Executor;
+use futures::channel::mpsc;
+use futures::lock::Mutex;
+use futures::StreamExt;
+use leptos::prelude::*;
+use leptos_forms_rs::submission::{finish_server_submission, ServerFormError};
+use leptos_forms_rs::{
+    use_server_action_form, Form, FormHandle, ServerActionForm, ValidationErrors,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Signup {
+    #[form(required)]
+    name: String,
+    #[form(email)]
+    email: String,
+}
+
+fn signup() -> Signup {
+    Signup {
+        name: "Ada".to_string(),
+        email: "ada@example.com".to_string(),
+    }
+}
+
+fn taken_email() -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    errors.add_field_error("email", "Email is already registered".to_string());
+    errors.add_form_error("Please check the form".to_string());
+    errors
+}
+
+type Answer = Result<(), ValidationErrors>;
+
+/// A form submitted through an action answering with what the test sends
+fn server_form(
+    form: &FormHandle<Signup>,
+) -> (
+    ServerActionForm<Signup, Signup, (), ValidationErrors>,
+    mpsc::UnboundedSender<Answer>,
+) {
+    let _ = Executor::init_futures_executor();
+    let (answer, answers) = mpsc::unbounded();
+    let answers = Arc::new(Mutex::new(answers));
+    let action = Action::new(move |_: &Signup| {
+        let answers = answers.clone();
+        async move { answers.lock().await.next().await.unwrap_or(Ok(())) }
+    });
+    let server_form = use_server_action_form(form, action, |values| values);
+    settle(|| true);
+    (server_form, answer)
+}
+
+/// Run the spawned tasks until `done` holds
+fn settle(done: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        Executor::poll_local();
+        if done() {
+            return;
+        }
+        assert!(Instant::now() < deadline, "the action did not settle");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn test_form_is_submitting_while_the_action_is_pending() {
+    let owner = Owner::new();
+    owner.set();
+    let form = FormHandle::new(signup());
+    let (server_form, answer) = server_form(&form);
+
+    server_form.submit.run(());
+    settle(|| server_form.pending.get_untracked());
+    assert!(form.is_submitting().get_untracked());
+    assert_eq!(server_form.optimistic.get_untracked(), Some(signup()));
+
+    answer.unbounded_send(Ok(())).unwrap();
+    settle(|| !form.is_submitting().get_untracked());
+    assert!(!server_form.pending.get_untracked());
+    assert_eq!(server_form.optimistic.get_untracked(), None);
+    assert!(form.errors().get_untracked().is_empty());
+}
+
+#[test]
+fn test_server_errors_are_shown_once_the_action_answers() {
+    let owner = Owner::new();
+    owner.set();
+    let form = FormHandle::new(signup());
+    let (server_form, answer) = server_form(&form);
+
+    server_form.submit.run(());
+    settle(|| server_form.pending.get_untracked());
+    answer.unbounded_send(Err(taken_email())).unwrap();
+    settle(|| !form.is_submitting().get_untracked());
+    assert!(form.errors().get_untracked().has_field_error("email"));
+    assert_eq!(server_form.optimistic.get_untracked(), None);
+}
+
+#[test]
+fn test_aborted_calls_do_not_show_the_previous_answer() {
+    let owner = Owner::new();
+    owner.set();
+    let form = FormHandle::new(signup());
+    let (server_form, answer) = server_form(&form);
+
+    server_form.submit.run(());
+    settle(|| server_form.pending.get_untracked());
+    answer.unbounded_send(Err(taken_email())).unwrap();
+    settle(|| !form.is_submitting().get_untracked());
+
+    // A call aborted before it answered leaves the action's value as it was
+    form.submit().unwrap();
+    let call = server_form.action.dispatch(signup());
+    settle(|| server_form.pending.get_untracked());
+    call.abort();
+    settle(|| !form.is_submitting().get_untracked());
+    assert!(!server_form.pending.get_untracked());
+    assert!(form.errors().get_untracked().is_empty());
+}
+
+#[test]
+fn test_validation_errors_round_trip_as_server_fn_error_type() {
+    let errors = ValidationErrors::de(taken_email().ser());
+    assert_eq!(errors, taken_email());
+    assert_eq!(errors.validation_errors(), taken_email());
+}
+
+#[test]
+fn test_call_failures_become_form_errors() {
+    let errors = ValidationErrors::from_server_fn_error(ServerFnErrorErr::Request(
+        "connection refused".to_string(),
+    ));
+    assert!(errors.field_errors.is_empty());
+    assert_eq!(errors.form_errors.len(), 1);
+    assert!(errors.form_errors[0].contains("connection refused"));
+}
+
+#[test]
+fn test_server_fn_error_carries_field_errors() {
+    let error = taken_email().into_server_fn_error();
+    assert!(matches!(error, ServerFnError::ServerError(_)));
+    assert_eq!(error.validation_errors(), taken_email());
+}
+
+#[test]
+fn test_problem_payloads_are_mapped_to_fields() {
+    let payload = json!({
+        "title": "The submission is not valid",
+        "errors": {"contacts.0.email": ["Invalid email"]},
+    });
+    let error: ServerFnError = ServerFnError::ServerError(payload.to_string());
+    let errors = error.validation_errors();
+    assert_eq!(
+        errors.get_field_error("contacts[0].email"),
+        Some(&vec!["Invalid email".to_string()])
+    );
+}
+
+#[test]
+fn test_other_server_fn_errors_are_form_errors() {
+    let error: ServerFnError = ServerFnError::ServerError("Database unavailable".to_string());
+    assert_eq!(
+        error.validation_errors().form_errors,
+        vec!["Database unavailable".to_string()]
+    );
+
+    let error: ServerFnError = ServerFnError::Request("offline".to_string());
+    let errors = error.validation_errors();
+    assert!(errors.field_errors.is_empty());
+    assert_eq!(errors.form_errors, vec![error.to_string()]);
+}
+
+#[test]
+fn test_failed_submission_shows_server_errors() {
+    let form = FormHandle::new(signup());
+    form.submit().unwrap();
+    assert!(form.is_submitting().get_untracked());
+
+    finish_server_submission(&form, &Err::<(), _>(taken_email()));
+    assert!(!form.is_submitting().get_untracked());
+    assert!(form.errors().get_untracked().has_field_error("email"));
+    assert_eq!(form.values().get_untracked(), signup());
+}
+
+#[test]
+fn test_successful_submission_finishes() {
+    let form = FormHandle::new(signup());
+    form.submit().unwrap();
+
+    finish_server_submission::<_, _, ServerFnError>(&form, &Ok(()));
+    assert!(!form.is_submitting().get_untracked());
+    assert!(form.errors().get_untracked().is_empty());
+}
+
+#[test]
+fn test_only_successful_submissions_run_success_listeners() {
+    let form = FormHandle::new(signup());
+    let succeeded = RwSignal::new(0);
+    form.on_submit_success(move |_| succeeded.update(|count| *count += 1));
+
+    form.submit().unwrap();
+    finish_server_submission(&form, &Err::<(), _>(taken_email()));
+    assert_eq!(succeeded.get_untracked(), 0);
+
+    form.submit().unwrap();
+    finish_server_submission::<_, _, ServerFnError>(&form, &Ok(()));
+    assert_eq!(succeeded.get_untracked(), 1);
+}
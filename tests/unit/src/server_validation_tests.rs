@@ -0,0 +1,142 @@
+//! Tests for decoding and validating submissions on the server
+
+use leptos_forms_rs::submission::{
+    validate_json, validate_submission, validate_urlencoded, MAX_FORM_PARTS, MAX_NESTING_DEPTH,
+};
+use leptos_forms_rs::Form;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Contact {
+    #[form(required)]
+    name: String,
+    #[form(required, email)]
+    email: String,
+    age: Option<u32>,
+    subscribe: bool,
+    #[form(hidden)]
+    source: String,
+}
+
+const URLENCODED: &str = "application/x-www-form-urlencoded";
+
+#[test]
+fn test_valid_urlencoded_submission_is_decoded() {
+    let contact: Contact = validate_submission(
+        Some(URLENCODED),
+        b"name=Ada&email=ada%40example.com&age=36&subscribe=on",
+    )
+    .unwrap();
+    assert_eq!(
+        contact,
+        Contact {
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            age: Some(36),
+            subscribe: true,
+            source: String::new(),
+        }
+    );
+}
+
+#[test]
+fn test_valid_json_submission_is_decoded() {
+    let body =
+        json!({"name": "Grace", "email": "grace@example.com", "age": null, "subscribe": false});
+    let contact: Contact = validate_submission(
+        Some("application/json; charset=utf-8"),
+        body.to_string().as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(contact.name, "Grace");
+    assert_eq!(contact.age, None);
+    // Hidden fields left out of the payload keep their defaults
+    assert_eq!(contact.source, "");
+}
+
+#[test]
+fn test_form_validation_rules_run_on_the_server() {
+    let errors = validate_urlencoded::<Contact>("name=&email=not-an-email").unwrap_err();
+    assert!(errors.has_field_error("name"));
+    assert!(errors.has_field_error("email"));
+
+    let errors = validate_json::<Contact>(br#"{"name": "Ada", "email": ""}"#).unwrap_err();
+    assert!(!errors.has_field_error("name"));
+    assert!(errors.has_field_error("email"));
+}
+
+#[test]
+fn test_undecodable_values_are_field_errors() {
+    let errors =
+        validate_urlencoded::<Contact>("name=Ada&email=ada%40example.com&age=old").unwrap_err();
+    assert_eq!(
+        errors.get_field_error("age"),
+        Some(&vec!["Invalid integer: old".to_string()])
+    );
+
+    let errors = validate_json::<Contact>(
+        br#"{"name": "Ada", "email": "ada@example.com", "subscribe": [1]}"#,
+    )
+    .unwrap_err();
+    assert!(errors.has_field_error("subscribe"));
+}
+
+#[test]
+fn test_unreadable_bodies_are_form_errors() {
+    for (content_type, body) in [
+        (Some("application/json"), &b"{not json"[..]),
+        (Some("application/json"), &b"[1, 2]"[..]),
+        (Some("text/plain"), &b"name=Ada"[..]),
+        (None, &b"name=Ada"[..]),
+        (Some("multipart/form-data"), &b""[..]),
+    ] {
+        let errors = validate_submission::<Contact>(content_type, body).unwrap_err();
+        assert!(errors.field_errors.is_empty(), "{:?}", content_type);
+        assert_eq!(errors.form_errors.len(), 1, "{:?}", content_type);
+    }
+}
+
+#[test]
+fn test_bodies_with_many_fields_decode_quickly() {
+    let extra: String = (0..MAX_FORM_PARTS - 3)
+        .map(|index| format!("&junk{}=x", index))
+        .collect();
+    let body = format!("name=Ada&email=ada%40example.com{}", extra);
+
+    let started = std::time::Instant::now();
+    let contact = validate_urlencoded::<Contact>(&body).unwrap();
+    assert_eq!(contact.name, "Ada");
+    assert!(started.elapsed() < std::time::Duration::from_secs(1));
+}
+
+#[test]
+fn test_oversized_bodies_are_refused() {
+    let too_many: String = (0..=MAX_FORM_PARTS)
+        .map(|index| format!("f{}=x&", index))
+        .collect();
+    let errors = validate_urlencoded::<Contact>(&too_many).unwrap_err();
+    assert!(errors.form_errors[0].contains("more than"));
+
+    let too_deep = format!("name{}=x", "[a]".repeat(MAX_NESTING_DEPTH));
+    let errors = validate_urlencoded::<Contact>(&too_deep).unwrap_err();
+    assert!(errors.form_errors[0].contains("nested"));
+}
+
+#[test]
+fn test_multipart_submission_is_validated() {
+    let body = concat!(
+        "--XYZ\r\n",
+        "Content-Disposition: form-data; name=\"name\"\r\n\r\n",
+        "Ada\r\n",
+        "--XYZ\r\n",
+        "Content-Disposition: form-data; name=\"email\"\r\n\r\n",
+        "ada\r\n",
+        "--XYZ--\r\n",
+    );
+    let errors =
+        validate_submission::<Contact>(Some("multipart/form-data; boundary=XYZ"), body.as_bytes())
+            .unwrap_err();
+    assert!(!errors.has_field_error("name"));
+    assert!(errors.has_field_error("email"));
+}
@@ -116,6 +116,7 @@ impl Form for LargeStressForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::MinLength(2)],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -126,6 +127,7 @@ impl Form for LargeStressForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Required, Validator::Email],
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -140,6 +142,7 @@ impl Form for LargeStressForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: vec![Validator::Min(0.0), Validator::Max(120.0)],
+            ..Default::default()
         });
 
         // Add many text fields
@@ -152,6 +155,7 @@ impl Form for LargeStressForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::MaxLength(100)],
+                ..Default::default()
             });
         }
 
@@ -169,6 +173,7 @@ impl Form for LargeStressForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: vec![Validator::Min(-1000.0), Validator::Max(1000.0)],
+                ..Default::default()
             });
         }
 
@@ -182,6 +187,7 @@ impl Form for LargeStressForm {
                 dependencies: Vec::new(),
                 attributes: HashMap::new(),
                 validators: Vec::new(),
+                ..Default::default()
             });
         }
 
@@ -194,6 +200,7 @@ impl Form for LargeStressForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: Vec::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -204,6 +211,7 @@ impl Form for LargeStressForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: Vec::new(),
+            ..Default::default()
         });
 
         metadata.push(FieldMetadata {
@@ -214,6 +222,7 @@ impl Form for LargeStressForm {
             dependencies: Vec::new(),
             attributes: HashMap::new(),
             validators: Vec::new(),
+            ..Default::default()
         });
 
         metadata
@@ -0,0 +1,297 @@
+//! Tests for CSRF tokens and the checks that keep bots from submitting forms
+
+use chrono::{Duration as TimeDelta, Utc};
+use futures::executor::block_on;
+use leptos::prelude::*;
+use leptos_forms_rs::submission::{
+    csrf_tokens_match, parse_cookie, read_protected_submission, BotProtection, CsrfProtection,
+    HttpClient, HttpFuture, HttpRequest, HttpResponse, HttpSubmitter, Submission,
+    SubmissionProtection, SubmissionRejected, CSRF_HEADER, RENDERED_AT_FIELD,
+};
+use leptos_forms_rs::{Form, FormHandle};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Contact {
+    #[form(required)]
+    name: String,
+    #[form(required)]
+    message: String,
+}
+
+fn contact() -> Contact {
+    Contact {
+        name: "Ada".to_string(),
+        message: "Hello".to_string(),
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingClient {
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl HttpClient for RecordingClient {
+    fn send(&self, request: HttpRequest) -> HttpFuture {
+        self.requests.lock().unwrap().push(request);
+        Box::pin(std::future::ready(Ok(HttpResponse::new(200, "{}"))))
+    }
+}
+
+const SIGNING_KEY: &str = "server secret";
+
+fn bots() -> BotProtection {
+    BotProtection::new().signing_key(SIGNING_KEY)
+}
+
+/// An urlencoded body carrying `stamp` and the given extra fields
+fn posted_with_stamp(stamp: &str, extra: &str) -> String {
+    format!(
+        "name=Ada&message=Hello&{}={}{}",
+        RENDERED_AT_FIELD, stamp, extra
+    )
+}
+
+/// An urlencoded body rendered `seconds_ago` by the server
+fn posted(seconds_ago: i64, extra: &str) -> String {
+    let stamp = bots().render_stamp(Utc::now() - TimeDelta::seconds(seconds_ago));
+    posted_with_stamp(&stamp, extra)
+}
+
+fn read(
+    body: &str,
+    protection: &SubmissionProtection,
+) -> Result<Submission<Contact>, SubmissionRejected> {
+    read_protected_submission(
+        Some("application/x-www-form-urlencoded"),
+        body.as_bytes(),
+        protection,
+    )
+}
+
+#[test]
+fn test_callback_source_supplies_the_current_token() {
+    let token = Arc::new(Mutex::new(Some("first".to_string())));
+    let source = token.clone();
+    let csrf = CsrfProtection::callback(move || source.lock().unwrap().clone());
+    assert_eq!(csrf.token().as_deref(), Some("first"));
+    assert_eq!(csrf.field_name(), "csrf_token");
+    assert_eq!(csrf.header_name(), CSRF_HEADER);
+
+    *token.lock().unwrap() = Some(String::new());
+    assert_eq!(csrf.token(), None);
+}
+
+#[test]
+fn test_server_renders_with_the_server_token() {
+    // Meta tags cannot be read outside the browser
+    let meta = CsrfProtection::meta("csrf-token");
+    assert!(!meta.is_readable());
+    assert_eq!(meta.token(), None);
+
+    let csrf = meta.server_token(|| Some("from-session".to_string()));
+    assert!(csrf.is_readable());
+    assert_eq!(csrf.token().as_deref(), Some("from-session"));
+}
+
+#[test]
+fn test_cookies_are_found_by_name() {
+    let cookies = "theme=dark; XSRF-TOKEN=abc123; session=\"s1\"";
+    assert_eq!(
+        parse_cookie(cookies, "XSRF-TOKEN").as_deref(),
+        Some("abc123")
+    );
+    assert_eq!(parse_cookie(cookies, "session").as_deref(), Some("s1"));
+    assert_eq!(parse_cookie(cookies, "TOKEN"), None);
+}
+
+#[test]
+fn test_csrf_tokens_must_match_exactly() {
+    assert!(csrf_tokens_match("abc123", "abc123"));
+    assert!(!csrf_tokens_match("abc123", "abc124"));
+    assert!(!csrf_tokens_match("abc123", "abc12"));
+}
+
+#[test]
+fn test_bot_checks_catch_honeypots_and_hasty_submissions() {
+    let bots = BotProtection::new();
+    let now = Utc::now();
+    let rendered_at = Some(now - TimeDelta::seconds(10));
+
+    assert_eq!(bots.check(Some(""), rendered_at, now), Ok(()));
+    assert_eq!(
+        bots.check(Some("http://spam.example"), rendered_at, now),
+        Err(SubmissionRejected::Honeypot)
+    );
+    assert_eq!(
+        bots.check(None, Some(now - TimeDelta::seconds(1)), now),
+        Err(SubmissionRejected::TooFast)
+    );
+    assert_eq!(
+        bots.check(None, None, now),
+        Err(SubmissionRejected::MissingRenderTime)
+    );
+    assert_eq!(
+        bots.check(None, Some(now - TimeDelta::days(2)), now),
+        Err(SubmissionRejected::ExpiredRenderTime)
+    );
+    assert!(SubmissionRejected::TooFast.is_bot());
+    assert!(!SubmissionRejected::InvalidCsrfToken.is_bot());
+    assert!(!SubmissionRejected::ExpiredRenderTime.is_bot());
+
+    let lenient = BotProtection::new().without_min_time_to_submit();
+    assert_eq!(lenient.check(None, None, now), Ok(()));
+    let ageless = BotProtection::new().without_max_render_age();
+    assert_eq!(
+        ageless.check(None, Some(now - TimeDelta::days(2)), now),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_protected_submission_is_read_without_its_protection_fields() {
+    let protection = SubmissionProtection::new()
+        .csrf_token("abc123")
+        .bots(bots());
+    let submission = read(&posted(10, "&csrf_token=abc123&website="), &protection).unwrap();
+    assert!(submission.is_valid());
+    assert_eq!(submission.values, contact());
+}
+
+#[test]
+fn test_server_rejects_bots() {
+    let protection = SubmissionProtection::new().bots(bots().honeypot("fax"));
+    assert_eq!(
+        read(&posted(10, "&fax=555"), &protection).unwrap_err(),
+        SubmissionRejected::Honeypot
+    );
+    assert_eq!(
+        read(&posted(0, ""), &protection).unwrap_err(),
+        SubmissionRejected::TooFast
+    );
+    assert_eq!(
+        read("name=Ada&message=Hello", &protection).unwrap_err(),
+        SubmissionRejected::MissingRenderTime
+    );
+}
+
+#[test]
+fn test_server_rejects_forged_render_times() {
+    let protection = SubmissionProtection::new().bots(bots());
+    let long_ago = Utc::now() - TimeDelta::hours(1);
+
+    // An old render time the server never signed
+    let unsigned = BotProtection::new().render_stamp(long_ago);
+    assert_eq!(
+        read(&posted_with_stamp(&unsigned, ""), &protection).unwrap_err(),
+        SubmissionRejected::InvalidRenderTime
+    );
+
+    // One signed with another key, or a signed stamp with its time changed
+    let other_key = BotProtection::new()
+        .signing_key("guessed")
+        .render_stamp(long_ago);
+    assert_eq!(
+        read(&posted_with_stamp(&other_key, ""), &protection).unwrap_err(),
+        SubmissionRejected::InvalidRenderTime
+    );
+    let recent = bots().render_stamp(Utc::now());
+    let signature = recent.split_once('~').unwrap().1;
+    let tampered = format!("{}~{}", long_ago.to_rfc3339(), signature);
+    assert_eq!(
+        read(&posted_with_stamp(&tampered, ""), &protection).unwrap_err(),
+        SubmissionRejected::InvalidRenderTime
+    );
+}
+
+#[test]
+fn test_server_rejects_expired_render_times() {
+    let protection =
+        SubmissionProtection::new().bots(bots().max_render_age(Duration::from_secs(60)));
+    assert!(read(&posted(10, ""), &protection).is_ok());
+    assert_eq!(
+        read(&posted(120, ""), &protection).unwrap_err(),
+        SubmissionRejected::ExpiredRenderTime
+    );
+}
+
+#[test]
+fn test_server_without_signing_key_checks_the_honeypot_only() {
+    // Render times it did not sign prove nothing, so none are checked
+    let protection = SubmissionProtection::new().bots(BotProtection::new());
+    let stamp = BotProtection::new().render_stamp(Utc::now());
+    assert!(read(&posted_with_stamp(&stamp, ""), &protection).is_ok());
+    assert!(read("name=Ada&message=Hello", &protection).is_ok());
+    assert_eq!(
+        read("name=Ada&message=Hello&website=spam", &protection).unwrap_err(),
+        SubmissionRejected::Honeypot
+    );
+}
+
+#[test]
+fn test_server_rejects_missing_and_forged_csrf_tokens() {
+    let protection = SubmissionProtection::new().csrf_token("abc123");
+    assert_eq!(
+        read("name=Ada&message=Hello", &protection).unwrap_err(),
+        SubmissionRejected::MissingCsrfToken
+    );
+    assert_eq!(
+        read("name=Ada&message=Hello&csrf_token=forged", &protection).unwrap_err(),
+        SubmissionRejected::InvalidCsrfToken
+    );
+
+    // Submissions sent with JavaScript carry the token in a header
+    let from_header = protection.csrf_header_value(Some("abc123"));
+    assert!(read("name=Ada&message=Hello", &from_header).is_ok());
+}
+
+#[test]
+fn test_submitter_sends_csrf_header_and_bot_fields() {
+    let client = RecordingClient::default();
+    let submitter = HttpSubmitter::post("/api/contact")
+        .with_client(client.clone())
+        .csrf(CsrfProtection::callback(|| Some("abc123".to_string())))
+        .bot_protection(BotProtection::new().min_time_to_submit(Duration::ZERO));
+    let form = FormHandle::new(contact());
+    // What the server does while rendering the form
+    let server_bots = bots().min_time_to_submit(Duration::ZERO);
+    form.share_render_stamp(&server_bots);
+
+    block_on(submitter.submit(&form)).unwrap();
+    let requests = client.requests.lock().unwrap();
+    assert_eq!(requests[0].header(CSRF_HEADER), Some("abc123"));
+
+    let body: Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(body["website"], "");
+    assert!(body[RENDERED_AT_FIELD].is_string());
+
+    // The server accepts what the submitter sent
+    let protection = SubmissionProtection::new()
+        .csrf_token("abc123")
+        .csrf_header_value(requests[0].header(CSRF_HEADER))
+        .bots(server_bots);
+    let submission = read_protected_submission::<Contact>(
+        Some("application/json"),
+        requests[0].body.as_bytes(),
+        &protection,
+    )
+    .unwrap();
+    assert_eq!(submission.values, contact());
+}
+
+#[test]
+fn test_submitter_refuses_forms_submitted_too_soon() {
+    let client = RecordingClient::default();
+    let submitter = HttpSubmitter::post("/api/contact")
+        .with_client(client.clone())
+        .bot_protection(BotProtection::new().min_time_to_submit(Duration::from_secs(60)));
+    let form = FormHandle::new(contact());
+
+    let error = block_on(submitter.submit(&form)).unwrap_err();
+    assert!(error.is_submission_error());
+    assert!(client.requests.lock().unwrap().is_empty());
+    assert!(!form.is_submitting().get_untracked());
+}
@@ -0,0 +1,397 @@
+//! Tests for queueing submissions made offline and retrying them
+
+use futures::executor::block_on;
+use leptos::prelude::*;
+use leptos_forms_rs::core::{FieldEncryptor, MemoryPersistence};
+use leptos_forms_rs::submission::{
+    HttpClient, HttpFuture, HttpRequest, HttpResponse, HttpSubmitter, MemoryNetworkStatus,
+    NetworkStatus, QueueOutcome, QueuedStatus, QueuedSubmission, RetryPolicy, SubmissionQueue,
+};
+use leptos_forms_rs::{Form, FormError, FormHandle};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Inspection {
+    #[form(required)]
+    site: String,
+    passed: bool,
+}
+
+fn inspection() -> Inspection {
+    Inspection {
+        site: "Pump station 4".to_string(),
+        passed: true,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Form)]
+struct Payment {
+    holder: String,
+    #[form(sensitive)]
+    card_number: String,
+}
+
+fn payment() -> Payment {
+    Payment {
+        holder: "Ada".to_string(),
+        card_number: "4111111111111111".to_string(),
+    }
+}
+
+/// Reverses the text, which is enough to tell encrypted values apart
+struct Reverse;
+
+impl FieldEncryptor for Reverse {
+    fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        Ok(format!(
+            "enc:{}",
+            plaintext.chars().rev().collect::<String>()
+        ))
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> Result<String, String> {
+        let reversed = ciphertext.strip_prefix("enc:").ok_or("not encrypted")?;
+        Ok(reversed.chars().rev().collect())
+    }
+}
+
+/// Answers requests with the given results in turn, then with 200
+#[derive(Clone, Default)]
+struct ScriptedClient {
+    results: Arc<Mutex<VecDeque<Result<HttpResponse, FormError>>>>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl ScriptedClient {
+    fn new(results: Vec<Result<HttpResponse, FormError>>) -> Self {
+        Self {
+            results: Arc::new(Mutex::new(results.into())),
+            requests: Arc::default(),
+        }
+    }
+
+    fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpClient for ScriptedClient {
+    fn send(&self, request: HttpRequest) -> HttpFuture {
+        self.requests.lock().unwrap().push(request);
+        let result = self
+            .results
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Ok(HttpResponse::new(200, "{}")));
+        Box::pin(std::future::ready(result))
+    }
+}
+
+fn unreachable() -> Result<HttpResponse, FormError> {
+    Err(FormError::submission_error("Failed to fetch", None, None))
+}
+
+/// Retries fall due right away, so tests need not wait
+fn immediate_retry(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        base_delay: Duration::ZERO,
+        max_attempts,
+        ..RetryPolicy::default()
+    }
+}
+
+fn queue(
+    client: &ScriptedClient,
+    persistence: &MemoryPersistence,
+    network: &MemoryNetworkStatus,
+) -> SubmissionQueue {
+    let submitter = HttpSubmitter::post("/api/inspections").with_client(client.clone());
+    SubmissionQueue::memory(submitter, persistence, "inspection-queue")
+        .with_network(network.clone())
+        .with_retry(immediate_retry(3))
+}
+
+fn keys_of(items: &[QueuedSubmission]) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| item.idempotency_key.clone())
+        .collect()
+}
+
+#[test]
+fn test_backoff_grows_exponentially_up_to_the_limit() {
+    let policy = RetryPolicy {
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(10),
+        max_attempts: 10,
+        jitter: 0.0,
+    };
+    assert_eq!(policy.delay(1, 0.7), Duration::from_secs(1));
+    assert_eq!(policy.delay(2, 0.7), Duration::from_secs(2));
+    assert_eq!(policy.delay(4, 0.7), Duration::from_secs(8));
+    assert_eq!(policy.delay(5, 0.7), Duration::from_secs(10));
+    assert_eq!(policy.delay(60, 0.7), Duration::from_secs(10));
+}
+
+#[test]
+fn test_jitter_shortens_the_wait_within_its_fraction() {
+    let policy = RetryPolicy {
+        base_delay: Duration::from_secs(8),
+        jitter: 0.5,
+        ..RetryPolicy::default()
+    };
+    assert_eq!(policy.delay(1, 0.0), Duration::from_secs(8));
+    assert_eq!(policy.delay(1, 0.5), Duration::from_secs(6));
+    assert_eq!(policy.delay(1, 1.0), Duration::from_secs(4));
+}
+
+#[test]
+fn test_online_submission_is_sent_with_an_idempotency_key() {
+    let client = ScriptedClient::new(vec![Ok(HttpResponse::new(201, "{}"))]);
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let queue = queue(&client, &persistence, &MemoryNetworkStatus::new(true));
+    let form = FormHandle::new(inspection());
+
+    let outcome = block_on(queue.submit(&form)).unwrap();
+    assert!(matches!(outcome, QueueOutcome::Sent(response) if response.status == 201));
+    assert!(queue.items().get_untracked().is_empty());
+    assert!(!form.is_submitting().get_untracked());
+
+    let requests = client.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].header("Idempotency-Key").is_some());
+    let body: Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(body, json!({"site": "Pump station 4", "passed": true}));
+}
+
+#[test]
+fn test_offline_submission_is_queued_and_persisted() {
+    let client = ScriptedClient::default();
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let network = MemoryNetworkStatus::new(false);
+    let queue = queue(&client, &persistence, &network);
+
+    let form = FormHandle::new(inspection());
+    let succeeded = Arc::new(Mutex::new(false));
+    let flag = succeeded.clone();
+    form.on_submit_success(move |_| *flag.lock().unwrap() = true);
+
+    let outcome = block_on(queue.submit(&form)).unwrap();
+    let QueueOutcome::Queued(key) = outcome else {
+        panic!("expected the submission to be queued");
+    };
+    // It may still fail, so it does not count as a success
+    assert!(!*succeeded.lock().unwrap());
+    assert!(client.requests().is_empty());
+    assert_eq!(keys_of(&queue.pending().get_untracked()), [key.as_str()]);
+    assert!(persistence.raw().unwrap().contains(&key));
+
+    // A queue opened later, e.g. after a reload, finds it again
+    let reopened = self::queue(&client, &persistence, &network);
+    assert_eq!(
+        reopened.items().get_untracked(),
+        queue.items().get_untracked()
+    );
+}
+
+#[test]
+fn test_flush_waits_for_connectivity() {
+    let client = ScriptedClient::default();
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let network = MemoryNetworkStatus::new(false);
+    let queue = queue(&client, &persistence, &network);
+    block_on(queue.send(&inspection())).unwrap();
+
+    assert_eq!(block_on(queue.flush()), 0);
+    assert!(client.requests().is_empty());
+
+    network.set_online(true);
+    assert_eq!(block_on(queue.flush()), 1);
+    assert!(queue.items().get_untracked().is_empty());
+    assert_eq!(persistence.raw(), None);
+}
+
+#[test]
+fn test_network_listeners_hear_about_changes() {
+    let network = MemoryNetworkStatus::new(false);
+    let heard = Arc::new(Mutex::new(Vec::new()));
+    let log = heard.clone();
+    network
+        .subscribe(Rc::new(move |online| log.lock().unwrap().push(online)))
+        .unwrap();
+
+    network.set_online(true);
+    network.set_online(true);
+    network.set_online(false);
+    assert_eq!(*heard.lock().unwrap(), [true, false]);
+}
+
+#[test]
+fn test_failed_attempts_are_retried_with_the_same_key() {
+    let client = ScriptedClient::new(vec![
+        unreachable(),
+        Ok(HttpResponse::new(503, "")),
+        Ok(HttpResponse::new(201, "{}")),
+    ]);
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let queue = queue(&client, &persistence, &MemoryNetworkStatus::new(true));
+
+    let QueueOutcome::Queued(key) = block_on(queue.send(&inspection())).unwrap() else {
+        panic!("expected the submission to be queued");
+    };
+    let item = &queue.items().get_untracked()[0];
+    assert_eq!(item.attempts, 1);
+    assert_eq!(item.last_error.as_deref(), Some("Failed to fetch"));
+
+    assert_eq!(block_on(queue.flush()), 0);
+    assert_eq!(queue.items().get_untracked()[0].attempts, 2);
+    assert_eq!(block_on(queue.flush()), 1);
+    assert!(queue.items().get_untracked().is_empty());
+
+    let keys: Vec<_> = client
+        .requests()
+        .iter()
+        .map(|request| request.header("Idempotency-Key").unwrap().to_string())
+        .collect();
+    assert_eq!(keys, [key.clone(), key.clone(), key]);
+}
+
+#[test]
+fn test_retries_wait_for_the_backoff() {
+    let client = ScriptedClient::new(vec![unreachable()]);
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let queue =
+        queue(&client, &persistence, &MemoryNetworkStatus::new(true)).with_retry(RetryPolicy {
+            base_delay: Duration::from_secs(60),
+            ..RetryPolicy::default()
+        });
+
+    block_on(queue.send(&inspection())).unwrap();
+    let item = &queue.items().get_untracked()[0];
+    assert!(item.next_attempt_at > item.queued_at + chrono::Duration::seconds(29));
+
+    assert_eq!(block_on(queue.flush()), 0);
+    assert_eq!(client.requests().len(), 1);
+}
+
+#[test]
+fn test_rejected_submission_shows_errors_and_is_not_queued() {
+    let body = r#"{"errors": {"site": ["Unknown site"]}}"#;
+    let client = ScriptedClient::new(vec![Ok(HttpResponse::new(422, body))]);
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let queue = queue(&client, &persistence, &MemoryNetworkStatus::new(true));
+    let form = FormHandle::new(inspection());
+
+    let error = block_on(queue.submit(&form)).unwrap_err();
+    assert!(error.is_submission_error());
+    assert!(form.errors().get_untracked().has_field_error("site"));
+    assert!(queue.items().get_untracked().is_empty());
+}
+
+#[test]
+fn test_submissions_out_of_attempts_fail_until_retried() {
+    let client = ScriptedClient::new(vec![
+        unreachable(),
+        unreachable(),
+        Ok(HttpResponse::new(400, "")),
+    ]);
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let queue = queue(&client, &persistence, &MemoryNetworkStatus::new(true))
+        .with_retry(immediate_retry(2));
+
+    let QueueOutcome::Queued(key) = block_on(queue.send(&inspection())).unwrap() else {
+        panic!("expected the submission to be queued");
+    };
+    block_on(queue.flush());
+    assert!(queue.pending().get_untracked().is_empty());
+    assert_eq!(keys_of(&queue.failed().get_untracked()), [key.as_str()]);
+
+    // Failed submissions are left alone by flushes
+    assert_eq!(block_on(queue.flush()), 0);
+    assert_eq!(client.requests().len(), 2);
+
+    assert!(queue.retry(&key));
+    assert_eq!(
+        queue.items().get_untracked()[0].status,
+        QueuedStatus::Pending
+    );
+    block_on(queue.flush());
+    // A client error fails the submission right away
+    let item = &queue.items().get_untracked()[0];
+    assert_eq!(item.status, QueuedStatus::Failed);
+    assert_eq!(item.attempts, 1);
+
+    assert!(queue.remove(&key));
+    assert!(!queue.remove(&key));
+    assert!(queue.items().get_untracked().is_empty());
+}
+
+#[test]
+fn test_sensitive_values_are_not_stored_in_plain_text() {
+    let client = ScriptedClient::default();
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let network = MemoryNetworkStatus::new(false);
+    let queue = queue(&client, &persistence, &network);
+
+    let form = FormHandle::new(payment());
+    let QueueOutcome::Queued(key) = block_on(queue.submit(&form)).unwrap() else {
+        panic!("expected the submission to be queued");
+    };
+    let stored = persistence.raw().unwrap();
+    assert!(stored.contains(&key));
+    assert!(!stored.contains("4111111111111111"));
+
+    // This page still has the value to send
+    network.set_online(true);
+    block_on(queue.flush());
+    let body: Value = serde_json::from_str(&client.requests()[0].body).unwrap();
+    assert_eq!(body["card_number"], "4111111111111111");
+}
+
+#[test]
+fn test_reloaded_submissions_without_their_sensitive_values_fail() {
+    let client = ScriptedClient::default();
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let network = MemoryNetworkStatus::new(false);
+    let queue = queue(&client, &persistence, &network);
+    let QueueOutcome::Queued(key) = block_on(queue.submit(&FormHandle::new(payment()))).unwrap()
+    else {
+        panic!("expected the submission to be queued");
+    };
+
+    let reopened = self::queue(&client, &persistence, &network);
+    assert!(reopened.pending().get_untracked().is_empty());
+    let failed = reopened.failed().get_untracked();
+    assert_eq!(keys_of(&failed), [key.as_str()]);
+    assert!(failed[0].last_error.is_some());
+    // Sending it again would send blanks
+    assert!(!reopened.retry(&key));
+}
+
+#[test]
+fn test_encrypted_sensitive_values_survive_a_reload() {
+    let client = ScriptedClient::default();
+    let persistence = MemoryPersistence::new("inspection-queue");
+    let network = MemoryNetworkStatus::new(false);
+    let queue = queue(&client, &persistence, &network).with_encryptor(Reverse);
+    block_on(queue.submit(&FormHandle::new(payment()))).unwrap();
+    let stored = persistence.raw().unwrap();
+    assert!(!stored.contains("4111111111111111"));
+    assert!(stored.contains("enc:"));
+
+    let reopened = self::queue(&client, &persistence, &network).with_encryptor(Reverse);
+    assert_eq!(
+        reopened.items().get_untracked(),
+        queue.items().get_untracked()
+    );
+    network.set_online(true);
+    assert_eq!(block_on(reopened.flush()), 1);
+    let body: Value = serde_json::from_str(&client.requests()[0].body).unwrap();
+    assert_eq!(body["card_number"], "4111111111111111");
+}
@@ -46,6 +46,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "email_field".to_string(),
@@ -55,6 +56,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "url_field".to_string(),
@@ -64,6 +66,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "phone_field".to_string(),
@@ -73,6 +76,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "min_length_field".to_string(),
@@ -82,6 +86,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "max_length_field".to_string(),
@@ -91,6 +96,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "exact_length_field".to_string(),
@@ -100,6 +106,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "min_value".to_string(),
@@ -113,6 +120,7 @@ impl Form for ValidationTestForm {
                 default_value: Some(FieldValue::Number(0.0)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "max_value".to_string(),
@@ -126,6 +134,7 @@ impl Form for ValidationTestForm {
                 default_value: Some(FieldValue::Number(50.0)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "range_value".to_string(),
@@ -139,6 +148,7 @@ impl Form for ValidationTestForm {
                 default_value: Some(FieldValue::Number(50.0)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "regex_field".to_string(),
@@ -148,6 +158,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "alphanumeric_field".to_string(),
@@ -157,6 +168,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "conditional_required".to_string(),
@@ -166,6 +178,7 @@ impl Form for ValidationTestForm {
                 default_value: None,
                 dependencies: vec!["dependency_trigger".to_string()],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
             FieldMetadata {
                 name: "dependency_trigger".to_string(),
@@ -175,6 +188,7 @@ impl Form for ValidationTestForm {
                 default_value: Some(FieldValue::Boolean(false)),
                 dependencies: vec![],
                 attributes: std::collections::HashMap::new(),
+                ..Default::default()
             },
         ]
     }